@@ -9,7 +9,9 @@ use actix_web::body::BodyStream;
 use actix_web::dev::{HttpResponseBuilder, Payload, PayloadStream};
 use actix_web::http::StatusCode;
 use actix_web::{http, web, Error, FromRequest, HttpRequest, HttpResponse, Responder};
-use async_graphql::http::{multipart_stream, StreamBody};
+use async_graphql::http::{
+    multipart_stream_with_format, MultipartStreamFormat, ResponseFormat, StreamBody,
+};
 use async_graphql::{
     IntoQueryBuilder, IntoQueryBuilderOpts, ParseRequestError, QueryBuilder, QueryResponse,
     StreamResponse,
@@ -78,7 +80,9 @@ impl FromRequest for GQLRequest {
                     .into_query_builder_opts(&config)
                     .map_ok(GQLRequest)
                     .map_err(|err| match err {
-                        ParseRequestError::PayloadTooLarge => {
+                        ParseRequestError::PayloadTooLarge
+                        | ParseRequestError::RequestTooLarge
+                        | ParseRequestError::VariableFileTooLarge { .. } => {
                             actix_web::error::ErrorPayloadTooLarge(err)
                         }
                         _ => actix_web::error::ErrorBadRequest(err),
@@ -102,22 +106,38 @@ impl Responder for GQLResponse {
     type Error = Error;
     type Future = Ready<Result<HttpResponse, Error>>;
 
-    fn respond_to(self, _req: &HttpRequest) -> Self::Future {
-        let mut res = HttpResponse::build(StatusCode::OK);
-        res.content_type("application/json");
-        add_cache_control(&mut res, &self.0);
-        let res =
-            res.body(serde_json::to_string(&async_graphql::http::GQLResponse(self.0)).unwrap());
+    fn respond_to(self, req: &HttpRequest) -> Self::Future {
+        let accept = req
+            .headers()
+            .get_all(http::header::ACCEPT)
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(",");
+        let format = ResponseFormat::from_accept(Some(&accept));
+        let gql_resp = async_graphql::http::GQLResponse(self.0);
+        let mut res = HttpResponse::build(
+            StatusCode::from_u16(gql_resp.status_code(format)).unwrap_or(StatusCode::OK),
+        );
+        res.content_type(format.content_type());
+        add_cache_control(&mut res, &gql_resp.0);
+        let res = res.body(serde_json::to_string(&gql_resp).unwrap());
         futures::future::ok(res)
     }
 }
 
 /// Responder for GraphQL response stream
-pub struct GQLResponseStream(StreamResponse);
+pub struct GQLResponseStream(StreamResponse, MultipartStreamFormat);
+
+impl GQLResponseStream {
+    /// Specify the format of the multipart response, the default is `MultipartStreamFormat::Incremental`.
+    pub fn with_format(self, format: MultipartStreamFormat) -> Self {
+        GQLResponseStream(self.0, format)
+    }
+}
 
 impl From<StreamResponse> for GQLResponseStream {
     fn from(resp: StreamResponse) -> Self {
-        GQLResponseStream(resp)
+        GQLResponseStream(resp, Default::default())
     }
 }
 
@@ -129,10 +149,11 @@ impl Responder for GQLResponseStream {
         match self.0 {
             StreamResponse::Single(resp) => GQLResponse(resp).respond_to(req),
             StreamResponse::Stream(stream) => {
-                let body =
-                    BodyStream::new(multipart_stream(stream).map(Result::<_, Infallible>::Ok));
+                let body = BodyStream::new(
+                    multipart_stream_with_format(stream, self.1).map(Result::<_, Infallible>::Ok),
+                );
                 let mut res = HttpResponse::build(StatusCode::OK);
-                res.content_type("multipart/mixed; boundary=\"-\"");
+                res.content_type(self.1.content_type());
                 futures::future::ok(res.body(body))
             }
         }
@@ -1,6 +1,6 @@
 use crate::utils::{
-    get_rustdoc, parse_default, parse_default_with, parse_guards, parse_post_guards,
-    parse_validator,
+    get_rustdoc, parse_default, parse_default_with, parse_duration_millis, parse_guards,
+    parse_post_guards, parse_validator,
 };
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -206,6 +206,7 @@ pub struct Field {
     pub guard: Option<TokenStream>,
     pub post_guard: Option<TokenStream>,
     pub features: Vec<String>,
+    pub middlewares: Vec<syn::Expr>,
 }
 
 impl Field {
@@ -221,6 +222,8 @@ impl Field {
         let mut owned = false;
         let mut guard = None;
         let mut post_guard = None;
+        let mut middlewares = Vec::new();
+        let mut timeout = None;
 
         for attr in attrs {
             match attr.parse_meta()? {
@@ -290,6 +293,33 @@ impl Field {
                                             "Attribute 'requires' should be a string.",
                                         ));
                                     }
+                                } else if nv.path.is_ident("timeout") {
+                                    if let syn::Lit::Str(lit) = &nv.lit {
+                                        let millis = parse_duration_millis(&lit.value())
+                                            .ok_or_else(|| {
+                                                Error::new_spanned(
+                                                    &nv.lit,
+                                                    "Invalid duration, such as \"500ms\", \"3s\" or \"1m\".",
+                                                )
+                                            })?;
+                                        timeout = Some(syn::parse2(quote! {
+                                            #crate_name::middleware::Timeout(::std::time::Duration::from_millis(#millis))
+                                        })?);
+                                    } else {
+                                        return Err(Error::new_spanned(
+                                            &nv.lit,
+                                            "Attribute 'timeout' should be a string.",
+                                        ));
+                                    }
+                                } else if nv.path.is_ident("middleware") {
+                                    if let syn::Lit::Str(lit) = &nv.lit {
+                                        middlewares.push(syn::parse_str(&lit.value())?);
+                                    } else {
+                                        return Err(Error::new_spanned(
+                                            &nv.lit,
+                                            "Attribute 'middleware' should be a string.",
+                                        ));
+                                    }
                                 } else if nv.path.is_ident("feature") {
                                     if let syn::Lit::Str(lit) = &nv.lit {
                                         features = lit
@@ -323,6 +353,11 @@ impl Field {
             desc = get_rustdoc(attrs)?;
         }
 
+        // The timeout wraps the other middlewares of the field.
+        if let Some(timeout) = timeout {
+            middlewares.insert(0, timeout);
+        }
+
         Ok(Some(Self {
             name,
             desc,
@@ -335,6 +370,7 @@ impl Field {
             guard,
             post_guard,
             features,
+            middlewares,
         }))
     }
 }
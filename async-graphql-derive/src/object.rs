@@ -1,6 +1,8 @@
 use crate::args;
 use crate::output_type::OutputType;
-use crate::utils::{feature_block, get_crate_name, get_param_getter_ident, get_rustdoc};
+use crate::utils::{
+    feature_block, get_crate_name, get_param_getter_ident, get_rustdoc, middleware_block,
+};
 use inflector::Inflector;
 use proc_macro::TokenStream;
 use quote::quote;
@@ -177,7 +179,13 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                     )
                     .expect("invalid result type");
                 }
-                let do_find = quote! { self.#field_ident(ctx, #(#use_keys),*).await.map_err(|err| err.into_error(ctx.position()))? };
+                let do_find = quote! {
+                    {
+                        let _permit = ctx.resolver_permit().await;
+                        self.#field_ident(ctx, #(#use_keys),*).await
+                    }
+                    .map_err(|err| err.into_error(ctx.position()))?
+                };
 
                 find_entities.push((
                     args.len(),
@@ -398,7 +406,10 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
 
                 let resolve_obj = quote! {
                     {
-                        let res = self.#field_ident(ctx, #(#use_params),*).await;
+                        let res = {
+                            let _permit = ctx.resolver_permit().await;
+                            self.#field_ident(ctx, #(#use_params),*).await
+                        };
                         res.map_err(|err| err.into_error_with_path(ctx.position(), ctx.path_node.as_ref().unwrap().to_json()))?
                     }
                 };
@@ -416,14 +427,21 @@ pub fn generate(object_args: &args::Object, item_impl: &mut ItemImpl) -> Result<
                             .map_err(|err| err.into_error_with_path(ctx.position(), ctx.path_node.as_ref().unwrap().to_json()))?;
                     });
 
-                resolvers.push(quote! {
-                    if ctx.name.node == #field_name {
+                let block = middleware_block(
+                    &crate_name,
+                    &field.middlewares,
+                    quote! {
                         #(#get_params)*
                         #guard
                         let ctx_obj = ctx.with_selection_set(&ctx.selection_set);
                         let res = #resolve_obj;
                         #post_guard
                         return #crate_name::OutputValueType::resolve(&res, &ctx_obj, ctx.item).await;
+                    },
+                );
+                resolvers.push(quote! {
+                    if ctx.name.node == #field_name {
+                        #block
                     }
                 });
 
@@ -1,5 +1,5 @@
 use crate::args;
-use crate::utils::{feature_block, get_crate_name, get_rustdoc};
+use crate::utils::{feature_block, get_crate_name, get_rustdoc, middleware_block};
 use inflector::Inflector;
 use proc_macro::TokenStream;
 use quote::quote;
@@ -134,13 +134,20 @@ pub fn generate(object_args: &args::Object, input: &DeriveInput) -> Result<Token
                     }
                 });
 
-                resolvers.push(quote! {
-                    if ctx.name.node == #field_name {
+                let block = middleware_block(
+                    &crate_name,
+                    &field.middlewares,
+                    quote! {
                         #guard
                         let res = self.#ident(ctx).await.map_err(|err| err.into_error_with_path(ctx.position(), ctx.path_node.as_ref().unwrap().to_json()))?;
                         let ctx_obj = ctx.with_selection_set(&ctx.selection_set);
                         #post_guard
                         return #crate_name::OutputValueType::resolve(&res, &ctx_obj, ctx.item).await;
+                    },
+                );
+                resolvers.push(quote! {
+                    if ctx.name.node == #field_name {
+                        #block
                     }
                 });
             }
@@ -329,3 +329,44 @@ pub fn feature_block(
         block
     }
 }
+
+pub fn middleware_block(
+    crate_name: &TokenStream,
+    middlewares: &[syn::Expr],
+    block: TokenStream,
+) -> TokenStream {
+    if middlewares.is_empty() {
+        return block;
+    }
+    quote! {
+        let resolve = || {
+            let fut: #crate_name::futures::future::BoxFuture<'_, #crate_name::Result<#crate_name::serde_json::Value>> =
+                Box::pin(async move { #block });
+            fut
+        };
+        static MIDDLEWARES: #crate_name::once_cell::sync::Lazy<
+            ::std::vec::Vec<::std::sync::Arc<dyn #crate_name::middleware::FieldMiddleware>>,
+        > = #crate_name::once_cell::sync::Lazy::new(|| {
+            ::std::vec![#(::std::sync::Arc::new(#middlewares) as ::std::sync::Arc<dyn #crate_name::middleware::FieldMiddleware>),*]
+        });
+        return #crate_name::middleware::NextFieldResolve::new(ctx, &MIDDLEWARES, &resolve)
+            .run()
+            .await;
+    }
+}
+
+/// Parse a duration such as `500ms`, `3s`, `1m` or `1h` to milliseconds.
+pub fn parse_duration_millis(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let idx = s.find(|c: char| !c.is_ascii_digit())?;
+    let (n, unit) = s.split_at(idx);
+    let n = n.parse::<u64>().ok()?;
+    let scale = match unit.trim() {
+        "ms" => 1,
+        "s" => 1000,
+        "m" => 60 * 1000,
+        "h" => 60 * 60 * 1000,
+        _ => return None,
+    };
+    n.checked_mul(scale)
+}
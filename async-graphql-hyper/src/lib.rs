@@ -0,0 +1,260 @@
+//! Async-graphql integration with Hyper
+
+#![warn(missing_docs)]
+#![allow(clippy::type_complexity)]
+#![allow(clippy::needless_doctest_main)]
+#![forbid(unsafe_code)]
+
+use async_graphql::{
+    IntoQueryBuilderOpts, ObjectType, QueryBuilder, Schema, SubscriptionType, WebSocketTransport,
+};
+use bytes::Bytes;
+use futures::future::{self, BoxFuture, Ready};
+use futures::task::{Context, Poll};
+use futures::{select, SinkExt, StreamExt};
+use hyper::header::{HeaderValue, CONNECTION, SEC_WEBSOCKET_PROTOCOL, UPGRADE};
+use hyper::{Body, Request, Response, StatusCode};
+use std::convert::Infallible;
+use tokio_tungstenite::tungstenite::protocol::{Message, Role};
+use tokio_tungstenite::WebSocketStream;
+use tower_service::Service;
+
+/// Create a service factory that can be passed to `hyper::Server::serve`.
+///
+/// # Examples
+/// *[Full Example](<https://github.com/async-graphql/examples/blob/master/hyper/starwars/src/main.rs>)*
+///
+/// ```no_run
+/// use async_graphql::*;
+/// use hyper::Server;
+///
+/// struct QueryRoot;
+///
+/// #[Object]
+/// impl QueryRoot {
+///     #[field(desc = "Returns the sum of a and b")]
+///     async fn add(&self, a: i32, b: i32) -> i32 {
+///         a + b
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+///     Server::bind(&([0, 0, 0, 0], 8000).into())
+///         .serve(async_graphql_hyper::make_service(schema))
+///         .await
+///         .unwrap();
+/// }
+/// ```
+pub fn make_service<Query, Mutation, Subscription>(
+    schema: Schema<Query, Mutation, Subscription>,
+) -> MakeGraphQLService<Query, Mutation, Subscription>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+{
+    make_service_opts(schema, Default::default())
+}
+
+/// Similar to make_service, but you can set the options `IntoQueryBuilderOpts`.
+pub fn make_service_opts<Query, Mutation, Subscription>(
+    schema: Schema<Query, Mutation, Subscription>,
+    opts: IntoQueryBuilderOpts,
+) -> MakeGraphQLService<Query, Mutation, Subscription>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+{
+    MakeGraphQLService(GraphQLService(
+        async_graphql_tower::GraphQLService::new_opts(schema, opts),
+    ))
+}
+
+/// A service factory that creates a `GraphQLService` for every connection
+pub struct MakeGraphQLService<Query, Mutation, Subscription>(
+    GraphQLService<Query, Mutation, Subscription>,
+);
+
+impl<Query, Mutation, Subscription> MakeGraphQLService<Query, Mutation, Subscription>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+{
+    /// Set a function that configures the `QueryBuilder` of every request, such as adding
+    /// per-request data.
+    pub fn query_builder_configuration<F>(self, f: F) -> Self
+    where
+        F: Fn(QueryBuilder) -> QueryBuilder + Send + Sync + 'static,
+    {
+        MakeGraphQLService(GraphQLService((self.0).0.query_builder_configuration(f)))
+    }
+}
+
+impl<'a, Target, Query, Mutation, Subscription> Service<&'a Target>
+    for MakeGraphQLService<Query, Mutation, Subscription>
+{
+    type Response = GraphQLService<Query, Mutation, Subscription>;
+    type Error = Infallible;
+    type Future = Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _target: &'a Target) -> Self::Future {
+        future::ok(self.0.clone())
+    }
+}
+
+/// GraphQL service
+///
+/// Requests that ask for a WebSocket upgrade are served with the `graphql-ws` protocol, all other
+/// requests are handled by `async_graphql_tower::GraphQLService`.
+pub struct GraphQLService<Query, Mutation, Subscription>(
+    async_graphql_tower::GraphQLService<Query, Mutation, Subscription>,
+);
+
+impl<Query, Mutation, Subscription> Clone for GraphQLService<Query, Mutation, Subscription> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<Query, Mutation, Subscription> Service<Request<Body>>
+    for GraphQLService<Query, Mutation, Subscription>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+{
+    type Response = Response<Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        if is_websocket_upgrade(&req) {
+            let schema = self.0.schema().clone();
+            return Box::pin(future::ok(upgrade_websocket(schema, req)));
+        }
+        self.0.call(req)
+    }
+}
+
+fn is_websocket_upgrade(req: &Request<Body>) -> bool {
+    req.headers()
+        .get(UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.eq_ignore_ascii_case("websocket"))
+        .unwrap_or_default()
+}
+
+fn upgrade_websocket<Query, Mutation, Subscription>(
+    schema: Schema<Query, Mutation, Subscription>,
+    req: Request<Body>,
+) -> Response<Body>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+{
+    // Let tungstenite validate the handshake and compute `Sec-WebSocket-Accept`.
+    let mut handshake = Request::get("/").version(req.version());
+    for name in &[
+        CONNECTION.as_str(),
+        UPGRADE.as_str(),
+        "sec-websocket-version",
+        "sec-websocket-key",
+    ] {
+        if let Some(value) = req.headers().get(*name) {
+            handshake = handshake.header(*name, value.clone());
+        }
+    }
+    let handshake_resp = match handshake
+        .body(())
+        .map_err(Into::into)
+        .and_then(|handshake| {
+            tokio_tungstenite::tungstenite::handshake::server::create_response(&handshake)
+        }) {
+        Ok(handshake_resp) => handshake_resp,
+        Err(err) => {
+            let mut resp = Response::new(Body::from(err.to_string()));
+            *resp.status_mut() = StatusCode::BAD_REQUEST;
+            return resp;
+        }
+    };
+
+    let offers_graphql_ws = req
+        .headers()
+        .get_all(SEC_WEBSOCKET_PROTOCOL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|protocol| protocol.trim() == "graphql-ws");
+
+    tokio::spawn(async move {
+        if let Ok(upgraded) = req.into_body().on_upgrade().await {
+            let websocket = WebSocketStream::from_raw_socket(upgraded, Role::Server, None).await;
+            serve_websocket(websocket, schema).await;
+        }
+    });
+
+    let (parts, _) = handshake_resp.into_parts();
+    let mut resp = Response::from_parts(parts, Body::empty());
+    if offers_graphql_ws {
+        resp.headers_mut().insert(
+            SEC_WEBSOCKET_PROTOCOL,
+            HeaderValue::from_static("graphql-ws"),
+        );
+    }
+    resp
+}
+
+async fn serve_websocket<Query, Mutation, Subscription>(
+    websocket: WebSocketStream<hyper::upgrade::Upgraded>,
+    schema: Schema<Query, Mutation, Subscription>,
+) where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+{
+    let (mut tx, rx) = websocket.split();
+    let (mut stx, srx) = schema.subscription_connection(WebSocketTransport::default());
+
+    let mut rx = rx.fuse();
+    let mut srx = srx.fuse();
+
+    loop {
+        select! {
+            bytes = srx.next() => {
+                if let Some(bytes) = bytes {
+                    if let Ok(text) = String::from_utf8(bytes.to_vec()) {
+                        if tx.send(Message::Text(text)).await.is_err() {
+                            return;
+                        }
+                    }
+                } else {
+                    return;
+                }
+            }
+            msg = rx.next() => {
+                if let Some(Ok(msg)) = msg {
+                    if let Message::Text(text) = msg {
+                        if stx.send(Bytes::from(text)).await.is_err() {
+                            return;
+                        }
+                    }
+                } else {
+                    return;
+                }
+            }
+        }
+    }
+}
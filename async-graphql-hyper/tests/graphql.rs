@@ -0,0 +1,270 @@
+use async_graphql::*;
+use futures::io::AsyncReadExt;
+use futures::{SinkExt, Stream, StreamExt};
+use hyper::{Body, Client, Request, Server, StatusCode};
+use serde_json::json;
+use std::net::SocketAddr;
+use tokio_tungstenite::tungstenite::Message;
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn add(&self, a: i32, b: i32) -> i32 {
+        a + b
+    }
+
+    async fn value(&self) -> Deferred<i32> {
+        10.into()
+    }
+}
+
+struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn upload(&self, file: Upload) -> String {
+        let filename = file.filename().to_string();
+        let mut content = String::new();
+        file.into_async_read()
+            .read_to_string(&mut content)
+            .await
+            .unwrap();
+        format!("{}:{}", filename, content)
+    }
+}
+
+struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    async fn values(&self) -> impl Stream<Item = i32> {
+        futures::stream::iter(0..3)
+    }
+}
+
+/// Start a server on a random loopback port.
+fn start_server() -> SocketAddr {
+    let schema = Schema::new(QueryRoot, MutationRoot, SubscriptionRoot);
+    let server =
+        Server::bind(&([127, 0, 0, 1], 0).into()).serve(async_graphql_hyper::make_service(schema));
+    let addr = server.local_addr();
+    tokio::spawn(server);
+    addr
+}
+
+async fn send(req: Request<Body>) -> (StatusCode, hyper::HeaderMap, String) {
+    let resp = Client::new().request(req).await.unwrap();
+    let (parts, body) = resp.into_parts();
+    let bytes = hyper::body::to_bytes(body).await.unwrap();
+    (
+        parts.status,
+        parts.headers,
+        String::from_utf8(bytes.to_vec()).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn quickstart() {
+    let addr = start_server();
+
+    let (status, headers, body) = send(
+        Request::post(format!("http://{}/", addr))
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"query":"{ add(a: 10, b: 20) }"}"#))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers["content-type"], "application/json");
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+        json!({"data": {"add": 30}})
+    );
+
+    let (status, _, body) = send(
+        Request::get(format!(
+            "http://{}/?query=%7B%20add%28a%3A%201%2C%20b%3A%202%29%20%7D",
+            addr
+        ))
+        .body(Body::empty())
+        .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+        json!({"data": {"add": 3}})
+    );
+}
+
+#[tokio::test]
+async fn upload() {
+    let addr = start_server();
+    let body = [
+        "--xxx",
+        "Content-Disposition: form-data; name=\"operations\"",
+        "",
+        r#"{ "query": "mutation ($file: Upload!) { upload(file: $file) }", "variables": { "file": null } }"#,
+        "--xxx",
+        "Content-Disposition: form-data; name=\"map\"",
+        "",
+        r#"{ "0": ["variables.file"] }"#,
+        "--xxx",
+        "Content-Disposition: form-data; name=\"0\"; filename=\"a.txt\"",
+        "Content-Type: text/plain",
+        "",
+        "hello",
+        "--xxx--",
+        "",
+    ]
+    .join("\r\n");
+
+    let (status, _, body) = send(
+        Request::post(format!("http://{}/", addr))
+            .header("content-type", "multipart/form-data; boundary=xxx")
+            .body(Body::from(body))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+        json!({"data": {"upload": "a.txt:hello"}})
+    );
+}
+
+#[tokio::test]
+async fn defer() {
+    let addr = start_server();
+    let (status, headers, body) = send(
+        Request::post(format!("http://{}/", addr))
+            .header("content-type", "application/json")
+            .body(Body::from(
+                r#"{"query":"{ add(a: 1, b: 2) value @defer }"}"#,
+            ))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        headers["content-type"],
+        "multipart/mixed; boundary=\"-\"; deferSpec=20220824"
+    );
+    assert!(body.contains(r#"{"data":{"add":3,"value":null},"hasNext":true}"#));
+    assert!(body.contains(
+        r#"{"incremental":[{"data":{"value":10},"path":[],"label":"value"}],"hasNext":true}"#
+    ));
+    assert!(body.contains(r#"{"hasNext":false}"#));
+}
+
+#[tokio::test]
+async fn subscription() {
+    let addr = start_server();
+    let (mut socket, resp) = tokio_tungstenite::connect_async(
+        Request::get(format!("ws://{}/", addr))
+            .header("sec-websocket-protocol", "graphql-ws")
+            .body(())
+            .unwrap(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.headers()["sec-websocket-protocol"], "graphql-ws");
+
+    socket
+        .send(Message::Text(
+            json!({"type": "connection_init"}).to_string(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        next_json(&mut socket).await,
+        json!({"type": "connection_ack"})
+    );
+
+    socket
+        .send(Message::Text(
+            json!({
+                "type": "start",
+                "id": "1",
+                "payload": {"query": "subscription { values }"},
+            })
+            .to_string(),
+        ))
+        .await
+        .unwrap();
+    for n in 0..3 {
+        assert_eq!(
+            next_json(&mut socket).await,
+            json!({"type": "data", "id": "1", "payload": {"data": {"values": n}}})
+        );
+    }
+}
+
+#[tokio::test]
+async fn subprotocol_not_offered() {
+    let addr = start_server();
+    let (_, resp) = tokio_tungstenite::connect_async(format!("ws://{}/", addr))
+        .await
+        .unwrap();
+    assert!(resp.headers().get("sec-websocket-protocol").is_none());
+}
+
+#[tokio::test]
+async fn query_builder_configuration() {
+    struct Token(&'static str);
+
+    struct QueryRoot;
+
+    #[Object]
+    impl QueryRoot {
+        async fn token(&self, ctx: &Context<'_>) -> &str {
+            ctx.data::<Token>().0
+        }
+    }
+
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(
+        async_graphql_hyper::make_service(schema)
+            .query_builder_configuration(|builder| builder.data(Token("abc"))),
+    );
+    let addr = server.local_addr();
+    tokio::spawn(server);
+
+    let (_, _, body) = send(
+        Request::post(format!("http://{}/", addr))
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"query":"{ token }"}"#))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+        json!({"data": {"token": "abc"}})
+    );
+}
+
+#[tokio::test]
+async fn bad_handshake() {
+    let addr = start_server();
+    let (status, _, _) = send(
+        Request::get(format!("http://{}/", addr))
+            .header("connection", "upgrade")
+            .header("upgrade", "websocket")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+async fn next_json<S>(socket: &mut S) -> serde_json::Value
+where
+    S: Stream<Item = std::result::Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    loop {
+        if let Message::Text(text) = socket.next().await.unwrap().unwrap() {
+            return serde_json::from_str(&text).unwrap();
+        }
+    }
+}
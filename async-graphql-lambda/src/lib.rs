@@ -1,11 +1,79 @@
 //! Async-graphql integration with AWS lambda
 //!
+//! It handles the proxy events of API Gateway, both the REST API payload (format 1.0) and the HTTP
+//! API payload (format 2.0) are supported. The events are independent of the Lambda runtime, so
+//! this crate can be used with any of them.
+//!
+//! The `GQLRequestExt` and `GQLResponseExt` traits convert between `http` requests and responses
+//! and GraphQL queries. `lambda_http::Request` is an `http::Request`, and `http::Response<Vec<u8>>`
+//! implements `lambda_http::IntoResponse`, so they can be used directly in a `lambda_http` handler.
+//!
+//! # Examples
+//!
+//! ```
+//! use async_graphql::*;
+//! use async_graphql_lambda::{GQLRequestExt, GQLResponseExt, LambdaRequest, LambdaResponse};
+//! use ::http::{Method, Request, Response};
+//!
+//! struct QueryRoot;
+//!
+//! #[Object]
+//! impl QueryRoot {
+//!     async fn add(&self, a: i32, b: i32) -> i32 {
+//!         a + b
+//!     }
+//! }
+//!
+//! type MySchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+//!
+//! // A `lambda_http` handler
+//! async fn http_handler(schema: &MySchema, req: Request<Vec<u8>>) -> Response<Vec<u8>> {
+//!     match req.graphql().await {
+//!         Ok(query_builder) => query_builder.execute(schema).await.into_http_response(),
+//!         Err(err) => Response::builder()
+//!             .status(400)
+//!             .body(err.to_string().into_bytes())
+//!             .unwrap(),
+//!     }
+//! }
+//!
+//! // A handler of API Gateway events
+//! async fn event_handler(schema: &MySchema, event: LambdaRequest) -> LambdaResponse {
+//!     async_graphql_lambda::graphql(schema, event, |query_builder| query_builder).await
+//! }
+//!
+//! async_std::task::block_on(async {
+//!     let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+//!
+//!     let mut req = Request::new(br#"{"query": "{ add(a: 1, b: 2) }"}"#.to_vec());
+//!     *req.method_mut() = Method::POST;
+//!     req.headers_mut()
+//!         .insert("content-type", "application/json".parse().unwrap());
+//!     let resp = http_handler(&schema, req).await;
+//!     assert_eq!(resp.body(), br#"{"data":{"add":3}}"#);
+//!
+//!     let event = LambdaRequest {
+//!         http_method: Some("POST".to_string()),
+//!         body: Some(r#"{"query": "{ add(a: 1, b: 2) }"}"#.to_string()),
+//!         ..Default::default()
+//!     };
+//!     let resp = event_handler(&schema, event).await;
+//!     assert_eq!(resp.body, r#"{"data":{"add":3}}"#);
+//! });
+//! ```
+
+#![warn(missing_docs)]
 #![forbid(unsafe_code)]
 
-use async_graphql::{IntoQueryBuilder, IntoQueryBuilderOpts, ParseRequestError, QueryBuilder};
-use futures::io::AllowStdIo;
-use lambda_http::Request;
-use std::io::Cursor;
+use async_graphql::http::{handle_request_opts, GQLResponse, ResponseFormat};
+use async_graphql::{
+    IntoQueryBuilder, IntoQueryBuilderOpts, ObjectType, ParseRequestError, QueryBuilder,
+    QueryResponse, Schema, SubscriptionType,
+};
+use futures::io::{AllowStdIo, Cursor};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Lambda request extension
 ///
@@ -24,7 +92,7 @@ pub trait GQLRequestExt {
 }
 
 #[async_trait::async_trait]
-impl GQLRequestExt for Request {
+impl<B: AsRef<[u8]> + Sync> GQLRequestExt for http::Request<B> {
     async fn graphql_opts(
         &self,
         opts: IntoQueryBuilderOpts,
@@ -33,8 +101,265 @@ impl GQLRequestExt for Request {
             .headers()
             .get("content-type")
             .and_then(|value| value.to_str().ok());
-        (ct, AllowStdIo::new(Cursor::new(self.body().to_vec())))
+        (
+            ct,
+            AllowStdIo::new(std::io::Cursor::new(self.body().as_ref().to_vec())),
+        )
             .into_query_builder_opts(&opts)
             .await
     }
 }
+
+/// Lambda response extension
+///
+pub trait GQLResponseExt {
+    /// Convert a GraphQL response to `http::Response`.
+    fn into_http_response(self) -> http::Response<Vec<u8>>
+    where
+        Self: Sized,
+    {
+        self.into_http_response_with_format(ResponseFormat::Json)
+    }
+
+    /// Similar to into_http_response, but you can set the response format.
+    ///
+    /// Use `ResponseFormat::from_accept` to select the format from the `Accept` header.
+    fn into_http_response_with_format(self, format: ResponseFormat) -> http::Response<Vec<u8>>;
+}
+
+impl GQLResponseExt for async_graphql::Result<QueryResponse> {
+    fn into_http_response_with_format(self, format: ResponseFormat) -> http::Response<Vec<u8>> {
+        let cache_control = match &self {
+            Ok(QueryResponse { cache_control, .. }) => cache_control.value(),
+            Err(_) => None,
+        };
+        let gql_resp = GQLResponse(self);
+        let mut builder = http::Response::builder()
+            .status(gql_resp.status_code(format))
+            .header("content-type", format.content_type());
+        if let Some(cache_control) = cache_control {
+            builder = builder.header("cache-control", cache_control);
+        }
+        builder
+            .body(serde_json::to_vec(&gql_resp).unwrap())
+            .unwrap()
+    }
+}
+
+/// API Gateway proxy request event
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LambdaRequest {
+    /// The HTTP method (format 1.0).
+    pub http_method: Option<String>,
+
+    /// The request path (format 1.0).
+    pub path: Option<String>,
+
+    /// The request path (format 2.0).
+    pub raw_path: Option<String>,
+
+    /// The headers, multiple values of a header are joined with commas in format 2.0.
+    pub headers: Option<HashMap<String, String>>,
+
+    /// The headers with all of their values (format 1.0).
+    pub multi_value_headers: Option<HashMap<String, Vec<String>>>,
+
+    /// The query string parameters (format 1.0).
+    pub query_string_parameters: Option<HashMap<String, String>>,
+
+    /// The query string parameters with all of their values (format 1.0).
+    pub multi_value_query_string_parameters: Option<HashMap<String, Vec<String>>>,
+
+    /// The query string (format 2.0).
+    pub raw_query_string: Option<String>,
+
+    /// The request context.
+    pub request_context: Option<LambdaRequestContext>,
+
+    /// The request body.
+    pub body: Option<String>,
+
+    /// Whether the body is base64 encoded.
+    #[serde(default)]
+    pub is_base64_encoded: bool,
+}
+
+/// The request context of `LambdaRequest`
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct LambdaRequestContext {
+    /// The HTTP request (format 2.0).
+    pub http: Option<LambdaHttpContext>,
+}
+
+/// The HTTP request of `LambdaRequestContext`
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct LambdaHttpContext {
+    /// The HTTP method.
+    pub method: String,
+}
+
+/// API Gateway proxy response
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LambdaResponse {
+    /// The HTTP status code.
+    pub status_code: u16,
+
+    /// The headers.
+    pub headers: HashMap<String, String>,
+
+    /// The response body.
+    pub body: String,
+
+    /// Whether the body is base64 encoded.
+    pub is_base64_encoded: bool,
+}
+
+/// Execute a GraphQL request from an API Gateway event.
+///
+/// The request is handled by `async_graphql::http::handle_request`, so GET requests, batching and
+/// uploads are supported. Streaming responses are collected into a single multipart body.
+pub async fn graphql<Query, Mutation, Subscription, F>(
+    schema: &Schema<Query, Mutation, Subscription>,
+    event: LambdaRequest,
+    query_builder_configuration: F,
+) -> LambdaResponse
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+    F: Fn(QueryBuilder) -> QueryBuilder,
+{
+    graphql_opts(
+        schema,
+        event,
+        query_builder_configuration,
+        Default::default(),
+    )
+    .await
+}
+
+/// Similar to graphql, but you can set the options `IntoQueryBuilderOpts`.
+pub async fn graphql_opts<Query, Mutation, Subscription, F>(
+    schema: &Schema<Query, Mutation, Subscription>,
+    event: LambdaRequest,
+    query_builder_configuration: F,
+    opts: IntoQueryBuilderOpts,
+) -> LambdaResponse
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+    F: Fn(QueryBuilder) -> QueryBuilder,
+{
+    let req = match into_http_request(event) {
+        Ok(req) => req,
+        Err(err) => {
+            let mut headers = HashMap::new();
+            headers.insert("content-type".to_string(), "application/json".to_string());
+            return LambdaResponse {
+                status_code: 400,
+                headers,
+                body: serde_json::json!({ "errors": [{ "message": err }] }).to_string(),
+                is_base64_encoded: false,
+            };
+        }
+    };
+
+    let resp = handle_request_opts(schema, req, query_builder_configuration, &opts).await;
+    let (parts, body) = resp.into_parts();
+    let body = body.collect::<Vec<_>>().await.concat();
+
+    let mut headers = HashMap::new();
+    for (name, value) in &parts.headers {
+        if let Ok(value) = value.to_str() {
+            headers
+                .entry(name.as_str().to_string())
+                .and_modify(|values: &mut String| {
+                    values.push_str(", ");
+                    values.push_str(value);
+                })
+                .or_insert_with(|| value.to_string());
+        }
+    }
+
+    let (body, is_base64_encoded) = match String::from_utf8(body) {
+        Ok(body) => (body, false),
+        Err(err) => (base64::encode(err.into_bytes()), true),
+    };
+    LambdaResponse {
+        status_code: parts.status.as_u16(),
+        headers,
+        body,
+        is_base64_encoded,
+    }
+}
+
+fn into_http_request(event: LambdaRequest) -> Result<http::Request<Cursor<Vec<u8>>>, String> {
+    let request_context = event.request_context;
+    let method = event
+        .http_method
+        .or_else(|| {
+            request_context
+                .and_then(|ctx| ctx.http)
+                .map(|http| http.method)
+        })
+        .ok_or_else(|| "Missing HTTP method".to_string())?;
+
+    let mut uri = event
+        .raw_path
+        .or(event.path)
+        .unwrap_or_else(|| "/".to_string());
+    let query = match (
+        event.raw_query_string,
+        event.multi_value_query_string_parameters,
+        event.query_string_parameters,
+    ) {
+        (Some(query), _, _) => query,
+        (None, Some(params), _) => serde_urlencoded::to_string(
+            params
+                .iter()
+                .flat_map(|(name, values)| values.iter().map(move |value| (name, value)))
+                .collect::<Vec<_>>(),
+        )
+        .map_err(|err| err.to_string())?,
+        (None, None, Some(params)) => {
+            serde_urlencoded::to_string(&params).map_err(|err| err.to_string())?
+        }
+        (None, None, None) => String::new(),
+    };
+    if !query.is_empty() {
+        uri.push('?');
+        uri.push_str(&query);
+    }
+
+    let mut builder = http::Request::builder().method(method.as_str()).uri(uri);
+    match (event.multi_value_headers, event.headers) {
+        (Some(headers), _) => {
+            for (name, values) in headers {
+                for value in values {
+                    builder = builder.header(name.as_str(), value);
+                }
+            }
+        }
+        (None, Some(headers)) => {
+            for (name, value) in headers {
+                builder = builder.header(name.as_str(), value);
+            }
+        }
+        (None, None) => {}
+    }
+
+    let body = match event.body {
+        Some(body) if event.is_base64_encoded => {
+            base64::decode(body).map_err(|err| format!("Invalid base64 body: {}", err))?
+        }
+        Some(body) => body.into_bytes(),
+        None => Vec::new(),
+    };
+
+    builder
+        .body(Cursor::new(body))
+        .map_err(|err| err.to_string())
+}
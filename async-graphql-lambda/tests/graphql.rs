@@ -0,0 +1,179 @@
+use async_graphql::http::ResponseFormat;
+use async_graphql::*;
+use async_graphql_lambda::{GQLRequestExt, GQLResponseExt, LambdaRequest, LambdaResponse};
+use serde_json::json;
+
+struct QueryRoot;
+
+#[Object(cache_control(max_age = 60))]
+impl QueryRoot {
+    async fn add(&self, a: i32, b: i32) -> i32 {
+        a + b
+    }
+}
+
+async fn execute(event: serde_json::Value) -> LambdaResponse {
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    let event: LambdaRequest = serde_json::from_value(event).unwrap();
+    async_graphql_lambda::graphql(&schema, event, |query_builder| query_builder).await
+}
+
+#[async_std::test]
+async fn rest_api_event() {
+    let resp = execute(json!({
+        "resource": "/graphql",
+        "path": "/graphql",
+        "httpMethod": "POST",
+        "headers": {"Content-Type": "application/json"},
+        "multiValueHeaders": {"Content-Type": ["application/json"]},
+        "queryStringParameters": null,
+        "multiValueQueryStringParameters": null,
+        "requestContext": {"stage": "prod"},
+        "body": r#"{"query": "{ add(a: 10, b: 20) }"}"#,
+        "isBase64Encoded": false,
+    }))
+    .await;
+    assert_eq!(resp.status_code, 200);
+    assert_eq!(resp.headers["content-type"], "application/json");
+    assert_eq!(resp.headers["cache-control"], "max-age=60");
+    assert!(!resp.is_base64_encoded);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&resp.body).unwrap(),
+        json!({"data": {"add": 30}})
+    );
+
+    let resp = execute(json!({
+        "path": "/graphql",
+        "httpMethod": "GET",
+        "headers": null,
+        "queryStringParameters": {"query": "{ add(a: 1, b: 2) }"},
+        "body": null,
+    }))
+    .await;
+    assert_eq!(resp.status_code, 200);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&resp.body).unwrap(),
+        json!({"data": {"add": 3}})
+    );
+}
+
+#[async_std::test]
+async fn http_api_event() {
+    let resp = execute(json!({
+        "version": "2.0",
+        "routeKey": "$default",
+        "rawPath": "/graphql",
+        "rawQueryString": "",
+        "headers": {
+            "content-type": "application/json",
+            "accept": "application/graphql-response+json",
+        },
+        "requestContext": {"http": {"method": "POST", "path": "/graphql"}},
+        "body": base64::encode(r#"{"query": "{ add(a: 1, b: 2) }"}"#),
+        "isBase64Encoded": true,
+    }))
+    .await;
+    assert_eq!(resp.status_code, 200);
+    assert_eq!(
+        resp.headers["content-type"],
+        "application/graphql-response+json"
+    );
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&resp.body).unwrap(),
+        json!({"data": {"add": 3}})
+    );
+
+    let resp = execute(json!({
+        "version": "2.0",
+        "rawPath": "/graphql",
+        "rawQueryString": "query=%7B%20add%28a%3A%201%2C%20b%3A%202%29%20%7D",
+        "headers": {},
+        "requestContext": {"http": {"method": "GET"}},
+        "isBase64Encoded": false,
+    }))
+    .await;
+    assert_eq!(resp.status_code, 200);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&resp.body).unwrap(),
+        json!({"data": {"add": 3}})
+    );
+}
+
+#[async_std::test]
+async fn error_status() {
+    let resp = execute(json!({
+        "rawPath": "/graphql",
+        "headers": {
+            "content-type": "application/json",
+            "accept": "application/graphql-response+json",
+        },
+        "requestContext": {"http": {"method": "POST"}},
+        "body": r#"{"query": "{ add }"}"#,
+    }))
+    .await;
+    assert_eq!(resp.status_code, 400);
+    assert!(!resp.headers.contains_key("cache-control"));
+
+    let resp = execute(json!({
+        "httpMethod": "POST",
+        "path": "/graphql",
+        "headers": {"content-type": "application/json"},
+        "body": "{",
+    }))
+    .await;
+    assert_eq!(resp.status_code, 400);
+
+    let resp = execute(json!({
+        "httpMethod": "POST",
+        "path": "/graphql",
+        "body": "not base64!",
+        "isBase64Encoded": true,
+    }))
+    .await;
+    assert_eq!(resp.status_code, 400);
+
+    let resp = execute(json!({
+        "httpMethod": "DELETE",
+        "path": "/graphql",
+    }))
+    .await;
+    assert_eq!(resp.status_code, 405);
+}
+
+#[async_std::test]
+async fn request_ext() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+
+    let mut req = ::http::Request::new(r#"{"query": "{ add(a: 1, b: 2) }"}"#);
+    req.headers_mut()
+        .insert("content-type", "application/json".parse().unwrap());
+    let resp = req
+        .graphql()
+        .await
+        .unwrap()
+        .execute(&schema)
+        .await
+        .into_http_response();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "application/json");
+    assert_eq!(resp.headers()["cache-control"], "max-age=60");
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(resp.body()).unwrap(),
+        json!({"data": {"add": 3}})
+    );
+
+    let req = ::http::Request::new(r#"{"query": "{ add }"}"#);
+    let resp = req
+        .graphql()
+        .await
+        .unwrap()
+        .execute(&schema)
+        .await
+        .into_http_response_with_format(ResponseFormat::GraphQLResponseJson);
+    assert_eq!(resp.status(), 400);
+    assert_eq!(
+        resp.headers()["content-type"],
+        "application/graphql-response+json"
+    );
+    assert!(resp.headers().get("cache-control").is_none());
+}
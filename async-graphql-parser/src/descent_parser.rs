@@ -0,0 +1,1615 @@
+//! Hand-written recursive-descent parser for queries.
+//!
+//! It accepts exactly the language of `query.pest` and produces the same AST. An invalid query is
+//! reported at the furthest position the parser reached, with a description of the token found
+//! there.
+//!
+//! In the recovering mode, the parser reports an error at the furthest position it reached, skips
+//! the invalid argument list, selection or definition and goes on. It stops at an unterminated
+//! string, because the rest of the input can not be split into tokens reliably.
+//!
+//! The type system definitions are parsed but only their names are kept, they are reported by the
+//! validation like the input objects with duplicate fields.
+
+use crate::pos::{Pos, Positioned, Span};
+use crate::query::*;
+use crate::utils::{unquote_string, PositionCalculator};
+use crate::value::Value;
+use crate::{Error, Result};
+use std::collections::BTreeMap;
+
+/// Parse a GraphQL query, returns the first syntax error if it is invalid.
+pub(crate) fn parse_query(input: &str) -> Result<Document> {
+    let mut parser = Parser::new(input, None);
+    match parser.parse_document() {
+        Some(document) => Ok(document),
+        None => Err(parser.error()),
+    }
+}
+
+/// Parse a GraphQL query, returns the valid parts of the document and all the syntax errors.
+pub(crate) fn parse_query_recovering(input: &str) -> (Document, Vec<Error>) {
+    let mut parser = Parser::new(input, Some(Vec::new()));
+    let document = parser
+        .parse_document()
+        .expect("the recovering parser always returns a document");
+    let mut errors = parser.errors.unwrap_or_default();
+    errors.sort_by_key(|err| err.pos);
+    (document, errors)
+}
+
+/// The keywords which start a type system definition or extension.
+const TYPE_SYSTEM_KEYWORDS: &[&str] = &[
+    "extend",
+    "schema",
+    "scalar",
+    "type",
+    "interface",
+    "union",
+    "enum",
+    "input",
+    "directive",
+];
+
+struct Parser<'a> {
+    input: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    /// The end of the last token, where the span of a node ends.
+    prev_end: usize,
+    pc: PositionCalculator<'a>,
+    /// The furthest position where a token was expected, the errors are reported there.
+    furthest: usize,
+    /// The errors found so far, `None` if the parser stops at the first error.
+    errors: Option<Vec<Error>>,
+    /// Set when the recovering parser found an unterminated string and stops.
+    stopped: bool,
+    duplicate_input_fields: Vec<DuplicateInputField>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str, errors: Option<Vec<Error>>) -> Self {
+        Parser {
+            input,
+            bytes: input.as_bytes(),
+            pos: 0,
+            prev_end: 0,
+            pc: PositionCalculator::new(input),
+            furthest: 0,
+            errors,
+            stopped: false,
+            duplicate_input_fields: Vec::new(),
+        }
+    }
+
+    fn parse_document(&mut self) -> Option<Document> {
+        self.skip_ignored();
+
+        let mut definitions = Vec::new();
+        let mut type_system_definitions = Vec::new();
+        loop {
+            let start = self.pos;
+            let parsed = if self.at_type_system_definition() {
+                self.parse_type_system_definition()
+                    .map(|definition| type_system_definitions.push(definition))
+            } else {
+                self.parse_definition()
+                    .map(|definition| definitions.push(definition))
+            };
+            if parsed.is_none() {
+                let error = self.recover();
+                if self.stopped {
+                    self.discard_duplicate_input_fields(start);
+                    break;
+                }
+                let error = error?;
+                self.discard_duplicate_input_fields(start);
+                self.pos = start;
+                self.skip_definition(error);
+            }
+            self.expected();
+            if self.pos == self.bytes.len() {
+                break;
+            }
+        }
+
+        Some(Document {
+            definitions,
+            fragments: Default::default(),
+            current_operation: None,
+            type_system_definitions,
+            duplicate_input_fields: std::mem::take(&mut self.duplicate_input_fields),
+        })
+    }
+
+    /// Returns the error at the furthest position.
+    fn error(&mut self) -> Error {
+        Error {
+            pos: self.pc.position(self.furthest),
+            message: describe(&self.input[self.furthest..]),
+        }
+    }
+
+    /// Record an error at the furthest position and returns it, returns `None` if the parser
+    /// does not recover.
+    fn recover(&mut self) -> Option<usize> {
+        if self.stopped {
+            return None;
+        }
+        self.errors.as_ref()?;
+        let error = self.error();
+        let errors = self.errors.as_mut()?;
+        // The same error can be found again after backtracking.
+        if errors.iter().all(|err| err.pos != error.pos) {
+            errors.push(error);
+        }
+        let furthest = std::mem::replace(&mut self.furthest, 0);
+        if is_unterminated_string(&self.bytes[furthest..]) {
+            self.stopped = true;
+            return None;
+        }
+        Some(furthest)
+    }
+
+    /// Forget the duplicate input fields found from `start`, when the node which contains them is
+    /// parsed again or skipped.
+    fn discard_duplicate_input_fields(&mut self, start: usize) {
+        while let Some(field) = self.duplicate_input_fields.last() {
+            if field.duplicate.start_offset < start {
+                break;
+            }
+            self.duplicate_input_fields.pop();
+        }
+    }
+
+    /// Skip the rest of an invalid definition, up to the next definition after the error.
+    fn skip_definition(&mut self, error: usize) {
+        // The selection set belongs to the invalid definition if it starts with a keyword.
+        let keyword = self.at_keyword() || self.peek() == Some(b'"');
+        let mut first = true;
+        while let Some(b) = self.peek() {
+            if !first && self.pos >= error && (self.at_keyword() || (b == b'{' && !keyword)) {
+                return;
+            }
+            match b {
+                b'{' | b'(' | b'[' => self.skip_group(),
+                _ => self.skip_token(),
+            }
+            first = false;
+        }
+    }
+
+    /// Skip the rest of an invalid selection, up to the next selection after the error or the end
+    /// of the selection set.
+    fn skip_selection(&mut self, error: usize) {
+        let mut prev = "";
+        let mut prev2 = "";
+        while let Some(b) = self.peek() {
+            if b == b'}' {
+                return;
+            }
+            let token = if b == b'{' || b == b'(' || b == b'[' {
+                self.skip_group();
+                ")"
+            } else {
+                let token = &self.input[self.pos..self.pos + token_len(&self.input[self.pos..])];
+                let starts_selection = is_name_start(b) || token == "...";
+                // The first token is skipped, and a name can be a part of the invalid selection.
+                let in_selection = matches!(prev, "" | "@" | ":" | "$" | "...")
+                    || (prev == "on" && prev2 == "...");
+                if starts_selection && !in_selection && self.pos >= error {
+                    return;
+                }
+                self.skip_token();
+                token
+            };
+            prev2 = prev;
+            prev = token;
+        }
+    }
+
+    /// Skip a group of tokens in brackets, stops before the closing brace of the enclosing
+    /// selection set if the group is not closed.
+    fn skip_group(&mut self) {
+        let mut stack = Vec::new();
+        while let Some(b) = self.peek() {
+            match b {
+                b'{' | b'(' | b'[' => stack.push(b),
+                b'}' | b')' | b']' => {
+                    let open = match b {
+                        b'}' => b'{',
+                        b')' => b'(',
+                        _ => b'[',
+                    };
+                    match stack.iter().rposition(|b| *b == open) {
+                        Some(idx) => stack.truncate(idx),
+                        None if b == b'}' => return,
+                        None => {}
+                    }
+                }
+                _ => {}
+            }
+            self.skip_token();
+            if stack.is_empty() {
+                return;
+            }
+        }
+    }
+
+    fn skip_token(&mut self) {
+        self.pos += token_len(&self.input[self.pos..]);
+        self.skip_ignored();
+    }
+
+    fn at_keyword(&self) -> bool {
+        let rest = &self.bytes[self.pos..];
+        ["query", "mutation", "subscription", "fragment"]
+            .iter()
+            .chain(TYPE_SYSTEM_KEYWORDS)
+            .any(|keyword| rest.starts_with(keyword.as_bytes()))
+    }
+
+    /// Check if the next definition is a type system definition, which starts with a description
+    /// or a keyword that does not start an executable definition.
+    fn at_type_system_definition(&self) -> bool {
+        let rest = &self.bytes[self.pos..];
+        rest.first() == Some(&b'"')
+            || TYPE_SYSTEM_KEYWORDS
+                .iter()
+                .any(|keyword| rest.starts_with(keyword.as_bytes()))
+    }
+
+    /// Mark the current position as a position where a token was expected.
+    fn expected(&mut self) {
+        self.furthest = self.furthest.max(self.pos);
+    }
+
+    /// Skip whitespace, commas and comments.
+    fn skip_ignored(&mut self) {
+        while let Some(&b) = self.bytes.get(self.pos) {
+            match b {
+                b' ' | b',' | b'\t' | b'\n' | b'\r' => self.pos += 1,
+                0xEF if self.bytes[self.pos..].starts_with("\u{feff}".as_bytes()) => self.pos += 3,
+                b'#' => {
+                    while let Some(&b) = self.bytes.get(self.pos) {
+                        if b == b'\n' {
+                            break;
+                        }
+                        self.pos += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn position(&mut self) -> Pos {
+        self.pc.position(self.pos)
+    }
+
+    /// Returns the start of a node, its span is returned by `span` after its last token.
+    fn start(&mut self) -> (Pos, usize) {
+        (self.position(), self.pos)
+    }
+
+    fn span(&mut self, (start, start_offset): (Pos, usize)) -> Span {
+        self.pc.span(start, start_offset, self.prev_end)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    /// Check the next byte of an optional token.
+    fn next_is(&mut self, b: u8) -> bool {
+        self.expected();
+        self.peek() == Some(b)
+    }
+
+    /// Consume a punctuator or a keyword.
+    ///
+    /// Keywords are matched as prefixes like the grammar does, so `queryFoo` is the keyword
+    /// `query` followed by the name `Foo`.
+    fn eat(&mut self, token: &str) -> bool {
+        self.expected();
+        if self.bytes[self.pos..].starts_with(token.as_bytes()) {
+            self.pos += token.len();
+            self.prev_end = self.pos;
+            self.skip_ignored();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Option<()> {
+        if self.eat(token) {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// Consume a token of `len` bytes.
+    fn token(&mut self, len: usize) -> &'a str {
+        let token = &self.input[self.pos..self.pos + len];
+        self.pos += len;
+        self.prev_end = self.pos;
+        self.skip_ignored();
+        token
+    }
+
+    fn name(&mut self) -> Option<&'a str> {
+        self.expected();
+        let len = name_len(&self.bytes[self.pos..])?;
+        Some(self.token(len))
+    }
+
+    fn positioned_name(&mut self) -> Option<Positioned<String>> {
+        let start = self.start();
+        let name = self.name()?.to_string();
+        Some(Positioned::new(name, self.span(start)))
+    }
+
+    fn parse_definition(&mut self) -> Option<Positioned<Definition>> {
+        let start = self.pos;
+        if let Some(operation) = self.parse_named_operation_definition() {
+            return Some(operation.pack(Definition::Operation));
+        }
+        self.discard_duplicate_input_fields(start);
+        self.pos = start;
+
+        if self.next_is(b'{') {
+            return Some(
+                self.parse_selection_set()?
+                    .pack(OperationDefinition::SelectionSet)
+                    .pack(Definition::Operation),
+            );
+        }
+        Some(self.parse_fragment_definition()?.pack(Definition::Fragment))
+    }
+
+    fn parse_named_operation_definition(&mut self) -> Option<Positioned<OperationDefinition>> {
+        let start = self.start();
+        let ty = if self.eat("query") {
+            OperationType::Query
+        } else if self.eat("mutation") {
+            OperationType::Mutation
+        } else if self.eat("subscription") {
+            OperationType::Subscription
+        } else {
+            return None;
+        };
+        self.expected();
+        let name = match self.peek() {
+            Some(b) if is_name_start(b) => Some(self.positioned_name()?),
+            _ => None,
+        };
+        let variable_definitions = if self.next_is(b'(') {
+            self.parse_variable_definitions()?
+        } else {
+            Vec::new()
+        };
+        let directives = self.parse_directives()?;
+        let selection_set = self.parse_selection_set()?;
+
+        Some(match ty {
+            OperationType::Query => Positioned::new(
+                Query {
+                    name,
+                    variable_definitions,
+                    directives,
+                    selection_set,
+                },
+                self.span(start),
+            )
+            .pack(OperationDefinition::Query),
+            OperationType::Mutation => Positioned::new(
+                Mutation {
+                    name,
+                    variable_definitions,
+                    directives,
+                    selection_set,
+                },
+                self.span(start),
+            )
+            .pack(OperationDefinition::Mutation),
+            OperationType::Subscription => Positioned::new(
+                Subscription {
+                    name,
+                    variable_definitions,
+                    directives,
+                    selection_set,
+                },
+                self.span(start),
+            )
+            .pack(OperationDefinition::Subscription),
+        })
+    }
+
+    fn parse_variable_definitions(&mut self) -> Option<Vec<Positioned<VariableDefinition>>> {
+        let start = self.pos;
+        self.expect("(")?;
+        let mut vars = Vec::new();
+        while !self.eat(")") {
+            let var_start = self.pos;
+            match self.parse_variable_definition() {
+                Some(var) => vars.push(var),
+                None => {
+                    self.recover()?;
+                    self.discard_duplicate_input_fields(var_start);
+                    self.pos = start;
+                    self.skip_group();
+                    break;
+                }
+            }
+        }
+        Some(vars)
+    }
+
+    fn parse_variable_definition(&mut self) -> Option<Positioned<VariableDefinition>> {
+        let start = self.start();
+        let name = self.parse_variable()?;
+        self.expect(":")?;
+        let var_type = {
+            let start = self.start();
+            Positioned::new(self.parse_type()?, self.span(start))
+        };
+        let default_value = if self.next_is(b'=') {
+            let start = self.start();
+            self.expect("=")?;
+            Some(Positioned::new(self.parse_value()?, self.span(start)))
+        } else {
+            None
+        };
+        Some(Positioned::new(
+            VariableDefinition {
+                name,
+                var_type,
+                default_value,
+            },
+            self.span(start),
+        ))
+    }
+
+    fn parse_variable(&mut self) -> Option<Positioned<String>> {
+        self.expect("$")?;
+        self.positioned_name()
+    }
+
+    fn parse_type(&mut self) -> Option<Type> {
+        let ty = if self.eat("[") {
+            let ty = self.parse_type()?;
+            self.expect("]")?;
+            Type::List(Box::new(ty))
+        } else {
+            Type::Named(self.name()?.to_string())
+        };
+        Some(if self.eat("!") {
+            Type::NonNull(Box::new(ty))
+        } else {
+            ty
+        })
+    }
+
+    fn parse_directives(&mut self) -> Option<Vec<Positioned<Directive>>> {
+        let mut directives = Vec::new();
+        while self.next_is(b'@') {
+            let start = self.start();
+            self.expect("@")?;
+            let name = self.positioned_name()?;
+            let arguments = if self.next_is(b'(') {
+                self.parse_arguments()?
+            } else {
+                Vec::new()
+            };
+            directives.push(Positioned::new(
+                Directive { name, arguments },
+                self.span(start),
+            ));
+        }
+        Some(directives)
+    }
+
+    fn parse_arguments(&mut self) -> Option<Vec<(Positioned<String>, Positioned<Value>)>> {
+        let start = self.pos;
+        self.expect("(")?;
+        let mut arguments = Vec::new();
+        while !self.eat(")") {
+            let argument_start = self.pos;
+            match self.parse_argument() {
+                Some(argument) => arguments.push(argument),
+                None => {
+                    self.recover()?;
+                    self.discard_duplicate_input_fields(argument_start);
+                    self.pos = start;
+                    self.skip_group();
+                    break;
+                }
+            }
+        }
+        Some(arguments)
+    }
+
+    fn parse_argument(&mut self) -> Option<(Positioned<String>, Positioned<Value>)> {
+        let name = self.positioned_name()?;
+        self.expect(":")?;
+        let start = self.start();
+        Some((name, Positioned::new(self.parse_value()?, self.span(start))))
+    }
+
+    fn parse_value(&mut self) -> Option<Value> {
+        self.expected();
+        let rest = &self.bytes[self.pos..];
+        match *rest.first()? {
+            b'{' => {
+                self.expect("{")?;
+                let mut map = BTreeMap::new();
+                let mut names: Vec<Positioned<String>> = Vec::new();
+                while !self.eat("}") {
+                    let name = self.positioned_name()?;
+                    match names.iter().find(|first| first.node == name.node) {
+                        Some(first) => self.duplicate_input_fields.push(DuplicateInputField {
+                            first: first.clone(),
+                            duplicate: name.span(),
+                        }),
+                        None => names.push(name.clone()),
+                    }
+                    self.expect(":")?;
+                    map.insert(name.into_inner(), self.parse_value()?);
+                }
+                Some(Value::Object(map))
+            }
+            b'[' => {
+                self.expect("[")?;
+                let mut array = Vec::new();
+                while !self.eat("]") {
+                    array.push(self.parse_value()?);
+                }
+                Some(Value::List(array))
+            }
+            b'$' => Some(Value::Variable(self.parse_variable()?.into_inner())),
+            b'"' => {
+                let pos = self.position();
+                let len = string_len(rest)?;
+                unquote_string(self.token(len), pos).ok().map(Value::String)
+            }
+            b'-' | b'0'..=b'9' => match float_len(rest) {
+                Some(len) => self.token(len).parse().ok().map(Value::Float),
+                None => {
+                    let len = int_len(rest)?;
+                    self.token(len).parse().ok().map(Value::Int)
+                }
+            },
+            _ => {
+                if self.eat("null") {
+                    Some(Value::Null)
+                } else if self.eat("true") {
+                    Some(Value::Boolean(true))
+                } else if self.eat("false") {
+                    Some(Value::Boolean(false))
+                } else {
+                    Some(Value::Enum(self.name()?.to_string()))
+                }
+            }
+        }
+    }
+
+    fn parse_selection_set(&mut self) -> Option<Positioned<SelectionSet>> {
+        let start = self.start();
+        self.expect("{")?;
+        let mut items = Vec::new();
+        loop {
+            let start = self.pos;
+            match self.parse_selection() {
+                Some(selection) => items.push(selection),
+                None => {
+                    let error = self.recover()?;
+                    self.discard_duplicate_input_fields(start);
+                    self.pos = start;
+                    self.skip_selection(error);
+                    if self.pos == self.bytes.len() {
+                        break;
+                    }
+                }
+            }
+            if self.eat("}") {
+                break;
+            }
+        }
+        Some(Positioned::new(SelectionSet { items }, self.span(start)))
+    }
+
+    fn parse_selection(&mut self) -> Option<Positioned<Selection>> {
+        if !self.next_is(b'.') {
+            return Some(self.parse_field()?.pack(Selection::Field));
+        }
+
+        let start = self.pos;
+        if let Some(inline_fragment) = self.parse_inline_fragment() {
+            return Some(inline_fragment.pack(Selection::InlineFragment));
+        }
+        self.discard_duplicate_input_fields(start);
+        self.pos = start;
+
+        let start = self.start();
+        self.expect("...")?;
+        let fragment_name = self.positioned_name()?;
+        let directives = self.parse_directives()?;
+        Some(
+            Positioned::new(
+                FragmentSpread {
+                    fragment_name,
+                    directives,
+                },
+                self.span(start),
+            )
+            .pack(Selection::FragmentSpread),
+        )
+    }
+
+    fn parse_field(&mut self) -> Option<Positioned<Field>> {
+        let start = self.start();
+        let first = self.positioned_name()?;
+        let (alias, name) = if self.eat(":") {
+            (Some(first), self.positioned_name()?)
+        } else {
+            (None, first)
+        };
+        let arguments = if self.next_is(b'(') {
+            self.parse_arguments()?
+        } else {
+            Vec::new()
+        };
+        let directives = self.parse_directives()?;
+        let selection_set = if self.next_is(b'{') {
+            self.parse_selection_set()?
+        } else {
+            Default::default()
+        };
+
+        Some(Positioned::new(
+            Field {
+                alias,
+                name,
+                arguments,
+                directives,
+                selection_set,
+            },
+            self.span(start),
+        ))
+    }
+
+    fn parse_inline_fragment(&mut self) -> Option<Positioned<InlineFragment>> {
+        let start = self.start();
+        self.expect("...")?;
+        let after_dots = self.pos;
+        let type_condition = self.parse_type_condition();
+        if type_condition.is_none() {
+            self.pos = after_dots;
+        }
+        let directives = self.parse_directives()?;
+        let selection_set = self.parse_selection_set()?;
+
+        Some(Positioned::new(
+            InlineFragment {
+                type_condition,
+                directives,
+                selection_set,
+            },
+            self.span(start),
+        ))
+    }
+
+    fn parse_type_condition(&mut self) -> Option<Positioned<TypeCondition>> {
+        self.expect("on")?;
+        let name = self.positioned_name()?;
+        let span = name.span();
+        Some(Positioned::new(TypeCondition::On(name), span))
+    }
+
+    fn parse_fragment_definition(&mut self) -> Option<Positioned<FragmentDefinition>> {
+        let start = self.start();
+        self.expect("fragment")?;
+        let name = self.positioned_name()?;
+        let type_condition = self.parse_type_condition()?;
+        let directives = self.parse_directives()?;
+        let selection_set = self.parse_selection_set()?;
+
+        Some(Positioned::new(
+            FragmentDefinition {
+                name,
+                type_condition,
+                directives,
+                selection_set,
+            },
+            self.span(start),
+        ))
+    }
+
+    fn parse_type_system_definition(&mut self) -> Option<Positioned<TypeSystemDefinition>> {
+        let start = self.start();
+        let extend = self.eat("extend");
+        if !extend && self.next_is(b'"') {
+            self.parse_description()?;
+        }
+        let name = self.parse_type_system_definition_body(extend)?;
+        Some(Positioned::new(
+            TypeSystemDefinition { name },
+            self.span(start),
+        ))
+    }
+
+    /// Parse a type system definition after its description or an extension after `extend`,
+    /// returns its name.
+    fn parse_type_system_definition_body(
+        &mut self,
+        extend: bool,
+    ) -> Option<Option<Positioned<String>>> {
+        if self.eat("schema") {
+            self.parse_directives()?;
+            if self.eat("{") {
+                loop {
+                    if !(self.eat("query") || self.eat("mutation") || self.eat("subscription")) {
+                        return None;
+                    }
+                    self.expect(":")?;
+                    self.name()?;
+                    if self.eat("}") {
+                        break;
+                    }
+                }
+            }
+            return Some(None);
+        }
+
+        let name = if self.eat("scalar") {
+            let name = self.positioned_name()?;
+            self.parse_directives()?;
+            name
+        } else if self.eat("type") || self.eat("interface") {
+            let name = self.positioned_name()?;
+            if self.eat("implements") {
+                self.eat("&");
+                self.name()?;
+                while self.eat("&") {
+                    self.name()?;
+                }
+            }
+            self.parse_directives()?;
+            if self.eat("{") {
+                loop {
+                    self.parse_field_definition()?;
+                    if self.eat("}") {
+                        break;
+                    }
+                }
+            }
+            name
+        } else if self.eat("union") {
+            let name = self.positioned_name()?;
+            self.parse_directives()?;
+            if self.eat("=") {
+                self.eat("|");
+                self.name()?;
+                while self.eat("|") {
+                    self.name()?;
+                }
+            }
+            name
+        } else if self.eat("enum") {
+            let name = self.positioned_name()?;
+            self.parse_directives()?;
+            if self.eat("{") {
+                loop {
+                    if self.next_is(b'"') {
+                        self.parse_description()?;
+                    }
+                    self.name()?;
+                    self.parse_directives()?;
+                    if self.eat("}") {
+                        break;
+                    }
+                }
+            }
+            name
+        } else if self.eat("input") {
+            let name = self.positioned_name()?;
+            self.parse_directives()?;
+            if self.eat("{") {
+                loop {
+                    self.parse_input_value_definition()?;
+                    if self.eat("}") {
+                        break;
+                    }
+                }
+            }
+            name
+        } else if !extend {
+            self.expect("directive")?;
+            self.expect("@")?;
+            let name = self.positioned_name()?;
+            if self.next_is(b'(') {
+                self.parse_arguments_definition()?;
+            }
+            self.eat("repeatable");
+            self.expect("on")?;
+            self.eat("|");
+            self.name()?;
+            while self.eat("|") {
+                self.name()?;
+            }
+            name
+        } else {
+            return None;
+        };
+        Some(Some(name))
+    }
+
+    fn parse_description(&mut self) -> Option<()> {
+        self.expected();
+        let len = string_len(&self.bytes[self.pos..])?;
+        self.token(len);
+        Some(())
+    }
+
+    fn parse_field_definition(&mut self) -> Option<()> {
+        if self.next_is(b'"') {
+            self.parse_description()?;
+        }
+        self.name()?;
+        if self.next_is(b'(') {
+            self.parse_arguments_definition()?;
+        }
+        self.expect(":")?;
+        self.parse_type()?;
+        self.parse_directives()?;
+        Some(())
+    }
+
+    fn parse_arguments_definition(&mut self) -> Option<()> {
+        self.expect("(")?;
+        loop {
+            self.parse_input_value_definition()?;
+            if self.eat(")") {
+                return Some(());
+            }
+        }
+    }
+
+    fn parse_input_value_definition(&mut self) -> Option<()> {
+        if self.next_is(b'"') {
+            self.parse_description()?;
+        }
+        self.name()?;
+        self.expect(":")?;
+        self.parse_type()?;
+        if self.eat("=") {
+            self.parse_value()?;
+        }
+        self.parse_directives()?;
+        Some(())
+    }
+}
+
+/// Describe the token at the start of `s` for an error message.
+fn describe(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let token = &s[..token_len(s)];
+    match bytes.first() {
+        None => "Unexpected end of input".to_string(),
+        Some(b'"') => match string_len(bytes) {
+            Some(len) => match unquote_string(&s[..len], Pos::default()) {
+                Ok(_) => "Unexpected string".to_string(),
+                Err(err) => err.message,
+            },
+            None if is_unterminated_string(bytes) => "Unterminated string".to_string(),
+            None => "Invalid escape sequence in string".to_string(),
+        },
+        Some(b) if is_name_start(*b) => format!("Unexpected name \"{}\"", token),
+        Some(b'-') | Some(b'0'..=b'9') if float_len(bytes).or_else(|| int_len(bytes)).is_some() => {
+            if float_len(bytes).is_none() && token.parse::<i64>().is_err() {
+                format!("Number \"{}\" is out of range", token)
+            } else {
+                format!("Unexpected number \"{}\"", token)
+            }
+        }
+        _ => format!("Unexpected \"{}\"", token),
+    }
+}
+
+/// Length of the token at the start of `s`, an invalid string ends at the closing quote or at the
+/// end of the line.
+pub(crate) fn token_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    match bytes.first() {
+        None => 0,
+        Some(b'"') => string_len(bytes).unwrap_or_else(|| {
+            let mut i = 1;
+            while let Some(b) = bytes.get(i) {
+                match b {
+                    b'"' => return i + 1,
+                    b'\n' | b'\r' => return i,
+                    b'\\' => i += 2,
+                    _ => i += 1,
+                }
+            }
+            bytes.len()
+        }),
+        Some(b) if is_name_start(*b) => name_len(bytes).unwrap_or(1),
+        Some(b'-') | Some(b'0'..=b'9') => float_len(bytes).or_else(|| int_len(bytes)).unwrap_or(1),
+        Some(b'.') if bytes.starts_with(b"...") => 3,
+        Some(_) => s.chars().next().map(char::len_utf8).unwrap_or(1),
+    }
+}
+
+/// Check if `s` starts with a string which is not closed, a string with an invalid escape
+/// sequence is not.
+fn is_unterminated_string(s: &[u8]) -> bool {
+    if s.first() != Some(&b'"') || string_len(s).is_some() {
+        return false;
+    }
+    let mut i = 1;
+    while let Some(b) = s.get(i) {
+        match b {
+            b'\\' => match escape_len(&s[i..]) {
+                Some(len) => i += len,
+                None => return false,
+            },
+            _ => i += 1,
+        }
+    }
+    true
+}
+
+fn is_name_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_'
+}
+
+fn name_len(s: &[u8]) -> Option<usize> {
+    if !is_name_start(*s.first()?) {
+        return None;
+    }
+    Some(
+        1 + s[1..]
+            .iter()
+            .take_while(|b| b.is_ascii_alphanumeric() || **b == b'_')
+            .count(),
+    )
+}
+
+fn digits_len(s: &[u8]) -> usize {
+    s.iter().take_while(|b| b.is_ascii_digit()).count()
+}
+
+fn int_len(s: &[u8]) -> Option<usize> {
+    let mut i = if s.first() == Some(&b'-') { 1 } else { 0 };
+    match s.get(i)? {
+        b'0' => i += 1,
+        b'1'..=b'9' => i += 1 + digits_len(&s[i + 1..]),
+        _ => return None,
+    }
+    Some(i)
+}
+
+fn float_len(s: &[u8]) -> Option<usize> {
+    // The grammar allows a sign before the integer part, which can have a sign too.
+    let mut i = if s.first() == Some(&b'-') { 1 } else { 0 };
+    i += int_len(&s[i..])?;
+    if s.get(i) != Some(&b'.') {
+        return None;
+    }
+    i += 1;
+    let len = digits_len(&s[i..]);
+    if len == 0 {
+        return None;
+    }
+    i += len;
+
+    if let Some(b'e') | Some(b'E') = s.get(i) {
+        let mut j = i + 1;
+        if let Some(b'+') | Some(b'-') = s.get(j) {
+            j += 1;
+        }
+        let len = digits_len(&s[j..]);
+        if len > 0 {
+            i = j + len;
+        }
+    }
+    Some(i)
+}
+
+fn escape_len(s: &[u8]) -> Option<usize> {
+    match s.get(1)? {
+        b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => Some(2),
+        b'u' if s.len() >= 6 && s[2..6].iter().all(u8::is_ascii_hexdigit) => Some(6),
+        _ => None,
+    }
+}
+
+fn string_len(s: &[u8]) -> Option<usize> {
+    if s.starts_with(b"\"\"\"") {
+        if let Some(len) = block_string_len(s) {
+            return Some(len);
+        }
+    }
+
+    let mut i = 1;
+    loop {
+        match s.get(i)? {
+            b'"' => return Some(i + 1),
+            b'\\' => i += escape_len(&s[i..])?,
+            _ => i += 1,
+        }
+    }
+}
+
+fn block_string_len(s: &[u8]) -> Option<usize> {
+    let mut i = 3;
+    loop {
+        if s[i..].starts_with(b"\"\"\"") {
+            return Some(i + 3);
+        }
+        match s.get(i)? {
+            b'\\' => i += escape_len(&s[i..])?,
+            _ => i += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query_parser::parse_query_with_grammar;
+    use std::fs;
+
+    /// Check that both parsers accept the input and produce the same AST, or both reject it.
+    ///
+    /// The recovering parser must produce the same AST for valid queries, and report the error of
+    /// the parser which stops at the first error before the others for invalid queries.
+    fn check(input: &str) -> bool {
+        // The grammar-based parser panics on numbers out of range, skip them.
+        let expected = match std::panic::catch_unwind(|| {
+            parse_query_with_grammar(input)
+                .map(|doc| format!("{:?}", doc))
+                .map_err(|err| err.pos)
+        }) {
+            Ok(expected) => expected,
+            Err(_) => return false,
+        };
+        let actual = parse_query(input).map(|doc| format!("{:?}", doc));
+        assert_eq!(
+            actual.as_ref().ok(),
+            expected.as_ref().ok(),
+            "input: {:?}",
+            input
+        );
+
+        let (document, errors) = parse_query_recovering(input);
+        match expected {
+            Ok(expected) => {
+                assert_eq!(errors, Vec::new(), "input: {:?}", input);
+                assert_eq!(format!("{:?}", document), expected, "input: {:?}", input);
+
+                // The printed document is parsed to the same document, the type system
+                // definitions are not printed.
+                if document.definitions.is_empty() {
+                    return true;
+                }
+                for output in &[format!("{}", document), format!("{:#}", document)] {
+                    let reparsed = parse_query(output).expect(output);
+                    assert_eq!(
+                        reparsed.to_string(),
+                        document.to_string(),
+                        "input: {:?}",
+                        input
+                    );
+                }
+            }
+            // The grammar reports the start of the rule that failed, the hand-written parser
+            // reports the token where it failed.
+            Err(pos) => {
+                assert!(
+                    errors.first().map(|err| err.pos) >= Some(pos),
+                    "input: {:?}",
+                    input
+                );
+                assert_eq!(actual.err().as_ref(), errors.first(), "input: {:?}", input);
+                return false;
+            }
+        }
+        true
+    }
+
+    #[test]
+    fn test_queries() {
+        for entry in fs::read_dir("tests/queries").unwrap() {
+            if let Ok(entry) = entry {
+                assert!(check(&fs::read_to_string(entry.path()).unwrap()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_grammar_quirks() {
+        for input in &[
+            "{ a }",
+            "\u{feff}{ a },,,",
+            "{ a } # comment",
+            "{ a }\r\n{ b }\r{ c }",
+            "# comment\r{ a }",
+            "{ a: b }",
+            "{ a : b c:d }",
+            "queryFoo { a }",
+            "query{a}",
+            "querying { a }",
+            "mutation($a:Int=1$b:[Int!]!=[1,2]){a}",
+            "subscription Foo @a @b(c: 1) { a }",
+            "query ( ) { a }",
+            "query ($ a : Int) { a }",
+            "query ($a: Int!!) { a }",
+            "query ($a: [Int]) { a(b: $ a) }",
+            "{ a(b: 1.5e10, c: -0.5, d: 2.5E-3, e: 1.5e, f: 0123, g: 1e5) }",
+            "{ a(b: [1.5e10 -0 00.5 1abc]) }",
+            "{ a(b: truex c: nullable) }",
+            "{ a(b: [truex nullable falsetrue]) }",
+            "{ a(b: true, c: false, d: null, e: ENUM) }",
+            "{ a(b: {c: 1, c: 2, d: {e: [f]}}) }",
+            "{ a(b: {}, c: []) }",
+            "{ a() }",
+            "{ a(b: \"\") }",
+            "{ a(b: \"\"\"\"\"\") }",
+            "{ a(b: \"\"\"\"\"\"\") }",
+            "{ a(b: \"a\\\"b\\\\c\\/\\b\\f\\n\\r\\t\\u00e9\") }",
+            "{ a(b: \"\\uD800\") }",
+            "{ a(b: \"\\x\") }",
+            "{ a(b: \"\\u12\") }",
+            "{ a(b: \"multi\nline\") }",
+            "{ a(b: \"\"\"block \"quoted\" \\\"\"\" text\"\"\") }",
+            "{ a(b: \"\"\"block \\x\"\"\") }",
+            "{ a(b: \"unterminated) }",
+            "{ a @ skip(if: true) }",
+            "{ ... on Foo { a } }",
+            "{ ...onFoo { a } }",
+            "{ ...onFoo }",
+            "{ ...on }",
+            "{ ...on @a }",
+            "{ ...on @a { b } }",
+            "{ ... @a { b } }",
+            "{ ... { b } }",
+            "{ ...Foo @a @b }",
+            "{ . . . Foo }",
+            "fragment Foo on Bar { a }",
+            "fragmentFoo onBar { a }",
+            "fragment on on on { a }",
+            "fragment Foo { a }",
+            "{ a } fragment Foo on Bar { ...Foo }",
+            "{ a { } }",
+            "{ }",
+            "",
+            "   ",
+            "{ a",
+            "{ a } }",
+            "{ a } query",
+            "{ a(b: $) }",
+            "{ a(b: -) }",
+            "{ a(b: --1) }",
+            "{ _a1 __typename }",
+            "{ 1a }",
+            "{ a(b: 1)(c: 2) }",
+            "{ a @b { c } @d }",
+            "{ a(b: {c: {d: 1, d: 2}, c: 3, e: [{f: 1, f: 2}]}) }",
+            "query ($a: In = {b: 1, b: 2}) @c(d: {e: 1, e: 2}) { a }",
+            "schema { query: Query mutation: Mutation }",
+            "schema @a { }",
+            "schema { a }",
+            "extend schema @a",
+            "extend schema @a { a }",
+            "scalar Date @a(b: {c: 1, c: 2})",
+            "\"Description\" type Foo implements & Bar & Baz @a { a(b: Int = 1 @c): [Int!]! @d }",
+            "\"\"\"Block\"\"\" interface Foo implements Bar { \"a\" a: Int }",
+            "type Foo { }",
+            "type Foo { a }",
+            "type Foo implements { a: Int }",
+            "type Foo union Bar = | A | B enum C { D E @f } input G { h: Int = {i: 1, i: 2} }",
+            "union Foo { a }",
+            "union Foo = | { a }",
+            "typeFoo{a:Int}inputBar{a:Int}",
+            "directive @a(b: Int) repeatable on | FIELD | QUERY",
+            "directive @a on FIELD { a }",
+            "directive @a",
+            "extend directive @a on FIELD",
+            "extend type Foo { a: Int } extend input Bar @a",
+            "\"Description\" extend type Foo",
+            "\"Description\" { a }",
+            "{ a } type Foo @a(b: ) { a: Int } { b }",
+        ] {
+            check(input);
+        }
+    }
+
+    struct Generator {
+        state: u64,
+        out: String,
+    }
+
+    impl Generator {
+        fn next(&mut self, n: usize) -> usize {
+            // xorshift64
+            self.state ^= self.state << 13;
+            self.state ^= self.state >> 7;
+            self.state ^= self.state << 17;
+            (self.state % n as u64) as usize
+        }
+
+        fn coin(&mut self) -> bool {
+            self.next(2) == 0
+        }
+
+        fn pick(&mut self, items: &[&str]) -> String {
+            items[self.next(items.len())].to_string()
+        }
+
+        fn token(&mut self, token: &str) {
+            self.out.push_str(token);
+            let sep = self.pick(&[
+                " ",
+                " ",
+                " ",
+                "\n",
+                ",",
+                "\t",
+                "\r\n",
+                " # comment\n",
+                "\u{feff}",
+                "",
+            ]);
+            self.out.push_str(&sep);
+        }
+
+        fn name(&mut self) {
+            let name = self.pick(&[
+                "a", "b", "id", "on", "query", "fragment", "_x1", "true", "nullable", "onFoo",
+                "Type",
+            ]);
+            self.token(&name);
+        }
+
+        fn value(&mut self, depth: usize) {
+            match self.next(10) {
+                0 => {
+                    let value = self.pick(&["0", "-1", "42", "-0", "007"]);
+                    self.token(&value);
+                }
+                1 => {
+                    let value = self.pick(&["1.5", "-0.25", "3.0e10", "2.5E-3", "1.0e", "1e5"]);
+                    self.token(&value);
+                }
+                2 => {
+                    let value = self.pick(&[
+                        r#""abc""#,
+                        r#""a\"b\\c\u00e9""#,
+                        r#""""block "quoted" """"#,
+                        r#""""#,
+                        r#""""""""#,
+                    ]);
+                    self.token(&value);
+                }
+                3 => {
+                    let value = self.pick(&["true", "false", "null"]);
+                    self.token(&value);
+                }
+                4 => {
+                    self.token("$");
+                    self.name();
+                }
+                5 if depth > 0 => {
+                    self.token("[");
+                    for _ in 0..self.next(3) {
+                        self.value(depth - 1);
+                    }
+                    self.token("]");
+                }
+                6 if depth > 0 => {
+                    self.token("{");
+                    for _ in 0..self.next(3) {
+                        self.name();
+                        self.token(":");
+                        self.value(depth - 1);
+                    }
+                    self.token("}");
+                }
+                _ => self.name(),
+            }
+        }
+
+        fn arguments(&mut self) {
+            self.token("(");
+            for _ in 0..self.next(3) {
+                self.name();
+                self.token(":");
+                self.value(2);
+            }
+            self.token(")");
+        }
+
+        fn directives(&mut self) {
+            while self.next(3) == 0 {
+                self.token("@");
+                self.name();
+                if self.coin() {
+                    self.arguments();
+                }
+            }
+        }
+
+        fn selection_set(&mut self, depth: usize) {
+            self.token("{");
+            for _ in 0..1 + self.next(3) {
+                match self.next(5) {
+                    0 => {
+                        self.token("...");
+                        self.name();
+                        self.directives();
+                    }
+                    1 => {
+                        self.token("...");
+                        if self.coin() {
+                            self.token("on");
+                            self.name();
+                        }
+                        self.directives();
+                        self.selection_set(depth.saturating_sub(1));
+                    }
+                    _ => {
+                        if self.coin() {
+                            self.name();
+                            self.token(":");
+                        }
+                        self.name();
+                        if self.coin() {
+                            self.arguments();
+                        }
+                        self.directives();
+                        if depth > 0 && self.coin() {
+                            self.selection_set(depth - 1);
+                        }
+                    }
+                }
+            }
+            self.token("}");
+        }
+
+        fn ty(&mut self, depth: usize) {
+            if depth > 0 && self.coin() {
+                self.token("[");
+                self.ty(depth - 1);
+                self.token("]");
+            } else {
+                self.name();
+            }
+            if self.coin() {
+                self.token("!");
+            }
+        }
+
+        fn description(&mut self) {
+            if self.coin() {
+                let value = self.pick(&[r#""abc""#, r#""""block "quoted" """"#, r#""\x""#]);
+                self.token(&value);
+            }
+        }
+
+        fn input_values(&mut self, open: &str, close: &str) {
+            self.token(open);
+            for _ in 0..1 + self.next(2) {
+                self.description();
+                self.name();
+                self.token(":");
+                self.ty(1);
+                if self.coin() {
+                    self.token("=");
+                    self.value(1);
+                }
+                self.directives();
+            }
+            self.token(close);
+        }
+
+        fn type_system_definition(&mut self) {
+            let extend = self.coin();
+            if extend {
+                self.token("extend");
+            } else {
+                self.description();
+            }
+            match self.next(6) {
+                0 => {
+                    self.token("schema");
+                    self.directives();
+                    if !extend || self.coin() {
+                        self.token("{");
+                        for _ in 0..1 + self.next(2) {
+                            let ty = self.pick(&["query", "mutation", "subscription"]);
+                            self.token(&ty);
+                            self.token(":");
+                            self.name();
+                        }
+                        self.token("}");
+                    }
+                }
+                1 => {
+                    let keyword = self.pick(&["type", "interface"]);
+                    self.token(&keyword);
+                    self.name();
+                    if self.coin() {
+                        self.token("implements");
+                        for i in 0..1 + self.next(2) {
+                            if i > 0 || self.coin() {
+                                self.token("&");
+                            }
+                            self.name();
+                        }
+                    }
+                    self.directives();
+                    if self.coin() {
+                        self.token("{");
+                        for _ in 0..1 + self.next(2) {
+                            self.description();
+                            self.name();
+                            if self.coin() {
+                                self.input_values("(", ")");
+                            }
+                            self.token(":");
+                            self.ty(2);
+                            self.directives();
+                        }
+                        self.token("}");
+                    }
+                }
+                2 => {
+                    self.token("union");
+                    self.name();
+                    self.directives();
+                    if self.coin() {
+                        self.token("=");
+                        for i in 0..1 + self.next(2) {
+                            if i > 0 || self.coin() {
+                                self.token("|");
+                            }
+                            self.name();
+                        }
+                    }
+                }
+                3 => {
+                    self.token("enum");
+                    self.name();
+                    self.directives();
+                    if self.coin() {
+                        self.token("{");
+                        for _ in 0..1 + self.next(2) {
+                            self.description();
+                            self.name();
+                            self.directives();
+                        }
+                        self.token("}");
+                    }
+                }
+                4 => {
+                    let keyword = self.pick(&["scalar", "input"]);
+                    self.token(&keyword);
+                    self.name();
+                    self.directives();
+                    if keyword == "input" && self.coin() {
+                        self.input_values("{", "}");
+                    }
+                }
+                _ => {
+                    self.token("directive");
+                    self.token("@");
+                    self.name();
+                    if self.coin() {
+                        self.input_values("(", ")");
+                    }
+                    if self.coin() {
+                        self.token("repeatable");
+                    }
+                    self.token("on");
+                    for i in 0..1 + self.next(2) {
+                        if i > 0 || self.coin() {
+                            self.token("|");
+                        }
+                        self.name();
+                    }
+                }
+            }
+        }
+
+        fn document(&mut self) {
+            self.out.clear();
+            for _ in 0..1 + self.next(2) {
+                match self.next(4) {
+                    3 => self.type_system_definition(),
+                    0 => self.selection_set(2),
+                    1 => {
+                        let ty = self.pick(&["query", "mutation", "subscription"]);
+                        self.token(&ty);
+                        if self.coin() {
+                            self.name();
+                        }
+                        if self.coin() {
+                            self.token("(");
+                            for _ in 0..self.next(3) {
+                                self.token("$");
+                                self.name();
+                                self.token(":");
+                                self.ty(2);
+                                if self.coin() {
+                                    self.token("=");
+                                    self.value(1);
+                                }
+                            }
+                            self.token(")");
+                        }
+                        self.directives();
+                        self.selection_set(2);
+                    }
+                    _ => {
+                        self.token("fragment");
+                        self.name();
+                        self.token("on");
+                        self.name();
+                        self.directives();
+                        self.selection_set(2);
+                    }
+                }
+            }
+        }
+
+        fn mutate(&mut self, input: &str) -> String {
+            let mut chars: Vec<char> = input.chars().collect();
+            for _ in 0..1 + self.next(2) {
+                let idx = self.next(chars.len() + 1);
+                match self.next(3) {
+                    0 if idx < chars.len() => {
+                        chars.remove(idx);
+                    }
+                    1 if idx < chars.len() => {
+                        let c = chars[idx];
+                        chars.insert(idx, c);
+                    }
+                    _ => {
+                        let c = self.pick(&[
+                            "{", "}", "(", ")", "[", "]", ":", "$", "@", ".", "!", "=", "\"", "#",
+                            "\n", "a", "1", "\\", "e",
+                        ]);
+                        chars.insert(idx, c.chars().next().unwrap());
+                    }
+                }
+            }
+            chars.into_iter().collect()
+        }
+    }
+
+    #[test]
+    fn test_generated_queries() {
+        let mut generator = Generator {
+            state: 0x2545_f491_4f6c_dd1d,
+            out: String::new(),
+        };
+        let mut valid = 0;
+        for _ in 0..2000 {
+            generator.document();
+            let document = generator.out.clone();
+            if check(&document) {
+                valid += 1;
+            }
+            for _ in 0..5 {
+                let mutated = generator.mutate(&document);
+                check(&mutated);
+            }
+        }
+        // Make sure that the generated queries are not mostly rejected.
+        assert!(valid > 1000, "only {} valid queries", valid);
+    }
+}
@@ -8,15 +8,22 @@ extern crate thiserror;
 pub mod query;
 pub mod schema;
 
+mod descent_parser;
 mod error;
+mod normalize;
+mod options;
 mod pos;
+mod printer;
 mod query_parser;
 mod schema_parser;
 mod utils;
 mod value;
 
 pub use error::{Error, Result};
-pub use pos::{Pos, Positioned};
-pub use query_parser::parse_query;
+pub use options::ParserOptions;
+pub use pos::{Pos, Positioned, Span};
+pub use query_parser::{
+    parse_query, parse_query_recovering, parse_query_with_grammar, parse_query_with_options,
+};
 pub use schema_parser::parse_schema;
-pub use value::{UploadValue, Value};
+pub use value::{UploadContent, UploadValue, Value};
@@ -0,0 +1,314 @@
+use crate::pos::Positioned;
+use crate::query::*;
+use crate::value::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+impl Document {
+    /// Normalize an operation of the document.
+    ///
+    /// The normalized document has the operation followed by the fragments it uses sorted by
+    /// name. The aliases are removed, the selections, arguments and variable definitions are
+    /// sorted, and the literals are replaced by empty values, so that queries which only differ
+    /// in these details are normalized to the same document.
+    ///
+    /// The operation is selected like `retain_operation` does, returns `None` if it is not found.
+    pub fn normalize(&self, operation_name: Option<&str>) -> Option<Document> {
+        let fragments = self
+            .definitions
+            .iter()
+            .filter_map(|definition| match &definition.node {
+                Definition::Fragment(fragment) => Some((fragment.name.as_str(), fragment)),
+                Definition::Operation(_) => None,
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let operation = self.definitions.iter().find_map(|definition| {
+            let operation = match &definition.node {
+                Definition::Operation(operation) => operation,
+                Definition::Fragment(_) => return None,
+            };
+            let name = match &operation.node {
+                OperationDefinition::SelectionSet(_) => None,
+                OperationDefinition::Query(query) => query.name.as_ref(),
+                OperationDefinition::Mutation(mutation) => mutation.name.as_ref(),
+                OperationDefinition::Subscription(subscription) => subscription.name.as_ref(),
+            };
+            match (name, operation_name) {
+                (Some(name), Some(operation_name)) if name.node != operation_name => None,
+                _ => Some((definition, operation)),
+            }
+        });
+        let (definition, operation) = operation?;
+
+        let mut used_fragments = BTreeSet::new();
+        let operation = Positioned::new(
+            match &operation.node {
+                OperationDefinition::SelectionSet(selection_set) => {
+                    OperationDefinition::SelectionSet(normalize_selection_set(
+                        selection_set,
+                        &mut used_fragments,
+                    ))
+                }
+                OperationDefinition::Query(query) => OperationDefinition::Query(Positioned::new(
+                    Query {
+                        name: query.name.clone(),
+                        variable_definitions: normalize_variable_definitions(
+                            &query.variable_definitions,
+                        ),
+                        directives: normalize_directives(&query.directives),
+                        selection_set: normalize_selection_set(
+                            &query.selection_set,
+                            &mut used_fragments,
+                        ),
+                    },
+                    query.span(),
+                )),
+                OperationDefinition::Mutation(mutation) => {
+                    OperationDefinition::Mutation(Positioned::new(
+                        Mutation {
+                            name: mutation.name.clone(),
+                            variable_definitions: normalize_variable_definitions(
+                                &mutation.variable_definitions,
+                            ),
+                            directives: normalize_directives(&mutation.directives),
+                            selection_set: normalize_selection_set(
+                                &mutation.selection_set,
+                                &mut used_fragments,
+                            ),
+                        },
+                        mutation.span(),
+                    ))
+                }
+                OperationDefinition::Subscription(subscription) => {
+                    OperationDefinition::Subscription(Positioned::new(
+                        Subscription {
+                            name: subscription.name.clone(),
+                            variable_definitions: normalize_variable_definitions(
+                                &subscription.variable_definitions,
+                            ),
+                            directives: normalize_directives(&subscription.directives),
+                            selection_set: normalize_selection_set(
+                                &subscription.selection_set,
+                                &mut used_fragments,
+                            ),
+                        },
+                        subscription.span(),
+                    ))
+                }
+            },
+            operation.span(),
+        );
+
+        // The fragments can use other fragments.
+        let mut normalized_fragments = Vec::new();
+        let mut visited = BTreeSet::new();
+        let mut pending = used_fragments.iter().cloned().collect::<Vec<_>>();
+        while let Some(name) = pending.pop() {
+            let fragment = match fragments.get(name.as_str()) {
+                Some(fragment) if visited.insert(name) => fragment,
+                _ => continue,
+            };
+            let mut used_fragments = BTreeSet::new();
+            let normalized = Positioned::new(
+                FragmentDefinition {
+                    name: fragment.name.clone(),
+                    type_condition: fragment.type_condition.clone(),
+                    directives: normalize_directives(&fragment.directives),
+                    selection_set: normalize_selection_set(
+                        &fragment.selection_set,
+                        &mut used_fragments,
+                    ),
+                },
+                fragment.span(),
+            );
+            normalized_fragments.push(normalized);
+            pending.extend(used_fragments);
+        }
+        normalized_fragments.sort_by(|a, b| a.name.node.cmp(&b.name.node));
+
+        let mut definitions = vec![Positioned::new(
+            Definition::Operation(operation),
+            definition.span(),
+        )];
+        definitions.extend(normalized_fragments.into_iter().map(|fragment| {
+            let span = fragment.span();
+            Positioned::new(Definition::Fragment(fragment), span)
+        }));
+
+        Some(Document {
+            definitions,
+            fragments: Default::default(),
+            current_operation: None,
+            type_system_definitions: Vec::new(),
+            duplicate_input_fields: Vec::new(),
+        })
+    }
+
+    /// Returns the signature of an operation of the document, which is the minified text of the
+    /// normalized operation.
+    ///
+    /// The signature is stable for queries which only differ in formatting, aliases, literals
+    /// and the order of the fields and arguments, it can be used as the label of metrics or hashed
+    /// to identify the query.
+    pub fn operation_signature(&self, operation_name: Option<&str>) -> Option<String> {
+        self.normalize(operation_name)
+            .map(|document| document.to_string())
+    }
+}
+
+fn normalize_selection_set(
+    selection_set: &Positioned<SelectionSet>,
+    used_fragments: &mut BTreeSet<String>,
+) -> Positioned<SelectionSet> {
+    let mut items = selection_set
+        .items
+        .iter()
+        .map(|selection| {
+            let node = match &selection.node {
+                Selection::Field(field) => Selection::Field(Positioned::new(
+                    Field {
+                        alias: None,
+                        name: field.name.clone(),
+                        arguments: normalize_arguments(&field.arguments),
+                        directives: normalize_directives(&field.directives),
+                        selection_set: normalize_selection_set(
+                            &field.selection_set,
+                            used_fragments,
+                        ),
+                    },
+                    field.span(),
+                )),
+                Selection::FragmentSpread(fragment_spread) => {
+                    used_fragments.insert(fragment_spread.fragment_name.clone_inner());
+                    Selection::FragmentSpread(Positioned::new(
+                        FragmentSpread {
+                            fragment_name: fragment_spread.fragment_name.clone(),
+                            directives: normalize_directives(&fragment_spread.directives),
+                        },
+                        fragment_spread.span(),
+                    ))
+                }
+                Selection::InlineFragment(inline_fragment) => {
+                    Selection::InlineFragment(Positioned::new(
+                        InlineFragment {
+                            type_condition: inline_fragment.type_condition.clone(),
+                            directives: normalize_directives(&inline_fragment.directives),
+                            selection_set: normalize_selection_set(
+                                &inline_fragment.selection_set,
+                                used_fragments,
+                            ),
+                        },
+                        inline_fragment.span(),
+                    ))
+                }
+            };
+            Positioned::new(node, selection.span())
+        })
+        .collect::<Vec<_>>();
+    items.sort_by_cached_key(|selection| selection.to_string());
+    Positioned::new(SelectionSet { items }, selection_set.span())
+}
+
+fn normalize_variable_definitions(
+    variable_definitions: &[Positioned<VariableDefinition>],
+) -> Vec<Positioned<VariableDefinition>> {
+    let mut variable_definitions = variable_definitions
+        .iter()
+        .map(|var| {
+            Positioned::new(
+                VariableDefinition {
+                    name: var.name.clone(),
+                    var_type: var.var_type.clone(),
+                    default_value: var
+                        .default_value
+                        .as_ref()
+                        .map(|value| Positioned::new(hide_literals(value), value.span())),
+                },
+                var.span(),
+            )
+        })
+        .collect::<Vec<_>>();
+    variable_definitions.sort_by(|a, b| a.name.node.cmp(&b.name.node));
+    variable_definitions
+}
+
+fn normalize_directives(directives: &[Positioned<Directive>]) -> Vec<Positioned<Directive>> {
+    directives
+        .iter()
+        .map(|directive| {
+            Positioned::new(
+                Directive {
+                    name: directive.name.clone(),
+                    arguments: normalize_arguments(&directive.arguments),
+                },
+                directive.span(),
+            )
+        })
+        .collect()
+}
+
+fn normalize_arguments(
+    arguments: &[(Positioned<String>, Positioned<Value>)],
+) -> Vec<(Positioned<String>, Positioned<Value>)> {
+    let mut arguments = arguments
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.clone(),
+                Positioned::new(hide_literals(value), value.span()),
+            )
+        })
+        .collect::<Vec<_>>();
+    arguments.sort_by(|a, b| a.0.node.cmp(&b.0.node));
+    arguments
+}
+
+/// Replace the numbers, strings, lists and objects by empty values, the variables, enums and
+/// booleans are kept.
+fn hide_literals(value: &Value) -> Value {
+    match value {
+        Value::Int(_) => Value::Int(0),
+        Value::Float(_) => Value::Float(0.0),
+        Value::String(_) => Value::String(String::new()),
+        Value::List(_) => Value::List(Vec::new()),
+        Value::Object(_) => Value::Object(Default::default()),
+        Value::Upload(_) => Value::Null,
+        value => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_query;
+
+    #[test]
+    fn test_operation_signature() {
+        let a = parse_query(
+            r#"query Q($b: Int, $a: String = "x") {
+                user(id: 1, name: "abc") { ...F friends: users(first: 10) { id } }
+                other
+            }
+            fragment F on User { name ...G }
+            fragment G on User { email }
+            fragment Unused on User { id }"#,
+        )
+        .unwrap();
+        let b = parse_query(
+            r#"fragment G on User { email }
+            query Q($a: String = "y", $b: Int) {
+                other
+                user(name: "def", id: 2) { users(first: 5) { id } ...F }
+            }
+            fragment F on User { ...G, name }"#,
+        )
+        .unwrap();
+
+        let signature = a.operation_signature(None).unwrap();
+        assert_eq!(
+            signature,
+            r#"query Q($a:String="",$b:Int){other user(id:0,name:""){...F users(first:0){id}}} fragment F on User{...G name} fragment G on User{email}"#
+        );
+        assert_eq!(b.operation_signature(Some("Q")).unwrap(), signature);
+        assert!(a.operation_signature(Some("Other")).is_none());
+    }
+}
@@ -0,0 +1,250 @@
+use crate::query::{Definition, Document, OperationDefinition, Selection, SelectionSet};
+use crate::{Error, Pos, Result};
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Limits of the parser
+///
+/// The default options have no limits.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ParserOptions {
+    /// Maximum nesting depth of the selection sets, arguments, lists and objects.
+    pub max_depth: Option<usize>,
+
+    /// Maximum number of tokens, whitespace, commas and comments are not counted.
+    pub max_tokens: Option<usize>,
+
+    /// Maximum number of aliases in the document.
+    pub max_aliases: Option<usize>,
+
+    /// Maximum number of directives of a field.
+    pub max_directives_per_field: Option<usize>,
+}
+
+/// Check the depth and the number of tokens before parsing, so that a crafted query can not cause
+/// deep recursion or heavy CPU use in the parser.
+pub(crate) fn check_tokens(input: &str, options: &ParserOptions) -> Result<()> {
+    if options.max_depth.is_none() && options.max_tokens.is_none() {
+        return Ok(());
+    }
+
+    let mut lexer = Lexer {
+        input: input.chars().peekable(),
+        line: 1,
+        column: 1,
+    };
+    let mut depth = 0;
+    let mut tokens = 0;
+
+    while let Some((pos, c)) = lexer.next_token() {
+        tokens += 1;
+        if let Some(max_tokens) = options.max_tokens {
+            if tokens > max_tokens {
+                return Err(Error {
+                    pos,
+                    message: format!(
+                        "The query has too many tokens, the maximum is {}",
+                        max_tokens
+                    ),
+                });
+            }
+        }
+
+        match c {
+            '{' | '[' | '(' => {
+                depth += 1;
+                if let Some(max_depth) = options.max_depth {
+                    if depth > max_depth {
+                        return Err(Error {
+                            pos,
+                            message: format!(
+                                "The query is nested too deeply, the maximum depth is {}",
+                                max_depth
+                            ),
+                        });
+                    }
+                }
+            }
+            '}' | ']' | ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Check the number of aliases and directives of the parsed document.
+pub(crate) fn check_document(document: &Document, options: &ParserOptions) -> Result<()> {
+    if options.max_aliases.is_none() && options.max_directives_per_field.is_none() {
+        return Ok(());
+    }
+
+    let mut aliases = 0;
+    for definition in document.definitions() {
+        let selection_set = match &definition.node {
+            Definition::Operation(operation) => match &operation.node {
+                OperationDefinition::SelectionSet(selection_set) => selection_set,
+                OperationDefinition::Query(query) => &query.selection_set,
+                OperationDefinition::Mutation(mutation) => &mutation.selection_set,
+                OperationDefinition::Subscription(subscription) => &subscription.selection_set,
+            },
+            Definition::Fragment(fragment) => &fragment.selection_set,
+        };
+        check_selection_set(selection_set, options, &mut aliases)?;
+    }
+    Ok(())
+}
+
+fn check_selection_set(
+    selection_set: &SelectionSet,
+    options: &ParserOptions,
+    aliases: &mut usize,
+) -> Result<()> {
+    for selection in &selection_set.items {
+        match &selection.node {
+            Selection::Field(field) => {
+                if let Some(alias) = &field.alias {
+                    *aliases += 1;
+                    if let Some(max_aliases) = options.max_aliases {
+                        if *aliases > max_aliases {
+                            return Err(Error {
+                                pos: alias.position(),
+                                message: format!(
+                                    "The query has too many aliases, the maximum is {}",
+                                    max_aliases
+                                ),
+                            });
+                        }
+                    }
+                }
+                if let Some(max_directives) = options.max_directives_per_field {
+                    if field.directives.len() > max_directives {
+                        return Err(Error {
+                            pos: field.directives[max_directives].position(),
+                            message: format!(
+                                "The field \"{}\" has too many directives, the maximum is {}",
+                                field.name.node, max_directives
+                            ),
+                        });
+                    }
+                }
+                check_selection_set(&field.selection_set, options, aliases)?;
+            }
+            Selection::InlineFragment(inline_fragment) => {
+                check_selection_set(&inline_fragment.selection_set, options, aliases)?;
+            }
+            Selection::FragmentSpread(_) => {}
+        }
+    }
+    Ok(())
+}
+
+struct Lexer<'a> {
+    input: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn bump(&mut self) -> Option<char> {
+        let c = self.input.next()?;
+        match c {
+            '\n' => {
+                self.line += 1;
+                self.column = 1;
+            }
+            '\r' => {
+                if self.input.peek() != Some(&'\n') {
+                    self.line += 1;
+                    self.column = 1;
+                }
+            }
+            _ => self.column += 1,
+        }
+        Some(c)
+    }
+
+    fn bump_while(&mut self, f: impl Fn(char) -> bool) {
+        while let Some(&c) = self.input.peek() {
+            if !f(c) {
+                break;
+            }
+            self.bump();
+        }
+    }
+
+    /// Skip to the next token, returns its position and first character.
+    ///
+    /// This is only an approximation of the GraphQL lexer, which is good enough for counting
+    /// tokens and nesting, the syntax is checked by the parser.
+    fn next_token(&mut self) -> Option<(Pos, char)> {
+        loop {
+            let c = *self.input.peek()?;
+            match c {
+                ' ' | '\t' | '\n' | '\r' | ',' | '\u{feff}' => {
+                    self.bump();
+                }
+                '#' => self.bump_while(|c| c != '\n' && c != '\r'),
+                _ => break,
+            }
+        }
+
+        let pos = Pos {
+            line: self.line,
+            column: self.column,
+        };
+        let c = self.bump()?;
+        match c {
+            '"' => self.skip_string(),
+            '.' => self.bump_while(|c| c == '.'),
+            c if c.is_ascii_alphanumeric() || c == '_' || c == '-' => self.bump_while(|c| {
+                c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '+' || c == '-'
+            }),
+            _ => {}
+        }
+        Some((pos, c))
+    }
+
+    fn skip_string(&mut self) {
+        let mut quotes = 1;
+        while quotes < 3 && self.input.peek() == Some(&'"') {
+            self.bump();
+            quotes += 1;
+        }
+
+        match quotes {
+            // Empty string
+            2 => {}
+            // Block string
+            3 => {
+                let mut closing = 0;
+                while let Some(c) = self.bump() {
+                    match c {
+                        '"' => {
+                            closing += 1;
+                            if closing == 3 {
+                                break;
+                            }
+                        }
+                        '\\' => {
+                            closing = 0;
+                            self.bump();
+                        }
+                        _ => closing = 0,
+                    }
+                }
+            }
+            _ => {
+                while let Some(c) = self.bump() {
+                    match c {
+                        '"' | '\n' | '\r' => break,
+                        '\\' => {
+                            self.bump();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+}
@@ -26,11 +26,43 @@ impl fmt::Display for Pos {
     }
 }
 
+/// Range of an element in source code
+#[derive(PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Default, Hash)]
+pub struct Span {
+    /// Position of the first character
+    pub start: Pos,
+
+    /// Position right after the last character
+    pub end: Pos,
+
+    /// Zero-based byte offset of the first character
+    pub start_offset: usize,
+
+    /// Zero-based byte offset right after the last character
+    pub end_offset: usize,
+}
+
+impl fmt::Debug for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Span({}:{}-{}:{})",
+            self.start.line, self.start.column, self.end.line, self.end.column
+        )
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
 /// Represents the position of a AST node
 #[derive(Clone, Debug, Copy, Default)]
 #[allow(missing_docs)]
 pub struct Positioned<T: ?Sized> {
-    pub pos: Pos,
+    pub span: Span,
     pub node: T,
 }
 
@@ -101,8 +133,8 @@ impl BorrowMut<str> for Positioned<String> {
 }
 
 impl<T> Positioned<T> {
-    pub(crate) fn new(node: T, pos: Pos) -> Positioned<T> {
-        Positioned { node, pos }
+    pub(crate) fn new(node: T, span: Span) -> Positioned<T> {
+        Positioned { span, node }
     }
 
     #[inline]
@@ -113,13 +145,19 @@ impl<T> Positioned<T> {
     /// Get start position
     #[inline]
     pub fn position(&self) -> Pos {
-        self.pos
+        self.span.start
+    }
+
+    /// Get the range in source code
+    #[inline]
+    pub fn span(&self) -> Span {
+        self.span
     }
 
     #[inline]
     pub(crate) fn pack<F: FnOnce(Self) -> R, R>(self, f: F) -> Positioned<R> {
         Positioned {
-            pos: self.pos,
+            span: self.span,
             node: f(self),
         }
     }
@@ -0,0 +1,658 @@
+//! Print the query and schema documents as GraphQL text.
+//!
+//! `{}` prints the minified form, `{:#}` prints the pretty form with one selection or field per
+//! line.
+
+use crate::pos::Positioned;
+use crate::query::{self, *};
+use crate::schema;
+use crate::value::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub(crate) struct Printer<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    pretty: bool,
+    indent: usize,
+}
+
+impl<'a, 'b> Printer<'a, 'b> {
+    pub(crate) fn new(f: &'a mut fmt::Formatter<'b>, pretty: bool) -> Self {
+        Printer {
+            f,
+            pretty,
+            indent: 0,
+        }
+    }
+
+    fn write(&mut self, s: &str) -> fmt::Result {
+        self.f.write_str(s)
+    }
+
+    /// Write `pretty` in the pretty form, `minified` otherwise.
+    fn write_either(&mut self, pretty: &str, minified: &str) -> fmt::Result {
+        if self.pretty {
+            self.write(pretty)
+        } else {
+            self.write(minified)
+        }
+    }
+
+    fn newline(&mut self) -> fmt::Result {
+        write!(self.f, "\n{:1$}", "", self.indent * 2)
+    }
+
+    /// Write the items in braces, one per line in the pretty form.
+    fn block<T>(
+        &mut self,
+        items: &[T],
+        mut f: impl FnMut(&mut Self, &T) -> fmt::Result,
+    ) -> fmt::Result {
+        self.write("{")?;
+        self.indent += 1;
+        for (idx, item) in items.iter().enumerate() {
+            if self.pretty {
+                self.newline()?;
+            } else if idx > 0 {
+                self.write(" ")?;
+            }
+            f(self, item)?;
+        }
+        self.indent -= 1;
+        if self.pretty && !items.is_empty() {
+            self.newline()?;
+        }
+        self.write("}")
+    }
+
+    /// Write the items in parentheses separated by commas.
+    fn list<T>(
+        &mut self,
+        open: &str,
+        items: &[T],
+        close: &str,
+        mut f: impl FnMut(&mut Self, &T) -> fmt::Result,
+    ) -> fmt::Result {
+        self.write(open)?;
+        for (idx, item) in items.iter().enumerate() {
+            if idx > 0 {
+                self.write_either(", ", ",")?;
+            }
+            f(self, item)?;
+        }
+        self.write(close)
+    }
+
+    pub(crate) fn value(&mut self, value: &Value) -> fmt::Result {
+        match value {
+            Value::Variable(name) => write!(self.f, "${}", name),
+            Value::Int(n) => write!(self.f, "{}", n),
+            Value::Float(n) => self.float(*n),
+            Value::String(s) => self.string(s),
+            Value::Boolean(b) => write!(self.f, "{}", b),
+            Value::Null | Value::Upload(_) => self.write("null"),
+            Value::Enum(name) => self.write(name),
+            Value::List(items) => self.list("[", items, "]", Self::value),
+            Value::Object(map) => self.object(map, Self::value),
+        }
+    }
+
+    fn schema_value(&mut self, value: &schema::Value) -> fmt::Result {
+        match value {
+            schema::Value::Int(n) => write!(self.f, "{}", n),
+            schema::Value::Float(n) => self.float(*n),
+            schema::Value::String(s) => self.string(s),
+            schema::Value::Boolean(b) => write!(self.f, "{}", b),
+            schema::Value::Null => self.write("null"),
+            schema::Value::Enum(name) => self.write(name),
+            schema::Value::List(items) => self.list("[", items, "]", Self::schema_value),
+            schema::Value::Object(map) => self.object(map, Self::schema_value),
+        }
+    }
+
+    fn object<T>(
+        &mut self,
+        map: &BTreeMap<String, T>,
+        mut f: impl FnMut(&mut Self, &T) -> fmt::Result,
+    ) -> fmt::Result {
+        let items = map.iter().collect::<Vec<_>>();
+        self.list("{", &items, "}", |p, (name, value)| {
+            p.write(name)?;
+            p.write_either(": ", ":")?;
+            f(p, value)
+        })
+    }
+
+    fn float(&mut self, n: f64) -> fmt::Result {
+        // A float without a fractional part would be parsed as an integer.
+        let s = n.to_string();
+        if s.contains('.') || !n.is_finite() {
+            self.write(&s)
+        } else {
+            write!(self.f, "{}.0", s)
+        }
+    }
+
+    fn string(&mut self, s: &str) -> fmt::Result {
+        self.write("\"")?;
+        for c in s.chars() {
+            match c {
+                '"' => self.write("\\\"")?,
+                '\\' => self.write("\\\\")?,
+                '\n' => self.write("\\n")?,
+                '\r' => self.write("\\r")?,
+                '\t' => self.write("\\t")?,
+                c if c < ' ' => write!(self.f, "\\u{:04x}", c as u32)?,
+                c => write!(self.f, "{}", c)?,
+            }
+        }
+        self.write("\"")
+    }
+
+    fn arguments(&mut self, arguments: &[(Positioned<String>, Positioned<Value>)]) -> fmt::Result {
+        if arguments.is_empty() {
+            return Ok(());
+        }
+        self.list("(", arguments, ")", |p, (name, value)| {
+            p.write(name)?;
+            p.write_either(": ", ":")?;
+            p.value(value)
+        })
+    }
+
+    fn directive(&mut self, directive: &query::Directive) -> fmt::Result {
+        write!(self.f, "@{}", directive.name)?;
+        self.arguments(&directive.arguments)
+    }
+
+    fn directives(&mut self, directives: &[Positioned<query::Directive>]) -> fmt::Result {
+        for directive in directives {
+            self.write_either(" ", "")?;
+            self.directive(directive)?;
+        }
+        Ok(())
+    }
+
+    fn document(&mut self, document: &query::Document) -> fmt::Result {
+        for (idx, definition) in document.definitions.iter().enumerate() {
+            if idx > 0 {
+                self.write_either("\n\n", " ")?;
+            }
+            self.definition(definition)?;
+        }
+        Ok(())
+    }
+
+    fn definition(&mut self, definition: &query::Definition) -> fmt::Result {
+        match definition {
+            query::Definition::Operation(operation) => self.operation_definition(operation),
+            query::Definition::Fragment(fragment) => self.fragment_definition(fragment),
+        }
+    }
+
+    fn operation_definition(&mut self, operation: &OperationDefinition) -> fmt::Result {
+        match operation {
+            OperationDefinition::SelectionSet(selection_set) => self.selection_set(selection_set),
+            OperationDefinition::Query(query) => self.query(query),
+            OperationDefinition::Mutation(mutation) => self.mutation(mutation),
+            OperationDefinition::Subscription(subscription) => self.subscription(subscription),
+        }
+    }
+
+    fn query(&mut self, query: &Query) -> fmt::Result {
+        self.operation(
+            "query",
+            &query.name,
+            &query.variable_definitions,
+            &query.directives,
+            &query.selection_set,
+        )
+    }
+
+    fn mutation(&mut self, mutation: &Mutation) -> fmt::Result {
+        self.operation(
+            "mutation",
+            &mutation.name,
+            &mutation.variable_definitions,
+            &mutation.directives,
+            &mutation.selection_set,
+        )
+    }
+
+    fn subscription(&mut self, subscription: &Subscription) -> fmt::Result {
+        self.operation(
+            "subscription",
+            &subscription.name,
+            &subscription.variable_definitions,
+            &subscription.directives,
+            &subscription.selection_set,
+        )
+    }
+
+    fn operation(
+        &mut self,
+        ty: &str,
+        name: &Option<Positioned<String>>,
+        variable_definitions: &[Positioned<VariableDefinition>],
+        directives: &[Positioned<query::Directive>],
+        selection_set: &SelectionSet,
+    ) -> fmt::Result {
+        self.write(ty)?;
+        if let Some(name) = name {
+            write!(self.f, " {}", name)?;
+        }
+        if !variable_definitions.is_empty() {
+            self.list("(", variable_definitions, ")", |p, var| {
+                p.variable_definition(var)
+            })?;
+        }
+        self.directives(directives)?;
+        self.write_either(" ", "")?;
+        self.selection_set(selection_set)
+    }
+
+    fn variable_definition(&mut self, var: &VariableDefinition) -> fmt::Result {
+        write!(self.f, "${}", var.name)?;
+        self.write_either(": ", ":")?;
+        write!(self.f, "{}", var.var_type)?;
+        if let Some(default_value) = &var.default_value {
+            self.write_either(" = ", "=")?;
+            self.value(default_value)?;
+        }
+        Ok(())
+    }
+
+    fn fragment_definition(&mut self, fragment: &FragmentDefinition) -> fmt::Result {
+        write!(self.f, "fragment {} ", fragment.name)?;
+        self.type_condition(&fragment.type_condition)?;
+        self.directives(&fragment.directives)?;
+        self.write_either(" ", "")?;
+        self.selection_set(&fragment.selection_set)
+    }
+
+    fn type_condition(&mut self, type_condition: &TypeCondition) -> fmt::Result {
+        match type_condition {
+            TypeCondition::On(name) => write!(self.f, "on {}", name),
+        }
+    }
+
+    fn selection_set(&mut self, selection_set: &SelectionSet) -> fmt::Result {
+        self.block(&selection_set.items, |p, selection| p.selection(selection))
+    }
+
+    fn selection(&mut self, selection: &Selection) -> fmt::Result {
+        match selection {
+            Selection::Field(field) => self.field(field),
+            Selection::FragmentSpread(fragment_spread) => self.fragment_spread(fragment_spread),
+            Selection::InlineFragment(inline_fragment) => self.inline_fragment(inline_fragment),
+        }
+    }
+
+    fn field(&mut self, field: &query::Field) -> fmt::Result {
+        if let Some(alias) = &field.alias {
+            write!(self.f, "{}", alias)?;
+            self.write_either(": ", ":")?;
+        }
+        self.write(&field.name)?;
+        self.arguments(&field.arguments)?;
+        self.directives(&field.directives)?;
+        if !field.selection_set.items.is_empty() {
+            self.write_either(" ", "")?;
+            self.selection_set(&field.selection_set)?;
+        }
+        Ok(())
+    }
+
+    fn fragment_spread(&mut self, fragment_spread: &FragmentSpread) -> fmt::Result {
+        write!(self.f, "...{}", fragment_spread.fragment_name)?;
+        self.directives(&fragment_spread.directives)
+    }
+
+    fn inline_fragment(&mut self, inline_fragment: &InlineFragment) -> fmt::Result {
+        self.write("...")?;
+        if let Some(type_condition) = &inline_fragment.type_condition {
+            self.write_either(" ", "")?;
+            self.type_condition(type_condition)?;
+        }
+        self.directives(&inline_fragment.directives)?;
+        self.write_either(" ", "")?;
+        self.selection_set(&inline_fragment.selection_set)
+    }
+
+    fn schema_document(&mut self, document: &schema::Document) -> fmt::Result {
+        for (idx, definition) in document.definitions.iter().enumerate() {
+            if idx > 0 {
+                self.write_either("\n\n", " ")?;
+            }
+            self.schema_definition(definition)?;
+        }
+        Ok(())
+    }
+
+    fn schema_definition(&mut self, definition: &schema::Definition) -> fmt::Result {
+        match definition {
+            schema::Definition::SchemaDefinition(schema) => self.schema(schema),
+            schema::Definition::TypeDefinition(ty) => self.type_definition(ty),
+            schema::Definition::DirectiveDefinition(directive) => {
+                self.directive_definition(directive)
+            }
+        }
+    }
+
+    /// Write the description and the `extend` keyword before a definition.
+    fn header(&mut self, description: &Option<Positioned<String>>, extend: bool) -> fmt::Result {
+        if let Some(description) = description {
+            // The description is kept as it is written in the document.
+            self.write(description)?;
+            if self.pretty {
+                self.newline()?;
+            } else {
+                self.write(" ")?;
+            }
+        }
+        if extend {
+            self.write("extend ")?;
+        }
+        Ok(())
+    }
+
+    fn schema_directives(&mut self, directives: &[Positioned<schema::Directive>]) -> fmt::Result {
+        for directive in directives {
+            self.write_either(" ", "")?;
+            write!(self.f, "@{}", directive.name)?;
+            if !directive.arguments.is_empty() {
+                self.list("(", &directive.arguments, ")", |p, (name, value)| {
+                    p.write(name)?;
+                    p.write_either(": ", ":")?;
+                    p.schema_value(value)
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    fn schema(&mut self, schema: &schema::SchemaDefinition) -> fmt::Result {
+        self.header(&None, schema.extend)?;
+        self.write("schema")?;
+        self.schema_directives(&schema.directives)?;
+        self.write_either(" ", "")?;
+        let operations = [
+            ("query", &schema.query),
+            ("mutation", &schema.mutation),
+            ("subscription", &schema.subscription),
+        ]
+        .iter()
+        .filter_map(|(ty, name)| name.as_ref().map(|name| (*ty, name)))
+        .collect::<Vec<_>>();
+        self.block(&operations, |p, (ty, name)| {
+            p.write(ty)?;
+            p.write_either(": ", ":")?;
+            p.write(name)
+        })
+    }
+
+    fn type_definition(&mut self, ty: &schema::TypeDefinition) -> fmt::Result {
+        match ty {
+            schema::TypeDefinition::Scalar(scalar) => {
+                self.header(&scalar.description, scalar.extend)?;
+                write!(self.f, "scalar {}", scalar.name)?;
+                self.schema_directives(&scalar.directives)
+            }
+            schema::TypeDefinition::Object(object) => {
+                self.header(&object.description, object.extend)?;
+                write!(self.f, "type {}", object.name)?;
+                for (idx, interface) in object.implements_interfaces.iter().enumerate() {
+                    if idx == 0 {
+                        self.write(" implements ")?;
+                    } else {
+                        self.write_either(" & ", "&")?;
+                    }
+                    self.write(interface)?;
+                }
+                self.schema_directives(&object.directives)?;
+                self.fields_definition(&object.fields)
+            }
+            schema::TypeDefinition::Interface(interface) => {
+                self.header(&interface.description, interface.extend)?;
+                write!(self.f, "interface {}", interface.name)?;
+                self.schema_directives(&interface.directives)?;
+                self.fields_definition(&interface.fields)
+            }
+            schema::TypeDefinition::Union(union) => {
+                self.header(&union.description, union.extend)?;
+                write!(self.f, "union {}", union.name)?;
+                self.schema_directives(&union.directives)?;
+                for (idx, member) in union.members.iter().enumerate() {
+                    if idx == 0 {
+                        self.write_either(" = ", "=")?;
+                    } else {
+                        self.write_either(" | ", "|")?;
+                    }
+                    self.write(member)?;
+                }
+                Ok(())
+            }
+            schema::TypeDefinition::Enum(enum_type) => {
+                self.header(&enum_type.description, enum_type.extend)?;
+                write!(self.f, "enum {}", enum_type.name)?;
+                self.schema_directives(&enum_type.directives)?;
+                if enum_type.values.is_empty() {
+                    return Ok(());
+                }
+                self.write_either(" ", "")?;
+                self.block(&enum_type.values, |p, value| {
+                    p.header(&value.description, false)?;
+                    p.write(&value.name)?;
+                    p.schema_directives(&value.directives)
+                })
+            }
+            schema::TypeDefinition::InputObject(input) => {
+                self.header(&input.description, input.extend)?;
+                write!(self.f, "input {}", input.name)?;
+                self.schema_directives(&input.directives)?;
+                if input.fields.is_empty() {
+                    return Ok(());
+                }
+                self.write_either(" ", "")?;
+                self.block(&input.fields, |p, field| p.input_value(field))
+            }
+        }
+    }
+
+    fn fields_definition(&mut self, fields: &[Positioned<schema::Field>]) -> fmt::Result {
+        if fields.is_empty() {
+            return Ok(());
+        }
+        self.write_either(" ", "")?;
+        self.block(fields, |p, field| {
+            p.header(&field.description, false)?;
+            p.write(&field.name)?;
+            if !field.arguments.is_empty() {
+                p.list("(", &field.arguments, ")", |p, arg| p.input_value(arg))?;
+            }
+            p.write_either(": ", ":")?;
+            write!(p.f, "{}", field.ty)?;
+            p.schema_directives(&field.directives)
+        })
+    }
+
+    fn input_value(&mut self, input_value: &schema::InputValue) -> fmt::Result {
+        if let Some(description) = &input_value.description {
+            self.write(description)?;
+            self.write(" ")?;
+        }
+        self.write(&input_value.name)?;
+        self.write_either(": ", ":")?;
+        write!(self.f, "{}", input_value.ty)?;
+        if let Some(default_value) = &input_value.default_value {
+            self.write_either(" = ", "=")?;
+            self.schema_value(default_value)?;
+        }
+        self.schema_directives(&input_value.directives)
+    }
+
+    fn directive_definition(&mut self, directive: &schema::DirectiveDefinition) -> fmt::Result {
+        self.header(&directive.description, false)?;
+        write!(self.f, "directive @{}", directive.name)?;
+        // The arguments are required by the grammar.
+        self.list("(", &directive.arguments, ")", |p, arg| p.input_value(arg))?;
+        self.write(" on ")?;
+        for (idx, location) in directive.locations.iter().enumerate() {
+            if idx > 0 {
+                self.write_either(" | ", "|")?;
+            }
+            write!(self.f, "{}", location)?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! impl_display {
+    ($($ty:ty => $method:ident,)*) => {
+        $(
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let pretty = f.alternate();
+                Printer::new(f, pretty).$method(self)
+            }
+        }
+        )*
+    };
+}
+
+impl_display!(
+    query::Document => document,
+    query::Definition => definition,
+    OperationDefinition => operation_definition,
+    Query => query,
+    Mutation => mutation,
+    Subscription => subscription,
+    VariableDefinition => variable_definition,
+    FragmentDefinition => fragment_definition,
+    TypeCondition => type_condition,
+    SelectionSet => selection_set,
+    Selection => selection,
+    query::Field => field,
+    FragmentSpread => fragment_spread,
+    InlineFragment => inline_fragment,
+    query::Directive => directive,
+    schema::Document => schema_document,
+    schema::Definition => schema_definition,
+    schema::SchemaDefinition => schema,
+    schema::TypeDefinition => type_definition,
+    schema::DirectiveDefinition => directive_definition,
+    schema::InputValue => input_value,
+    schema::Value => schema_value,
+);
+
+impl fmt::Display for schema::DirectiveLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use schema::DirectiveLocation::*;
+
+        f.write_str(match self {
+            Query => "QUERY",
+            Mutation => "MUTATION",
+            Subscription => "SUBSCRIPTION",
+            Field => "FIELD",
+            FragmentDefinition => "FRAGMENT_DEFINITION",
+            FragmentSpread => "FRAGMENT_SPREAD",
+            InlineFragment => "INLINE_FRAGMENT",
+            Schema => "SCHEMA",
+            Scalar => "SCALAR",
+            Object => "OBJECT",
+            FieldDefinition => "FIELD_DEFINITION",
+            ArgumentDefinition => "ARGUMENT_DEFINITION",
+            Interface => "INTERFACE",
+            Union => "UNION",
+            Enum => "ENUM",
+            EnumValue => "ENUM_VALUE",
+            InputObject => "INPUT_OBJECT",
+            InputFieldDefinition => "INPUT_FIELD_DEFINITION",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_query, parse_schema};
+    use std::fs;
+
+    #[test]
+    fn test_print_query() {
+        for entry in fs::read_dir("tests/queries").unwrap() {
+            let input = fs::read_to_string(entry.unwrap().path()).unwrap();
+            let document = parse_query(&input).unwrap();
+            for output in &[format!("{}", document), format!("{:#}", document)] {
+                let reparsed = parse_query(output).unwrap();
+                assert_eq!(format!("{}", reparsed), format!("{}", document));
+            }
+        }
+    }
+
+    #[test]
+    fn test_print_schema() {
+        for entry in fs::read_dir("tests/schemas").unwrap() {
+            let input = fs::read_to_string(entry.unwrap().path()).unwrap();
+            let document = parse_schema(&input).unwrap();
+            for output in &[format!("{}", document), format!("{:#}", document)] {
+                let reparsed = parse_schema(output).unwrap();
+                assert_eq!(format!("{}", reparsed), format!("{}", document));
+            }
+        }
+    }
+
+    #[test]
+    fn test_print_format() {
+        let document = parse_query(
+            r#"query Q($a: Int = 1, $b: [String!]!) @dir {
+                x: a(b: $a, c: {d: 1.0, e: "f\"\n"}) @skip(if: true) { b ...F }
+                ... on T { c }
+            }
+            fragment F on T { d }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            document.to_string(),
+            r#"query Q($a:Int=1,$b:[String!]!)@dir{x:a(b:$a,c:{d:1.0,e:"f\"\n"})@skip(if:true){b ...F} ...on T{c}} fragment F on T{d}"#
+        );
+        assert_eq!(
+            format!("{:#}", document),
+            r#"query Q($a: Int = 1, $b: [String!]!) @dir {
+  x: a(b: $a, c: {d: 1.0, e: "f\"\n"}) @skip(if: true) {
+    b
+    ...F
+  }
+  ... on T {
+    c
+  }
+}
+
+fragment F on T {
+  d
+}"#
+        );
+
+        let document = parse_schema(
+            r#""A type" type A implements B & C @d(x: 1) { "A field" a(b: Int = 2): [A!] }
+            union U = A | B
+            directive @d(x: Int) on FIELD | QUERY"#,
+        )
+        .unwrap();
+        assert_eq!(
+            document.to_string(),
+            r#""A type" type A implements B&C@d(x:1){"A field" a(b:Int=2):[A!]} union U=A|B directive @d(x:Int) on FIELD|QUERY"#
+        );
+        assert_eq!(
+            format!("{:#}", document),
+            r#""A type"
+type A implements B & C @d(x: 1) {
+  "A field"
+  a(b: Int = 2): [A!]
+}
+
+union U = A | B
+
+directive @d(x: Int) on FIELD | QUERY"#
+        );
+    }
+}
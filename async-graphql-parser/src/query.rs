@@ -1,4 +1,4 @@
-use crate::pos::Positioned;
+use crate::pos::{Positioned, Span};
 use crate::value::Value;
 use std::collections::HashMap;
 use std::fmt;
@@ -57,6 +57,8 @@ pub struct Document {
     pub(crate) definitions: Vec<Positioned<Definition>>,
     pub(crate) fragments: FragmentsMap,
     pub(crate) current_operation: Option<CurrentOperation>,
+    pub(crate) type_system_definitions: Vec<Positioned<TypeSystemDefinition>>,
+    pub(crate) duplicate_input_fields: Vec<DuplicateInputField>,
 }
 
 impl Document {
@@ -70,6 +72,20 @@ impl Document {
         &self.fragments
     }
 
+    /// Returns the type system definitions and extensions of the document, which are not
+    /// executable.
+    #[inline]
+    pub fn type_system_definitions(&self) -> &[Positioned<TypeSystemDefinition>] {
+        &self.type_system_definitions
+    }
+
+    /// Returns the input object fields whose name is already used in the same input object, in
+    /// the order of the repeated fields.
+    #[inline]
+    pub fn duplicate_input_fields(&self) -> &[DuplicateInputField] {
+        &self.duplicate_input_fields
+    }
+
     #[inline]
     pub fn current_operation(&self) -> &CurrentOperation {
         self.current_operation
@@ -147,6 +163,24 @@ pub enum Definition {
     Fragment(Positioned<FragmentDefinition>),
 }
 
+/// A type system definition or extension in a query document, only its name is kept.
+#[derive(Debug, Clone)]
+pub struct TypeSystemDefinition {
+    /// The name of the type or the directive, `None` for a schema definition or extension.
+    pub name: Option<Positioned<String>>,
+}
+
+/// An input object field whose name is already used in the same input object.
+///
+/// An input object value keeps the last of the fields with the same name.
+#[derive(Debug, Clone)]
+pub struct DuplicateInputField {
+    /// The name of the first field.
+    pub first: Positioned<String>,
+    /// The span of the name of the repeated field.
+    pub duplicate: Span,
+}
+
 #[derive(Debug, Clone)]
 pub enum TypeCondition {
     On(Positioned<String>),
@@ -1,8 +1,10 @@
+use crate::descent_parser;
+use crate::options::{check_document, check_tokens, ParserOptions};
 use crate::pos::Positioned;
 use crate::query::*;
 use crate::utils::{unquote_string, PositionCalculator};
 use crate::value::Value;
-use crate::Result;
+use crate::{Error, Result};
 use pest::iterators::Pair;
 use pest::Parser;
 use std::collections::BTreeMap;
@@ -13,11 +15,62 @@ struct QueryParser;
 
 /// Parse a GraphQL query.
 pub fn parse_query<T: AsRef<str>>(input: T) -> Result<Document> {
-    let document_pair: Pair<Rule> = QueryParser::parse(Rule::document, input.as_ref())?
-        .next()
-        .unwrap();
+    parse_query_with_options(input, &Default::default())
+}
+
+/// Parse a GraphQL query with the specified limits.
+pub fn parse_query_with_options<T: AsRef<str>>(
+    input: T,
+    options: &ParserOptions,
+) -> Result<Document> {
+    check_tokens(input.as_ref(), options)?;
+    let document = descent_parser::parse_query(input.as_ref())?;
+    check_document(&document, options)?;
+    Ok(document)
+}
+
+/// Parse a GraphQL query with the specified limits and recover from syntax errors.
+///
+/// Returns the definitions and selections that could be parsed, and all the syntax errors in the
+/// order of their positions. The document is complete if there are no errors.
+///
+/// The query is not parsed if it has too many tokens or it is nested too deeply, the document is
+/// empty and the error is the only one.
+pub fn parse_query_recovering<T: AsRef<str>>(
+    input: T,
+    options: &ParserOptions,
+) -> (Document, Vec<Error>) {
+    if let Err(err) = check_tokens(input.as_ref(), options) {
+        let document = Document {
+            definitions: Vec::new(),
+            fragments: Default::default(),
+            current_operation: None,
+            type_system_definitions: Vec::new(),
+            duplicate_input_fields: Vec::new(),
+        };
+        return (document, vec![err]);
+    }
+
+    let (document, mut errors) = descent_parser::parse_query_recovering(input.as_ref());
+    if let Err(err) = check_document(&document, options) {
+        errors.push(err);
+        errors.sort_by_key(|err| err.pos);
+    }
+    (document, errors)
+}
+
+/// Parse a GraphQL query with the grammar of `query.pest`.
+///
+/// It is slower than `parse_query` and it does not apply any limit, it is the reference of the
+/// hand-written parser in the tests and the baseline of the benchmarks.
+#[doc(hidden)]
+pub fn parse_query_with_grammar<T: AsRef<str>>(input: T) -> Result<Document> {
+    let input = input.as_ref();
+    let document_pair: Pair<Rule> = QueryParser::parse(Rule::document, input)?.next().unwrap();
     let mut definitions = Vec::new();
-    let mut pc = PositionCalculator::new(input.as_ref());
+    let mut type_system_definitions = Vec::new();
+    let mut pc = PositionCalculator::new(input);
+    let duplicate_input_fields = find_duplicate_input_fields(&document_pair, input);
 
     for pair in document_pair.into_inner() {
         match pair.as_rule() {
@@ -30,6 +83,9 @@ pub fn parse_query<T: AsRef<str>>(input: T) -> Result<Document> {
             ),
             Rule::fragment_definition => definitions
                 .push(parse_fragment_definition(pair, &mut pc)?.pack(Definition::Fragment)),
+            Rule::type_system_definition => {
+                type_system_definitions.push(parse_type_system_definition(pair, &mut pc))
+            }
             Rule::EOI => {}
             _ => unreachable!(),
         }
@@ -39,9 +95,68 @@ pub fn parse_query<T: AsRef<str>>(input: T) -> Result<Document> {
         definitions,
         fragments: Default::default(),
         current_operation: None,
+        type_system_definitions,
+        duplicate_input_fields,
     })
 }
 
+/// Returns the fields of the input objects of the document whose name is already used in the
+/// same object, in the order of the repeated fields.
+fn find_duplicate_input_fields(
+    document_pair: &Pair<Rule>,
+    input: &str,
+) -> Vec<DuplicateInputField> {
+    let mut pc = PositionCalculator::new(input);
+    let mut span_of = |pair: &Pair<Rule>| {
+        let span = pair.as_span();
+        let start = pc.position(span.start());
+        pc.span(start, span.start(), span.end())
+    };
+
+    let mut duplicate_input_fields = Vec::new();
+    for object in document_pair
+        .clone()
+        .into_inner()
+        .flatten()
+        .filter(|pair| pair.as_rule() == Rule::object)
+    {
+        let mut names: Vec<Positioned<String>> = Vec::new();
+        for pair in object.into_inner() {
+            let name_pair = pair.into_inner().next().unwrap();
+            let name = Positioned::new(name_pair.as_str().to_string(), span_of(&name_pair));
+            match names.iter().find(|first| first.node == name.node) {
+                Some(first) => duplicate_input_fields.push(DuplicateInputField {
+                    first: first.clone(),
+                    duplicate: name.span(),
+                }),
+                None => names.push(name),
+            }
+        }
+    }
+    duplicate_input_fields.sort_by_key(|field| field.duplicate.start_offset);
+    duplicate_input_fields
+}
+
+fn parse_type_system_definition(
+    pair: Pair<Rule>,
+    pc: &mut PositionCalculator,
+) -> Positioned<TypeSystemDefinition> {
+    let start = pc.start(&pair);
+    let mut name = None;
+    for pair in pair.into_inner() {
+        let pair = match pair.as_rule() {
+            Rule::type_definition => pair.into_inner().next().unwrap(),
+            Rule::directive_definition => pair,
+            _ => continue,
+        };
+        name = pair
+            .into_inner()
+            .find(|pair| pair.as_rule() == Rule::name)
+            .map(|pair| Positioned::new(pair.as_str().to_string(), pc.span_of(&pair)));
+    }
+    Positioned::new(TypeSystemDefinition { name }, pc.finish(start))
+}
+
 fn parse_named_operation_definition(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
@@ -52,7 +167,7 @@ fn parse_named_operation_definition(
         Subscription,
     }
 
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut operation_type = OperationType::Query;
     let mut name = None;
     let mut variable_definitions = None;
@@ -70,7 +185,10 @@ fn parse_named_operation_definition(
                 };
             }
             Rule::name => {
-                name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair)));
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ));
             }
             Rule::variable_definitions => {
                 variable_definitions = Some(parse_variable_definitions(pair, pc)?);
@@ -93,7 +211,7 @@ fn parse_named_operation_definition(
                 directives: directives.unwrap_or_default(),
                 selection_set: selection_set.unwrap(),
             },
-            pos,
+            pc.finish(start),
         )
         .pack(OperationDefinition::Query),
         OperationType::Mutation => Positioned::new(
@@ -103,7 +221,7 @@ fn parse_named_operation_definition(
                 directives: directives.unwrap_or_default(),
                 selection_set: selection_set.unwrap(),
             },
-            pos,
+            pc.finish(start),
         )
         .pack(OperationDefinition::Mutation),
         OperationType::Subscription => Positioned::new(
@@ -113,7 +231,7 @@ fn parse_named_operation_definition(
                 directives: directives.unwrap_or_default(),
                 selection_set: selection_set.unwrap(),
             },
-            pos,
+            pc.finish(start),
         )
         .pack(OperationDefinition::Subscription),
     })
@@ -144,7 +262,7 @@ fn parse_variable_definition(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
 ) -> Result<Positioned<VariableDefinition>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut variable = None;
     let mut ty = None;
     let mut default_value = None;
@@ -154,13 +272,16 @@ fn parse_variable_definition(
             Rule::variable => variable = Some(parse_variable(pair, pc)?),
             Rule::type_ => {
                 ty = {
-                    let pos = pc.step(&pair);
-                    Some(Positioned::new(parse_type(pair, pc)?, pos))
+                    let start = pc.start(&pair);
+                    Some(Positioned::new(parse_type(pair, pc)?, pc.finish(start)))
                 }
             }
             Rule::default_value => {
-                let pos = pc.step(&pair);
-                default_value = Some(Positioned::new(parse_default_value(pair, pc)?, pos))
+                let start = pc.start(&pair);
+                default_value = Some(Positioned::new(
+                    parse_default_value(pair, pc)?,
+                    pc.finish(start),
+                ))
             }
             _ => unreachable!(),
         }
@@ -171,7 +292,7 @@ fn parse_variable_definition(
             var_type: ty.unwrap(),
             default_value,
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
@@ -190,14 +311,16 @@ fn parse_variable_definitions(
 }
 
 fn parse_directive(pair: Pair<Rule>, pc: &mut PositionCalculator) -> Result<Positioned<Directive>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut name = None;
     let mut arguments = None;
     for pair in pair.into_inner() {
         match pair.as_rule() {
             Rule::name => {
-                let pos = pc.step(&pair);
-                name = Some(Positioned::new(pair.as_str().to_string(), pos))
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
             }
             Rule::arguments => arguments = Some(parse_arguments(pair, pc)?),
             _ => unreachable!(),
@@ -208,7 +331,7 @@ fn parse_directive(pair: Pair<Rule>, pc: &mut PositionCalculator) -> Result<Posi
             name: name.unwrap(),
             arguments: arguments.unwrap_or_default(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
@@ -229,7 +352,10 @@ fn parse_directives(
 fn parse_variable(pair: Pair<Rule>, pc: &mut PositionCalculator) -> Result<Positioned<String>> {
     for pair in pair.into_inner() {
         if let Rule::name = pair.as_rule() {
-            return Ok(Positioned::new(pair.as_str().to_string(), pc.step(&pair)));
+            return Ok(Positioned::new(
+                pair.as_str().to_string(),
+                pc.span_of(&pair),
+            ));
         }
     }
     unreachable!()
@@ -301,11 +427,16 @@ fn parse_pair(
     let mut value = None;
     for pair in pair.into_inner() {
         match pair.as_rule() {
-            Rule::name => name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
+            Rule::name => {
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
+            }
             Rule::value => {
                 value = {
-                    let pos = pc.step(&pair);
-                    Some(Positioned::new(parse_value2(pair, pc)?, pos))
+                    let start = pc.start(&pair);
+                    Some(Positioned::new(parse_value2(pair, pc)?, pc.finish(start)))
                 }
             }
             _ => unreachable!(),
@@ -331,14 +462,17 @@ fn parse_arguments(
 fn parse_alias(pair: Pair<Rule>, pc: &mut PositionCalculator) -> Result<Positioned<String>> {
     for pair in pair.into_inner() {
         if let Rule::name = pair.as_rule() {
-            return Ok(Positioned::new(pair.as_str().to_string(), pc.step(&pair)));
+            return Ok(Positioned::new(
+                pair.as_str().to_string(),
+                pc.span_of(&pair),
+            ));
         }
     }
     unreachable!()
 }
 
 fn parse_field(pair: Pair<Rule>, pc: &mut PositionCalculator) -> Result<Positioned<Field>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut alias = None;
     let mut name = None;
     let mut directives = None;
@@ -348,7 +482,12 @@ fn parse_field(pair: Pair<Rule>, pc: &mut PositionCalculator) -> Result<Position
     for pair in pair.into_inner() {
         match pair.as_rule() {
             Rule::alias => alias = Some(parse_alias(pair, pc)?),
-            Rule::name => name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
+            Rule::name => {
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
+            }
             Rule::arguments => arguments = Some(parse_arguments(pair, pc)?),
             Rule::directives => directives = Some(parse_directives(pair, pc)?),
             Rule::selection_set => selection_set = Some(parse_selection_set(pair, pc)?),
@@ -364,7 +503,7 @@ fn parse_field(pair: Pair<Rule>, pc: &mut PositionCalculator) -> Result<Position
             directives: directives.unwrap_or_default(),
             selection_set: selection_set.unwrap_or_default(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
@@ -372,12 +511,17 @@ fn parse_fragment_spread(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
 ) -> Result<Positioned<FragmentSpread>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut name = None;
     let mut directives = None;
     for pair in pair.into_inner() {
         match pair.as_rule() {
-            Rule::name => name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
+            Rule::name => {
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
+            }
             Rule::directives => directives = Some(parse_directives(pair, pc)?),
             _ => unreachable!(),
         }
@@ -387,7 +531,7 @@ fn parse_fragment_spread(
             fragment_name: name.unwrap(),
             directives: directives.unwrap_or_default(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
@@ -397,10 +541,10 @@ fn parse_type_condition(
 ) -> Result<Positioned<TypeCondition>> {
     for pair in pair.into_inner() {
         if let Rule::name = pair.as_rule() {
-            let pos = pc.step(&pair);
+            let span = pc.span_of(&pair);
             return Ok(Positioned::new(
-                TypeCondition::On(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
-                pos,
+                TypeCondition::On(Positioned::new(pair.as_str().to_string(), span)),
+                span,
             ));
         }
     }
@@ -411,7 +555,7 @@ fn parse_inline_fragment(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
 ) -> Result<Positioned<InlineFragment>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut type_condition = None;
     let mut directives = None;
     let mut selection_set = None;
@@ -431,7 +575,7 @@ fn parse_inline_fragment(
             directives: directives.unwrap_or_default(),
             selection_set: selection_set.unwrap(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
@@ -439,7 +583,7 @@ fn parse_selection_set(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
 ) -> Result<Positioned<SelectionSet>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut items = Vec::new();
     for pair in pair.into_inner().map(|pair| pair.into_inner()).flatten() {
         match pair.as_rule() {
@@ -453,14 +597,14 @@ fn parse_selection_set(
             _ => unreachable!(),
         }
     }
-    Ok(Positioned::new(SelectionSet { items }, pos))
+    Ok(Positioned::new(SelectionSet { items }, pc.finish(start)))
 }
 
 fn parse_fragment_definition(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
 ) -> Result<Positioned<FragmentDefinition>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut name = None;
     let mut type_condition = None;
     let mut directives = None;
@@ -468,7 +612,12 @@ fn parse_fragment_definition(
 
     for pair in pair.into_inner() {
         match pair.as_rule() {
-            Rule::name => name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
+            Rule::name => {
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
+            }
             Rule::type_condition => type_condition = Some(parse_type_condition(pair, pc)?),
             Rule::directives => directives = Some(parse_directives(pair, pc)?),
             Rule::selection_set => selection_set = Some(parse_selection_set(pair, pc)?),
@@ -483,13 +632,14 @@ fn parse_fragment_definition(
             directives: directives.unwrap_or_default(),
             selection_set: selection_set.unwrap(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{Pos, Span};
     use std::fs;
 
     #[test]
@@ -510,4 +660,344 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_parser_options() {
+        let options = ParserOptions {
+            max_depth: Some(3),
+            max_tokens: Some(20),
+            max_aliases: Some(1),
+            max_directives_per_field: Some(1),
+        };
+        for entry in fs::read_dir("tests/queries").unwrap() {
+            if let Ok(entry) = entry {
+                parse_query_with_options(
+                    fs::read_to_string(entry.path()).unwrap(),
+                    &Default::default(),
+                )
+                .unwrap();
+            }
+        }
+
+        // Brackets in strings and comments are not counted.
+        parse_query_with_options(
+            "{ a(s: \"{{{{\", t: \"\"\"[[[[\"\"\") # {{{{\n b }",
+            &options,
+        )
+        .unwrap();
+
+        let err = parse_query_with_options("{ a { b { c { d } } } }", &options).unwrap_err();
+        assert_eq!(
+            err.pos,
+            Pos {
+                line: 1,
+                column: 13
+            }
+        );
+        assert_eq!(
+            err.message,
+            "The query is nested too deeply, the maximum depth is 3"
+        );
+
+        let err = parse_query_with_options("{ a(b: [[[1]]]) }", &options).unwrap_err();
+        assert_eq!(err.pos, Pos { line: 1, column: 9 });
+
+        let err = parse_query_with_options("{ a b c d e f g h i j k l m n o p q r s t }", &options)
+            .unwrap_err();
+        assert_eq!(
+            err.pos,
+            Pos {
+                line: 1,
+                column: 41
+            }
+        );
+        assert_eq!(
+            err.message,
+            "The query has too many tokens, the maximum is 20"
+        );
+
+        let err = parse_query_with_options("{ x: a y: b }", &options).unwrap_err();
+        assert_eq!(err.pos, Pos { line: 1, column: 8 });
+        assert_eq!(
+            err.message,
+            "The query has too many aliases, the maximum is 1"
+        );
+
+        let err = parse_query_with_options("{ a @skip(if: true) @include(if: true) }", &options)
+            .unwrap_err();
+        assert_eq!(
+            err.pos,
+            Pos {
+                line: 1,
+                column: 21
+            }
+        );
+        assert_eq!(
+            err.message,
+            "The field \"a\" has too many directives, the maximum is 1"
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        for (input, column, message) in &[
+            ("{ a(b: ) }", 8, "Unexpected \")\""),
+            ("{ a(b: \"c) }", 8, "Unterminated string"),
+            (
+                "{ a(b: 99999999999999999999) }",
+                8,
+                "Number \"99999999999999999999\" is out of range",
+            ),
+            ("query Q { a } query", 20, "Unexpected end of input"),
+            ("{ a } b", 7, "Unexpected name \"b\""),
+        ] {
+            let err = parse_query(input).unwrap_err();
+            assert_eq!(
+                (err.pos.column, err.message.as_str()),
+                (*column, *message),
+                "input: {:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_query_recovering_with_options() {
+        let options = ParserOptions {
+            max_depth: Some(2),
+            max_tokens: Some(20),
+            max_aliases: Some(1),
+            ..Default::default()
+        };
+
+        let (document, errors) = parse_query_recovering("{ a { b { c } } }", &options);
+        assert!(document.definitions.is_empty());
+        assert_eq!(
+            errors,
+            vec![Error {
+                pos: Pos { line: 1, column: 9 },
+                message: "The query is nested too deeply, the maximum depth is 2".to_string(),
+            }]
+        );
+
+        let (document, errors) = parse_query_recovering("{ x: a(b: ) y: c z: d }", &options);
+        assert_eq!(document.definitions.len(), 1);
+        assert_eq!(
+            errors,
+            vec![
+                Error {
+                    pos: Pos {
+                        line: 1,
+                        column: 11
+                    },
+                    message: "Unexpected \")\"".to_string(),
+                },
+                Error {
+                    pos: Pos {
+                        line: 1,
+                        column: 13
+                    },
+                    message: "The query has too many aliases, the maximum is 1".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spans() {
+        let input =
+            "query A($v: Int = 1) {\n  a: b(x: [1, 2]) @skip(if: $v) # c\n  ... on T { c }\n}\n";
+        for document in &[
+            parse_query(input).unwrap(),
+            parse_query_with_grammar(input).unwrap(),
+        ] {
+            let text = |span: Span| &input[span.start_offset..span.end_offset];
+            let query = match &document.definitions[0].node {
+                Definition::Operation(operation) => match &operation.node {
+                    OperationDefinition::Query(query) => query,
+                    _ => panic!("expected a query"),
+                },
+                _ => panic!("expected an operation"),
+            };
+            assert_eq!(text(query.span()), input.trim_end());
+            assert_eq!(query.span().end, Pos { line: 4, column: 2 });
+
+            let var = &query.variable_definitions[0];
+            assert_eq!(text(var.span()), "$v: Int = 1");
+            assert_eq!(text(var.default_value.as_ref().unwrap().span()), "= 1");
+
+            let field = match &query.selection_set.items[0].node {
+                Selection::Field(field) => field,
+                _ => panic!("expected a field"),
+            };
+            assert_eq!(text(field.span()), "a: b(x: [1, 2]) @skip(if: $v)");
+            assert_eq!(
+                field.span().end,
+                Pos {
+                    line: 2,
+                    column: 32
+                }
+            );
+            assert_eq!(text(field.arguments[0].1.span()), "[1, 2]");
+            assert_eq!(text(field.directives[0].span()), "@skip(if: $v)");
+
+            let inline_fragment = match &query.selection_set.items[1].node {
+                Selection::InlineFragment(inline_fragment) => inline_fragment,
+                _ => panic!("expected an inline fragment"),
+            };
+            assert_eq!(text(inline_fragment.span()), "... on T { c }");
+            let type_condition = inline_fragment.type_condition.as_ref().unwrap();
+            assert_eq!(text(type_condition.span()), "T");
+        }
+    }
+
+    #[test]
+    fn test_type_system_definitions() {
+        let input = "{ a }\n\"Foo\" type Foo implements Bar { a: Int }\nextend schema @a\ndirective @b on FIELD";
+        for document in &[
+            parse_query(input).unwrap(),
+            parse_query_with_grammar(input).unwrap(),
+        ] {
+            assert_eq!(document.definitions().len(), 1);
+            let definitions = document.type_system_definitions();
+            assert_eq!(definitions.len(), 3);
+            assert_eq!(
+                definitions[0].name.as_ref().map(|name| name.as_str()),
+                Some("Foo")
+            );
+            assert_eq!(definitions[0].position(), Pos { line: 2, column: 1 });
+            assert!(definitions[1].name.is_none());
+            assert_eq!(
+                definitions[2].name.as_ref().map(|name| name.as_str()),
+                Some("b")
+            );
+        }
+
+        for input in &[
+            "type Foo { a }",
+            "type Foo { }",
+            "schema { query }",
+            "extend directive @a on FIELD",
+            "\"Foo\" extend type Foo",
+        ] {
+            assert!(parse_query(input).is_err(), "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_duplicate_input_fields() {
+        let input = "{ a(x: { a: 1, b: { a: 1, a: 2 }, a: 3, a: 4 }, y: [{ a: 1 }, { a: 2 }]) }";
+        for document in &[
+            parse_query(input).unwrap(),
+            parse_query_with_grammar(input).unwrap(),
+        ] {
+            assert_eq!(
+                document
+                    .duplicate_input_fields()
+                    .iter()
+                    .map(|field| (
+                        field.first.as_str(),
+                        field.first.position().column,
+                        field.duplicate.start.column
+                    ))
+                    .collect::<Vec<_>>(),
+                vec![("a", 21, 27), ("a", 10, 35), ("a", 10, 41)]
+            );
+        }
+
+        // The duplicate input fields of the skipped selections are not kept.
+        let (document, errors) = parse_query_recovering(
+            "{ a(x: { b: 1, b: 2 }) b(x: { c: 1, c: 2 }) @ }",
+            &Default::default(),
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(document.duplicate_input_fields().len(), 1);
+        assert_eq!(document.duplicate_input_fields()[0].first.as_str(), "b");
+    }
+
+    #[test]
+    fn test_parse_query_recovering() {
+        let (document, errors) = parse_query_recovering("{ a }", &Default::default());
+        assert_eq!(document.definitions.len(), 1);
+        assert!(errors.is_empty());
+
+        let (document, errors) = parse_query_recovering(
+            "query A { a(x: 1 }\nfragment F on T { b c(s: ) }",
+            &Default::default(),
+        );
+        assert_eq!(
+            errors,
+            vec![
+                Error {
+                    pos: Pos {
+                        line: 1,
+                        column: 18
+                    },
+                    message: "Unexpected \"}\"".to_string(),
+                },
+                Error {
+                    pos: Pos {
+                        line: 2,
+                        column: 26
+                    },
+                    message: "Unexpected \")\"".to_string(),
+                },
+            ]
+        );
+
+        assert_eq!(document.definitions.len(), 2);
+        match &document.definitions[0].node {
+            Definition::Operation(operation) => match &operation.node {
+                OperationDefinition::Query(query) => {
+                    let items = &query.selection_set.items;
+                    assert_eq!(items.len(), 1);
+                    match &items[0].node {
+                        Selection::Field(field) => {
+                            assert_eq!(field.name.node, "a");
+                            assert_eq!(field.arguments.len(), 1);
+                        }
+                        _ => panic!("expected a field"),
+                    }
+                }
+                _ => panic!("expected a query"),
+            },
+            _ => panic!("expected an operation"),
+        }
+        match &document.definitions[1].node {
+            Definition::Fragment(fragment) => {
+                assert_eq!(fragment.name.node, "F");
+                assert_eq!(fragment.selection_set.items.len(), 2);
+            }
+            _ => panic!("expected a fragment"),
+        }
+
+        // The rest of the input is a part of an unterminated string.
+        let (document, errors) = parse_query_recovering(
+            "{ a }\n{ b(s: \"abc) }\nfragment F on T { c(",
+            &Default::default(),
+        );
+        assert_eq!(
+            errors,
+            vec![Error {
+                pos: Pos { line: 2, column: 8 },
+                message: "Unterminated string".to_string(),
+            }]
+        );
+        assert_eq!(document.definitions.len(), 1);
+
+        let (_, errors) = parse_query_recovering(
+            "{ a(s: \"\\x\") b(n: 99999999999999999999) }",
+            &Default::default(),
+        );
+        assert_eq!(
+            errors
+                .iter()
+                .map(|err| err.message.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "Invalid escape sequence in string",
+                "Number \"99999999999999999999\" is out of range"
+            ]
+        );
+    }
 }
@@ -1,5 +1,6 @@
 use crate::pos::Positioned;
 use std::collections::BTreeMap;
+use std::fmt;
 
 #[derive(Clone, Debug)]
 #[allow(missing_docs)]
@@ -21,6 +22,16 @@ pub enum Type {
     NonNull(Box<Type>),
 }
 
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Named(name) => write!(f, "{}", name),
+            Type::List(ty) => write!(f, "[{}]", ty),
+            Type::NonNull(ty) => write!(f, "{}!", ty),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Document {
     pub definitions: Vec<Positioned<Definition>>,
@@ -1,6 +1,6 @@
 use crate::schema::*;
 use crate::utils::{unquote_string, PositionCalculator};
-use crate::{Positioned, Result};
+use crate::{Error, Positioned, Result};
 use pest::iterators::Pair;
 use pest::Parser;
 use std::collections::BTreeMap;
@@ -78,7 +78,7 @@ fn parse_schema_definition(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
 ) -> Result<Positioned<SchemaDefinition>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut extend = false;
     let mut directives = None;
     let mut query = None;
@@ -96,8 +96,10 @@ fn parse_schema_definition(
                     match pair.as_rule() {
                         Rule::operation_type => op_name = Some(pair.as_str().to_string()),
                         Rule::name => {
-                            ty_name =
-                                Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair)))
+                            ty_name = Some(Positioned::new(
+                                pair.as_str().to_string(),
+                                pc.span_of(&pair),
+                            ))
                         }
                         _ => unreachable!(),
                     }
@@ -121,19 +123,21 @@ fn parse_schema_definition(
             mutation,
             subscription,
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
 fn parse_directive(pair: Pair<Rule>, pc: &mut PositionCalculator) -> Result<Positioned<Directive>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut name = None;
     let mut arguments = None;
     for pair in pair.into_inner() {
         match pair.as_rule() {
             Rule::name => {
-                let pos = pc.step(&pair);
-                name = Some(Positioned::new(pair.as_str().to_string(), pos))
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
             }
             Rule::arguments => arguments = Some(parse_arguments(pair, pc)?),
             _ => unreachable!(),
@@ -144,7 +148,7 @@ fn parse_directive(pair: Pair<Rule>, pc: &mut PositionCalculator) -> Result<Posi
             name: name.unwrap(),
             arguments: arguments.unwrap_or_default(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
@@ -184,11 +188,16 @@ fn parse_pair(
     let mut value = None;
     for pair in pair.into_inner() {
         match pair.as_rule() {
-            Rule::name => name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
+            Rule::name => {
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
+            }
             Rule::value => {
                 value = {
-                    let pos = pc.step(&pair);
-                    Some(Positioned::new(parse_value(pair, pc)?, pos))
+                    let start = pc.start(&pair);
+                    Some(Positioned::new(parse_value(pair, pc)?, pc.finish(start)))
                 }
             }
             _ => unreachable!(),
@@ -236,7 +245,17 @@ fn parse_object_value(pair: Pair<Rule>, pc: &mut PositionCalculator) -> Result<V
     let mut map = BTreeMap::new();
     for pair in pair.into_inner() {
         match pair.as_rule() {
-            Rule::pair => map.extend(std::iter::once(parse_object_pair(pair, pc)?)),
+            Rule::pair => {
+                let pos = pc.step(&pair);
+                let (name, value) = parse_object_pair(pair, pc)?;
+                if map.contains_key(&name) {
+                    return Err(Error {
+                        pos,
+                        message: format!("There can only be one input field named \"{}\".", name),
+                    });
+                }
+                map.insert(name, value);
+            }
             _ => unreachable!(),
         }
     }
@@ -258,7 +277,7 @@ fn parse_scalar_type(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
 ) -> Result<Positioned<ScalarType>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut description = None;
     let mut extend = false;
     let mut name = None;
@@ -267,10 +286,18 @@ fn parse_scalar_type(
     for pair in pair.into_inner() {
         match pair.as_rule() {
             Rule::string => {
-                description = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair)))
+                description = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
             }
             Rule::extend => extend = true,
-            Rule::name => name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
+            Rule::name => {
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
+            }
             Rule::directives => directives = Some(parse_directives(pair, pc)?),
             _ => unreachable!(),
         }
@@ -283,7 +310,7 @@ fn parse_scalar_type(
             name: name.unwrap(),
             directives: directives.unwrap_or_default(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
@@ -294,9 +321,10 @@ fn parse_implements_interfaces(
     let mut interfaces = Vec::new();
     for pair in pair.into_inner() {
         match pair.as_rule() {
-            Rule::name => {
-                interfaces.push(Positioned::new(pair.as_str().to_string(), pc.step(&pair)))
-            }
+            Rule::name => interfaces.push(Positioned::new(
+                pair.as_str().to_string(),
+                pc.span_of(&pair),
+            )),
             _ => unreachable!(),
         }
     }
@@ -318,7 +346,7 @@ fn parse_input_value_definition(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
 ) -> Result<Positioned<InputValue>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut description = None;
     let mut name = None;
     let mut type_ = None;
@@ -328,18 +356,26 @@ fn parse_input_value_definition(
     for pair in pair.into_inner() {
         match pair.as_rule() {
             Rule::string => {
-                description = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair)))
+                description = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
+            }
+            Rule::name => {
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
             }
-            Rule::name => name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
             Rule::type_ => {
-                let pos = pc.step(&pair);
-                type_ = Some(Positioned::new(parse_type(pair, pc)?, pos));
+                let start = pc.start(&pair);
+                type_ = Some(Positioned::new(parse_type(pair, pc)?, pc.finish(start)));
             }
             Rule::default_value => {
-                let pos = pc.step(&pair);
+                let start = pc.start(&pair);
                 default_value = Some(Positioned::new(
                     parse_value(pair.into_inner().next().unwrap(), pc)?,
-                    pos,
+                    pc.finish(start),
                 ));
             }
             Rule::directives => directives = Some(parse_directives(pair, pc)?),
@@ -355,7 +391,7 @@ fn parse_input_value_definition(
             ty: type_.unwrap(),
             directives: directives.unwrap_or_default(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
@@ -377,7 +413,7 @@ fn parse_field_definition(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
 ) -> Result<Positioned<Field>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut description = None;
     let mut name = None;
     let mut arguments = None;
@@ -387,13 +423,21 @@ fn parse_field_definition(
     for pair in pair.into_inner() {
         match pair.as_rule() {
             Rule::string => {
-                description = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair)))
+                description = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
+            }
+            Rule::name => {
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
             }
-            Rule::name => name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
             Rule::arguments_definition => arguments = Some(parse_arguments_definition(pair, pc)?),
             Rule::type_ => {
-                let pos = pc.step(&pair);
-                type_ = Some(Positioned::new(parse_type(pair, pc)?, pos));
+                let start = pc.start(&pair);
+                type_ = Some(Positioned::new(parse_type(pair, pc)?, pc.finish(start)));
             }
             Rule::directives => directives = Some(parse_directives(pair, pc)?),
             _ => unreachable!(),
@@ -408,7 +452,7 @@ fn parse_field_definition(
             ty: type_.unwrap(),
             directives: directives.unwrap_or_default(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
@@ -430,7 +474,7 @@ fn parse_object_type(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
 ) -> Result<Positioned<ObjectType>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut description = None;
     let mut extend = false;
     let mut name = None;
@@ -441,10 +485,18 @@ fn parse_object_type(
     for pair in pair.into_inner() {
         match pair.as_rule() {
             Rule::string => {
-                description = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair)))
+                description = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
             }
             Rule::extend => extend = true,
-            Rule::name => name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
+            Rule::name => {
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
+            }
             Rule::implements_interfaces => {
                 implements_interfaces = Some(parse_implements_interfaces(pair, pc)?)
             }
@@ -463,7 +515,7 @@ fn parse_object_type(
             directives: directives.unwrap_or_default(),
             fields: fields.unwrap_or_default(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
@@ -471,7 +523,7 @@ fn parse_interface_type(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
 ) -> Result<Positioned<InterfaceType>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut description = None;
     let mut extend = false;
     let mut name = None;
@@ -481,10 +533,18 @@ fn parse_interface_type(
     for pair in pair.into_inner() {
         match pair.as_rule() {
             Rule::string => {
-                description = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair)))
+                description = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
             }
             Rule::extend => extend = true,
-            Rule::name => name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
+            Rule::name => {
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
+            }
             Rule::directives => directives = Some(parse_directives(pair, pc)?),
             Rule::fields_definition => fields = Some(parse_fields_definition(pair, pc)?),
             _ => unreachable!(),
@@ -499,7 +559,7 @@ fn parse_interface_type(
             directives: directives.unwrap_or_default(),
             fields: fields.unwrap_or_default(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
@@ -510,7 +570,10 @@ fn parse_union_members(
     let mut members = Vec::new();
     for pair in pair.into_inner() {
         match pair.as_rule() {
-            Rule::name => members.push(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
+            Rule::name => members.push(Positioned::new(
+                pair.as_str().to_string(),
+                pc.span_of(&pair),
+            )),
             _ => unreachable!(),
         }
     }
@@ -521,7 +584,7 @@ fn parse_union_type(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
 ) -> Result<Positioned<UnionType>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut description = None;
     let mut extend = false;
     let mut name = None;
@@ -531,10 +594,18 @@ fn parse_union_type(
     for pair in pair.into_inner() {
         match pair.as_rule() {
             Rule::string => {
-                description = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair)))
+                description = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
             }
             Rule::extend => extend = true,
-            Rule::name => name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
+            Rule::name => {
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
+            }
             Rule::directives => directives = Some(parse_directives(pair, pc)?),
             Rule::union_member_types => members = Some(parse_union_members(pair, pc)?),
             _ => unreachable!(),
@@ -549,7 +620,7 @@ fn parse_union_type(
             directives: directives.unwrap_or_default(),
             members: members.unwrap_or_default(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
@@ -557,16 +628,24 @@ fn parse_enum_value(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
 ) -> Result<Positioned<EnumValue>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut description = None;
     let mut name = None;
     let mut directives = None;
     for pair in pair.into_inner() {
         match pair.as_rule() {
             Rule::string => {
-                description = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair)))
+                description = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
+            }
+            Rule::name => {
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
             }
-            Rule::name => name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
             Rule::directives => directives = Some(parse_directives(pair, pc)?),
             _ => unreachable!(),
         }
@@ -577,7 +656,7 @@ fn parse_enum_value(
             name: name.unwrap(),
             directives: directives.unwrap_or_default(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
@@ -596,7 +675,7 @@ fn parse_enum_values(
 }
 
 fn parse_enum_type(pair: Pair<Rule>, pc: &mut PositionCalculator) -> Result<Positioned<EnumType>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut description = None;
     let mut extend = false;
     let mut name = None;
@@ -606,10 +685,18 @@ fn parse_enum_type(pair: Pair<Rule>, pc: &mut PositionCalculator) -> Result<Posi
     for pair in pair.into_inner() {
         match pair.as_rule() {
             Rule::string => {
-                description = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair)))
+                description = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
             }
             Rule::extend => extend = true,
-            Rule::name => name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
+            Rule::name => {
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
+            }
             Rule::directives => directives = Some(parse_directives(pair, pc)?),
             Rule::enum_values_definition => values = Some(parse_enum_values(pair, pc)?),
             _ => unreachable!(),
@@ -624,7 +711,7 @@ fn parse_enum_type(pair: Pair<Rule>, pc: &mut PositionCalculator) -> Result<Posi
             directives: directives.unwrap_or_default(),
             values: values.unwrap_or_default(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
@@ -632,7 +719,7 @@ fn parse_input_type(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
 ) -> Result<Positioned<InputObjectType>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut description = None;
     let mut extend = false;
     let mut name = None;
@@ -642,10 +729,18 @@ fn parse_input_type(
     for pair in pair.into_inner() {
         match pair.as_rule() {
             Rule::string => {
-                description = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair)))
+                description = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
             }
             Rule::extend => extend = true,
-            Rule::name => name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
+            Rule::name => {
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
+            }
             Rule::directives => directives = Some(parse_directives(pair, pc)?),
             Rule::input_fields_definition => fields = Some(parse_arguments_definition(pair, pc)?),
             _ => unreachable!(),
@@ -660,7 +755,7 @@ fn parse_input_type(
             directives: directives.unwrap_or_default(),
             fields: fields.unwrap_or_default(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
@@ -672,7 +767,7 @@ fn parse_directive_locations(
     for pair in pair.into_inner() {
         match pair.as_rule() {
             Rule::directive_location => {
-                let pos = pc.step(&pair);
+                let start = pc.start(&pair);
                 let loc = match pair.as_str() {
                     "QUERY" => DirectiveLocation::Query,
                     "MUTATION" => DirectiveLocation::Mutation,
@@ -694,7 +789,7 @@ fn parse_directive_locations(
                     "INPUT_FIELD_DEFINITION" => DirectiveLocation::InputFieldDefinition,
                     _ => unreachable!(),
                 };
-                locations.push(Positioned::new(loc, pos));
+                locations.push(Positioned::new(loc, pc.finish(start)));
             }
             _ => unreachable!(),
         }
@@ -706,7 +801,7 @@ fn parse_directive_definition(
     pair: Pair<Rule>,
     pc: &mut PositionCalculator,
 ) -> Result<Positioned<DirectiveDefinition>> {
-    let pos = pc.step(&pair);
+    let start = pc.start(&pair);
     let mut description = None;
     let mut name = None;
     let mut arguments = None;
@@ -715,9 +810,17 @@ fn parse_directive_definition(
     for pair in pair.into_inner() {
         match pair.as_rule() {
             Rule::string => {
-                description = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair)))
+                description = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
+            }
+            Rule::name => {
+                name = Some(Positioned::new(
+                    pair.as_str().to_string(),
+                    pc.span_of(&pair),
+                ))
             }
-            Rule::name => name = Some(Positioned::new(pair.as_str().to_string(), pc.step(&pair))),
             Rule::arguments_definition => arguments = Some(parse_arguments_definition(pair, pc)?),
             Rule::directive_locations => locations = Some(parse_directive_locations(pair, pc)?),
             _ => unreachable!(),
@@ -731,13 +834,14 @@ fn parse_directive_definition(
             arguments: arguments.unwrap(),
             locations: locations.unwrap(),
         },
-        pos,
+        pc.finish(start),
     ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Pos;
     use std::fs;
 
     #[test]
@@ -758,4 +862,24 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_unique_input_field_names() {
+        parse_schema("type Query { a(x: In = { a: 1, b: { a: 2 } }): Int }").unwrap();
+
+        let err = parse_schema("type Query { a(x: In = { a: 1, b: 2, a: 3 }): Int }").unwrap_err();
+        assert_eq!(
+            err.pos,
+            Pos {
+                line: 1,
+                column: 38
+            }
+        );
+        assert_eq!(
+            err.message,
+            "There can only be one input field named \"a\"."
+        );
+
+        assert!(parse_schema("type Query @dir(x: { a: 1, a: 2 }) { a: Int }").is_err());
+    }
 }
@@ -1,49 +1,115 @@
-use crate::{Error, Pos, Result};
+use crate::descent_parser::token_len;
+use crate::{Error, Pos, Result, Span};
 use arrayvec::ArrayVec;
 use pest::iterators::Pair;
 use pest::RuleType;
-use std::iter::Peekable;
-use std::str::Chars;
 
 pub struct PositionCalculator<'a> {
-    input: Peekable<Chars<'a>>,
+    input: &'a str,
     pos: usize,
     line: usize,
     column: usize,
+    last_end: usize,
 }
 
 impl<'a> PositionCalculator<'a> {
     pub fn new(input: &'a str) -> PositionCalculator<'a> {
         Self {
-            input: input.chars().peekable(),
+            input,
             pos: 0,
             line: 1,
             column: 1,
+            last_end: 0,
         }
     }
 
     pub fn step<R: RuleType>(&mut self, pair: &Pair<R>) -> Pos {
         let pos = pair.as_span().start();
         debug_assert!(pos >= self.pos);
-        for _ in 0..pos - self.pos {
-            match self.input.next() {
-                Some('\r') => {
-                    if let Some(&'\n') = self.input.peek() {
-                        self.input.next();
+        self.position(pos)
+    }
+
+    /// Returns the start of the node of the pair, the span is computed by `finish` once the
+    /// children are parsed, so that the positions are computed in order.
+    pub fn start<R: RuleType>(&mut self, pair: &Pair<R>) -> NodeStart {
+        NodeStart {
+            pos: self.step(pair),
+            offset: pair.as_span().start(),
+            end: pair.as_span().end(),
+        }
+    }
+
+    /// The span of a pair can end with the whitespace and comments skipped before an optional rule
+    /// which did not match, so the node ends with its last token. The children are finished before
+    /// their parent, only the tokens after the last of them are read.
+    pub fn finish(&mut self, start: NodeStart) -> Span {
+        let from = self.last_end.max(start.offset).min(start.end);
+        let end = last_token_end(&self.input[from..start.end])
+            .map(|end| from + end)
+            .unwrap_or(from);
+        self.last_end = end;
+        self.span(start.pos, start.offset, end)
+    }
+
+    /// Returns the span of a pair without children.
+    pub fn span_of<R: RuleType>(&mut self, pair: &Pair<R>) -> Span {
+        let start = self.start(pair);
+        self.finish(start)
+    }
+
+    pub fn span(&mut self, start: Pos, start_offset: usize, end_offset: usize) -> Span {
+        Span {
+            start,
+            end: self.position(end_offset),
+            start_offset,
+            end_offset,
+        }
+    }
+
+    /// Returns the position of the byte offset.
+    ///
+    /// It is fast when the offsets are increasing or go back on the same line, otherwise it has
+    /// to look for the start of the line.
+    pub fn position(&mut self, pos: usize) -> Pos {
+        if pos < self.pos {
+            let skipped = &self.input[pos..self.pos];
+            let lines = skipped.matches('\n').count();
+            if lines == 0 {
+                self.column -= skipped.chars().count();
+            } else {
+                let line_start = self.input[..pos]
+                    .rfind('\n')
+                    .map(|idx| idx + 1)
+                    .unwrap_or(0);
+                self.line -= lines;
+                self.column = 1 + self.input[line_start..pos].chars().count();
+            }
+            self.pos = pos;
+            return Pos {
+                line: self.line,
+                column: self.column,
+            };
+        }
+
+        let mut chars = self.input[self.pos..pos].chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\r' => {
+                    if let Some(&'\n') = chars.peek() {
+                        chars.next();
                         self.line += 1;
                         self.column = 1;
                     } else {
                         self.column += 1;
                     }
                 }
-                Some('\n') => {
+                '\n' => {
                     self.line += 1;
                     self.column = 1;
                 }
-                Some(_) => {
+                _ => {
                     self.column += 1;
                 }
-                None => break,
             }
         }
         self.pos = pos;
@@ -54,6 +120,36 @@ impl<'a> PositionCalculator<'a> {
     }
 }
 
+/// Start of a node parsed from a pair.
+#[derive(Clone, Copy)]
+pub struct NodeStart {
+    pos: Pos,
+    offset: usize,
+    end: usize,
+}
+
+/// Returns the end of the last token of the input, `None` if there is none.
+fn last_token_end(input: &str) -> Option<usize> {
+    let mut from = 0;
+    let mut end = None;
+    while from < input.len() {
+        match input.as_bytes()[from] {
+            b' ' | b',' | b'\t' | b'\n' | b'\r' => from += 1,
+            b'#' => {
+                from += input[from..]
+                    .find(&['\n', '\r'][..])
+                    .unwrap_or(input.len() - from)
+            }
+            _ if input[from..].starts_with('\u{feff}') => from += '\u{feff}'.len_utf8(),
+            _ => {
+                from = (from + token_len(&input[from..]).max(1)).min(input.len());
+                end = Some(from);
+            }
+        }
+    }
+    end
+}
+
 pub fn unquote_string(s: &str, pos: Pos) -> Result<String> {
     let s = if s.starts_with(r#"""""#) {
         &s[3..s.len() - 3]
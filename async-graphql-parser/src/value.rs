@@ -1,12 +1,44 @@
+use crate::printer::Printer;
 use std::collections::BTreeMap;
 use std::fmt;
 use std::fmt::Formatter;
 use std::fs::File;
+use std::sync::Arc;
+
+/// Content of an uploaded file
+pub enum UploadContent {
+    /// The file is kept in memory.
+    Memory(Arc<[u8]>),
+
+    /// The file is stored in a temporary file.
+    File(File),
+}
+
+impl From<File> for UploadContent {
+    fn from(file: File) -> Self {
+        UploadContent::File(file)
+    }
+}
+
+impl From<Vec<u8>> for UploadContent {
+    fn from(data: Vec<u8>) -> Self {
+        UploadContent::Memory(data.into())
+    }
+}
+
+impl Clone for UploadContent {
+    fn clone(&self) -> Self {
+        match self {
+            UploadContent::Memory(data) => UploadContent::Memory(data.clone()),
+            UploadContent::File(file) => UploadContent::File(file.try_clone().unwrap()),
+        }
+    }
+}
 
 pub struct UploadValue {
     pub filename: String,
     pub content_type: Option<String>,
-    pub content: File,
+    pub content: UploadContent,
 }
 
 impl fmt::Debug for UploadValue {
@@ -20,7 +52,7 @@ impl Clone for UploadValue {
         Self {
             filename: self.filename.clone(),
             content_type: self.content_type.clone(),
-            content: self.content.try_clone().unwrap(),
+            content: self.content.clone(),
         }
     }
 }
@@ -91,61 +123,9 @@ impl PartialEq for Value {
     }
 }
 
-fn write_quoted(s: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(f, "\"")?;
-    for c in s.chars() {
-        match c {
-            '\r' => write!(f, "\r")?,
-            '\n' => writeln!(f)?,
-            '\t' => write!(f, "\t")?,
-            '"' => write!(f, "\"")?,
-            '\\' => write!(f, "\\")?,
-            '\u{0020}'..='\u{FFFF}' => write!(f, "{}", c)?,
-            _ => write!(f, "\\u{:04}", c as u32).unwrap(),
-        }
-    }
-    write!(f, "\"")
-}
-
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Value::Variable(name) => write!(f, "${}", name),
-            Value::Int(num) => write!(f, "{}", *num),
-            Value::Float(val) => write!(f, "{}", *val),
-            Value::String(ref val) => write_quoted(val, f),
-            Value::Boolean(true) => write!(f, "true"),
-            Value::Boolean(false) => write!(f, "false"),
-            Value::Null => write!(f, "null"),
-            Value::Enum(ref name) => write!(f, "{}", name),
-            Value::List(ref items) => {
-                write!(f, "[")?;
-                if !items.is_empty() {
-                    write!(f, "{}", items[0])?;
-                    for item in &items[1..] {
-                        write!(f, ", ")?;
-                        write!(f, "{}", item)?;
-                    }
-                }
-                write!(f, "]")
-            }
-            Value::Object(items) => {
-                write!(f, "{{")?;
-                let mut first = true;
-                for (name, value) in items {
-                    if first {
-                        first = false;
-                    } else {
-                        write!(f, ", ")?;
-                    }
-                    write!(f, "{}", name)?;
-                    write!(f, ": ")?;
-                    write!(f, "{}", value)?;
-                }
-                write!(f, "}}")
-            }
-            Value::Upload(_) => write!(f, "null"),
-        }
+        Printer::new(f, true).value(self)
     }
 }
 
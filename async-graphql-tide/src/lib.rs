@@ -5,21 +5,29 @@
 #![allow(clippy::needless_doctest_main)]
 #![forbid(unsafe_code)]
 
-use async_graphql::http::{multipart_stream, GQLRequest, GQLResponse, StreamBody};
+mod subscription;
+
+use async_graphql::http::{
+    multipart_stream_with_format, GQLRequest, GQLResponse, MultipartStreamFormat, ResponseFormat,
+    StreamBody,
+};
 use async_graphql::{
     IntoQueryBuilder, IntoQueryBuilderOpts, ObjectType, QueryBuilder, QueryResponse, Schema,
     StreamResponse, SubscriptionType,
 };
 use async_trait::async_trait;
-use futures::channel::mpsc;
+use futures::channel::{mpsc, oneshot};
 use futures::io::BufReader;
-use futures::{SinkExt, StreamExt};
+use futures::{future, SinkExt, StreamExt};
+use std::convert::TryFrom;
 use std::str::FromStr;
 use tide::{
     http::{headers, Method},
     Body, Request, Response, Status, StatusCode,
 };
 
+pub use subscription::{graphql_subscription, graphql_subscription_with_data};
+
 /// GraphQL request handler
 ///
 ///
@@ -82,11 +90,20 @@ where
     TideState: Send + Sync + 'static,
     F: Fn(QueryBuilder) -> QueryBuilder + Send,
 {
+    let accept = req.header(&headers::ACCEPT).map(|values| {
+        values
+            .iter()
+            .map(|value| value.as_str())
+            .collect::<Vec<_>>()
+            .join(",")
+    });
+    let format = ResponseFormat::from_accept(accept.as_deref());
     let query_builder = req.body_graphql_opts(opts).await?;
-    Response::new(StatusCode::Ok).body_graphql(
+    Response::new(StatusCode::Ok).body_graphql_with_format(
         query_builder_configuration(query_builder)
             .execute(&schema)
             .await,
+        format,
     )
 }
 
@@ -126,20 +143,52 @@ impl<State: Send + Sync + 'static> RequestExt<State> for Request<State> {
 ///
 pub trait ResponseExt: Sized {
     /// Set body as the result of a GraphQL query.
-    fn body_graphql(self, res: async_graphql::Result<QueryResponse>) -> tide::Result<Self>;
+    fn body_graphql(self, res: async_graphql::Result<QueryResponse>) -> tide::Result<Self> {
+        self.body_graphql_with_format(res, Default::default())
+    }
+
+    /// Similar to body_graphql, but you can set the format of the response, the status code is
+    /// set according to the format.
+    fn body_graphql_with_format(
+        self,
+        res: async_graphql::Result<QueryResponse>,
+        format: ResponseFormat,
+    ) -> tide::Result<Self>;
 
     /// Set body as the result of a GraphQL streaming query.
-    fn body_graphql_stream(self, res: StreamResponse) -> tide::Result<Self>;
+    fn body_graphql_stream(self, res: StreamResponse) -> tide::Result<Self> {
+        self.body_graphql_stream_with_format(res, Default::default())
+    }
+
+    /// Similar to body_graphql_stream, but you can set the format of the multipart response.
+    fn body_graphql_stream_with_format(
+        self,
+        res: StreamResponse,
+        format: MultipartStreamFormat,
+    ) -> tide::Result<Self>;
 }
 
 impl ResponseExt for Response {
-    fn body_graphql(self, res: async_graphql::Result<QueryResponse>) -> tide::Result<Self> {
+    fn body_graphql_with_format(
+        self,
+        res: async_graphql::Result<QueryResponse>,
+        format: ResponseFormat,
+    ) -> tide::Result<Self> {
         let mut resp = add_cache_control(self, &res);
-        resp.set_body(Body::from_json(&GQLResponse(res))?);
+        let gql_resp = GQLResponse(res);
+        if let Ok(status) = StatusCode::try_from(gql_resp.status_code(format)) {
+            AsMut::<tide::http::Response>::as_mut(&mut resp).set_status(status);
+        }
+        resp.set_body(Body::from_json(&gql_resp)?);
+        resp.set_content_type(format.content_type());
         Ok(resp)
     }
 
-    fn body_graphql_stream(mut self, res: StreamResponse) -> tide::Result<Self> {
+    fn body_graphql_stream_with_format(
+        mut self,
+        res: StreamResponse,
+        format: MultipartStreamFormat,
+    ) -> tide::Result<Self> {
         match res {
             StreamResponse::Single(res) => self.body_graphql(res),
             StreamResponse::Stream(stream) => {
@@ -147,18 +196,30 @@ impl ResponseExt for Response {
                 // I created an issue and got a reply that this might be fixed in the future.
                 // https://github.com/http-rs/http-types/pull/144
                 // Now I can only use forwarding to solve the problem.
-                let mut stream =
-                    Box::pin(multipart_stream(stream).map(Result::Ok::<_, std::io::Error>));
+                let mut stream = Box::pin(
+                    multipart_stream_with_format(stream, format)
+                        .map(Result::Ok::<_, std::io::Error>),
+                );
                 let (mut tx, rx) = mpsc::channel(0);
-                async_std::task::spawn(async move {
-                    while let Some(item) = stream.next().await {
-                        if tx.send(item).await.is_err() {
-                            return;
-                        }
-                    }
+                // Stop forwarding as soon as the body is dropped, the pending resolvers of the
+                // query are cancelled when the stream is dropped.
+                let (closed_tx, closed_rx) = oneshot::channel::<()>();
+                let rx = rx.map(move |item| {
+                    let _closed_tx = &closed_tx;
+                    item
                 });
+                async_std::task::spawn(future::select(
+                    Box::pin(async move {
+                        while let Some(item) = stream.next().await {
+                            if tx.send(item).await.is_err() {
+                                return;
+                            }
+                        }
+                    }),
+                    closed_rx,
+                ));
                 self.set_body(Body::from_reader(BufReader::new(StreamBody::new(rx)), None));
-                self.insert_header(tide::http::headers::CONTENT_TYPE, "multipart/mixed");
+                self.insert_header(tide::http::headers::CONTENT_TYPE, format.content_type());
                 Ok(self)
             }
         }
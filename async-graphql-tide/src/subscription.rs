@@ -0,0 +1,185 @@
+use async_graphql::{Data, FieldResult, ObjectType, Schema, SubscriptionType, WebSocketTransport};
+use async_tungstenite::tungstenite::protocol::{Message, Role};
+use async_tungstenite::tungstenite::{self, handshake::server::create_response};
+use async_tungstenite::WebSocketStream;
+use bytes::Bytes;
+use futures::channel::mpsc;
+use futures::io::{AsyncRead, AsyncWrite};
+use futures::{select, SinkExt, Stream, StreamExt};
+use tide::{Request, Response, StatusCode};
+
+/// GraphQL subscription endpoint
+///
+/// It upgrades the request to a WebSocket connection that speaks the `graphql-ws` protocol.
+///
+/// # Examples
+///
+/// ```no_run
+/// use async_graphql::*;
+/// use async_std::task;
+/// use futures::Stream;
+/// use tide::Request;
+///
+/// struct QueryRoot;
+///
+/// #[Object]
+/// impl QueryRoot {}
+///
+/// struct SubscriptionRoot;
+///
+/// #[Subscription]
+/// impl SubscriptionRoot {
+///     async fn values(&self) -> impl Stream<Item = i32> {
+///         futures::stream::iter(0..10)
+///     }
+/// }
+///
+/// fn main() -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
+///     task::block_on(async {
+///         let mut app = tide::new();
+///         app.at("/subscriptions").get(|req: Request<()>| async move {
+///             let schema = Schema::new(QueryRoot, EmptyMutation, SubscriptionRoot);
+///             async_graphql_tide::graphql_subscription(req, schema).await
+///         });
+///         app.listen("0.0.0.0:8000").await?;
+///
+///         Ok(())
+///     })
+/// }
+/// ```
+pub async fn graphql_subscription<Query, Mutation, Subscription, TideState>(
+    req: Request<TideState>,
+    schema: Schema<Query, Mutation, Subscription>,
+) -> tide::Result<Response>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+    TideState: Send + Sync + 'static,
+{
+    upgrade(req, schema, WebSocketTransport::default()).await
+}
+
+/// GraphQL subscription endpoint
+///
+/// Specifies that a function converts the init payload to data.
+pub async fn graphql_subscription_with_data<Query, Mutation, Subscription, TideState, F>(
+    req: Request<TideState>,
+    schema: Schema<Query, Mutation, Subscription>,
+    init_context_data: F,
+) -> tide::Result<Response>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+    TideState: Send + Sync + 'static,
+    F: Fn(serde_json::Value) -> FieldResult<Data> + Send + Sync + 'static,
+{
+    upgrade(req, schema, WebSocketTransport::new(init_context_data)).await
+}
+
+async fn upgrade<Query, Mutation, Subscription, TideState>(
+    req: Request<TideState>,
+    schema: Schema<Query, Mutation, Subscription>,
+    transport: WebSocketTransport,
+) -> tide::Result<Response>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+    TideState: Send + Sync + 'static,
+{
+    // Let tungstenite validate the handshake and compute `Sec-WebSocket-Accept`.
+    let mut handshake = tungstenite::http::Request::get("/");
+    for name in &[
+        "Connection",
+        "Upgrade",
+        "Sec-WebSocket-Version",
+        "Sec-WebSocket-Key",
+    ] {
+        if let Some(value) = req.header(*name).and_then(|values| values.get(0)) {
+            handshake = handshake.header(*name, value.as_str());
+        }
+    }
+    let handshake_resp = match handshake
+        .body(())
+        .map_err(|err| err.to_string())
+        .and_then(|handshake| create_response(&handshake).map_err(|err| err.to_string()))
+    {
+        Ok(handshake_resp) => handshake_resp,
+        Err(err) => {
+            let mut resp = Response::new(StatusCode::BadRequest);
+            resp.set_body(err);
+            return Ok(resp);
+        }
+    };
+
+    let mut resp = Response::new(StatusCode::SwitchingProtocols);
+    for (name, value) in handshake_resp.headers() {
+        if let Ok(value) = value.to_str() {
+            resp.insert_header(name.as_str(), value);
+        }
+    }
+    let offers_graphql_ws = req
+        .header("Sec-WebSocket-Protocol")
+        .into_iter()
+        .flat_map(|values| values.iter())
+        .flat_map(|value| value.as_str().split(','))
+        .any(|protocol| protocol.trim() == "graphql-ws");
+    if offers_graphql_ws {
+        resp.insert_header("Sec-WebSocket-Protocol", "graphql-ws");
+    }
+
+    let upgrade_receiver = AsMut::<tide::http::Response>::as_mut(&mut resp)
+        .recv_upgrade()
+        .await;
+    async_std::task::spawn(async move {
+        if let Some(connection) = upgrade_receiver.await {
+            let websocket = WebSocketStream::from_raw_socket(connection, Role::Server, None).await;
+            let (stx, srx) = schema.subscription_connection(transport);
+            serve(websocket, stx, srx).await;
+        }
+    });
+
+    Ok(resp)
+}
+
+/// Forward the messages between the WebSocket connection and the subscription connection.
+async fn serve<S>(
+    websocket: WebSocketStream<S>,
+    mut stx: mpsc::UnboundedSender<Bytes>,
+    srx: impl Stream<Item = Bytes> + Unpin,
+) where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (mut tx, rx) = websocket.split();
+    let mut rx = rx.fuse();
+    let mut srx = srx.fuse();
+
+    loop {
+        select! {
+            bytes = srx.next() => {
+                if let Some(bytes) = bytes {
+                    if let Ok(text) = String::from_utf8(bytes.to_vec()) {
+                        if tx.send(Message::Text(text)).await.is_err() {
+                            return;
+                        }
+                    }
+                } else {
+                    return;
+                }
+            }
+            msg = rx.next() => {
+                if let Some(Ok(msg)) = msg {
+                    if let Message::Text(text) = msg {
+                        if stx.send(Bytes::from(text)).await.is_err() {
+                            return;
+                        }
+                    }
+                } else {
+                    return;
+                }
+            }
+        }
+    }
+}
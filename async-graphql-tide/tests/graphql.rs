@@ -254,3 +254,169 @@ fn upload() -> Result<()> {
         Ok(())
     })
 }
+
+#[test]
+fn defer() -> Result<()> {
+    smol::run(async {
+        let listen_addr = test_utils::find_listen_addr().await;
+
+        let server = Task::<Result<()>>::spawn(async move {
+            use async_graphql_tide::{RequestExt, ResponseExt};
+            use tide::{http::StatusCode, Request, Response};
+
+            struct QueryRoot;
+            #[Object]
+            impl QueryRoot {
+                async fn value(&self) -> i32 {
+                    10
+                }
+
+                async fn deferred(&self) -> Deferred<i32> {
+                    20.into()
+                }
+            }
+
+            let mut app = tide::new();
+            app.at("/").post(|req: Request<()>| async move {
+                let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+                let query_builder = req.body_graphql().await?;
+                Response::new(StatusCode::Ok)
+                    .body_graphql_stream(query_builder.execute_stream(&schema).await)
+            });
+            app.listen(&listen_addr).await?;
+
+            Ok(())
+        });
+
+        let client = Task::<Result<()>>::spawn(async move {
+            Timer::after(Duration::from_millis(300)).await;
+
+            let resp = reqwest::Client::new()
+                .post(format!("http://{}", listen_addr).as_str())
+                .body(r#"{"query":"{ value deferred @defer }"}"#)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .send()
+                .await?;
+
+            assert_eq!(resp.status(), reqwest::StatusCode::OK);
+            assert_eq!(
+                resp.headers()[reqwest::header::CONTENT_TYPE],
+                "multipart/mixed; boundary=\"-\"; deferSpec=20220824"
+            );
+            let string = resp.text().await?;
+            println!("{}", string);
+
+            let parts = string
+                .trim_end_matches("\r\n-----\r\n")
+                .split("\r\n---\r\n")
+                .skip(1)
+                .map(|part| serde_json::from_str(part.split_once("\r\n\r\n").unwrap().1))
+                .collect::<serde_json::Result<Vec<serde_json::Value>>>()?;
+            assert_eq!(
+                parts,
+                vec![
+                    json!({"data": {"value": 10, "deferred": null}, "hasNext": true}),
+                    json!({"incremental": [{"data": {"deferred": 20}, "path": [], "label": "deferred"}], "hasNext": true}),
+                    json!({"hasNext": false}),
+                ]
+            );
+
+            Ok(())
+        });
+
+        client.await?;
+        server.cancel().await;
+
+        Ok(())
+    })
+}
+
+#[test]
+fn content_negotiation() -> Result<()> {
+    smol::run(async {
+        let listen_addr = test_utils::find_listen_addr().await;
+
+        let server = Task::<Result<()>>::spawn(async move {
+            use tide::Request;
+
+            struct QueryRoot;
+            #[Object]
+            impl QueryRoot {
+                async fn value(&self) -> i32 {
+                    10
+                }
+            }
+
+            let mut app = tide::new();
+            app.at("/").post(|req: Request<()>| async move {
+                let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+                async_graphql_tide::graphql(req, schema, |query_builder| query_builder).await
+            });
+            app.listen(&listen_addr).await?;
+
+            Ok(())
+        });
+
+        let client = Task::<Result<()>>::spawn(async move {
+            Timer::after(Duration::from_millis(300)).await;
+
+            let resp = reqwest::Client::new()
+                .post(format!("http://{}", listen_addr).as_str())
+                .body("{ value }")
+                .header(reqwest::header::CONTENT_TYPE, "application/graphql")
+                .header(reqwest::header::ACCEPT, "application/graphql-response+json")
+                .send()
+                .await?;
+
+            assert_eq!(resp.status(), reqwest::StatusCode::OK);
+            assert_eq!(
+                resp.headers()[reqwest::header::CONTENT_TYPE],
+                "application/graphql-response+json"
+            );
+            let string = resp.text().await?;
+            assert_eq!(string, json!({"data": {"value": 10}}).to_string());
+
+            let resp = reqwest::Client::new()
+                .post(format!("http://{}", listen_addr).as_str())
+                .body("{ value")
+                .header(reqwest::header::CONTENT_TYPE, "application/graphql")
+                .header(reqwest::header::ACCEPT, "application/graphql-response+json")
+                .send()
+                .await?;
+            assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+
+            let resp = reqwest::Client::new()
+                .post(format!("http://{}", listen_addr).as_str())
+                .body("{ value }")
+                .header(reqwest::header::CONTENT_TYPE, "application/graphql")
+                .header(reqwest::header::ACCEPT, "application/json;q=0.5")
+                .header(reqwest::header::ACCEPT, "application/graphql-response+json")
+                .send()
+                .await?;
+            assert_eq!(resp.status(), reqwest::StatusCode::OK);
+            assert_eq!(
+                resp.headers()[reqwest::header::CONTENT_TYPE],
+                "application/graphql-response+json"
+            );
+
+            let resp = reqwest::Client::new()
+                .post(format!("http://{}", listen_addr).as_str())
+                .body("{ value")
+                .header(reqwest::header::CONTENT_TYPE, "application/graphql")
+                .send()
+                .await?;
+            assert_eq!(resp.status(), reqwest::StatusCode::OK);
+            assert_eq!(
+                resp.headers()[reqwest::header::CONTENT_TYPE],
+                "application/json"
+            );
+
+            Ok(())
+        });
+
+        client.await?;
+        server.cancel().await;
+
+        Ok(())
+    })
+}
@@ -0,0 +1,339 @@
+mod test_utils;
+use futures::{Stream, StreamExt};
+use serde_json::json;
+use smol::{Task, Timer};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+use tungstenite::Message;
+
+use async_graphql::*;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+#[test]
+fn subscription() -> Result<()> {
+    smol::run(async {
+        let listen_addr = test_utils::find_listen_addr().await;
+
+        let server = Task::<Result<()>>::spawn(async move {
+            use tide::Request;
+
+            struct Token(String);
+
+            struct QueryRoot;
+            #[Object]
+            impl QueryRoot {}
+
+            struct SubscriptionRoot;
+            #[Subscription]
+            impl SubscriptionRoot {
+                async fn values(&self, ctx: &Context<'_>) -> impl Stream<Item = String> {
+                    let token = ctx.data::<Token>().0.clone();
+                    futures::stream::iter(0..3).map(move |n| format!("{}:{}", token, n))
+                }
+            }
+
+            let mut app = tide::new();
+            app.at("/subscriptions").get(|req: Request<()>| async move {
+                let schema = Schema::new(QueryRoot, EmptyMutation, SubscriptionRoot);
+                async_graphql_tide::graphql_subscription_with_data(req, schema, |value| {
+                    let mut data = Data::default();
+                    if let Some(token) = value.get("token").and_then(|token| token.as_str()) {
+                        data.insert(Token(token.to_string()));
+                    }
+                    Ok(data)
+                })
+                .await
+            });
+            app.listen(&listen_addr).await?;
+
+            Ok(())
+        });
+
+        let client = Task::<Result<()>>::blocking(async move {
+            std::thread::sleep(Duration::from_millis(300));
+
+            let req =
+                tungstenite::http::Request::get(format!("ws://{}/subscriptions", listen_addr))
+                    .header("Sec-WebSocket-Protocol", "graphql-ws")
+                    .body(())?;
+            let (mut socket, resp) = tungstenite::connect(req)?;
+            assert_eq!(
+                resp.headers()
+                    .get("sec-websocket-protocol")
+                    .and_then(|value| value.to_str().ok()),
+                Some("graphql-ws")
+            );
+
+            socket.write_message(Message::Text(
+                json!({"type": "connection_init", "payload": {"token": "abc"}}).to_string(),
+            ))?;
+            assert_eq!(read_json(&mut socket)?, json!({"type": "connection_ack"}));
+
+            socket.write_message(Message::Text(
+                json!({
+                    "type": "start",
+                    "id": "1",
+                    "payload": {"query": "subscription { values }"},
+                })
+                .to_string(),
+            ))?;
+            for n in 0..3 {
+                assert_eq!(
+                    read_json(&mut socket)?,
+                    json!({
+                        "type": "data",
+                        "id": "1",
+                        "payload": {"data": {"values": format!("abc:{}", n)}},
+                    })
+                );
+            }
+
+            socket.close(None)?;
+            Ok(())
+        });
+
+        client.await?;
+        server.cancel().await;
+
+        Ok(())
+    })
+}
+
+#[test]
+fn not_websocket() -> Result<()> {
+    smol::run(async {
+        let listen_addr = test_utils::find_listen_addr().await;
+
+        let server = Task::<Result<()>>::spawn(async move {
+            use tide::Request;
+
+            struct QueryRoot;
+            #[Object]
+            impl QueryRoot {}
+
+            let mut app = tide::new();
+            app.at("/subscriptions").get(|req: Request<()>| async move {
+                let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+                async_graphql_tide::graphql_subscription(req, schema).await
+            });
+            app.listen(&listen_addr).await?;
+
+            Ok(())
+        });
+
+        let client = Task::<Result<()>>::spawn(async move {
+            Timer::after(Duration::from_millis(300)).await;
+
+            let resp =
+                reqwest::get(format!("http://{}/subscriptions", listen_addr).as_str()).await?;
+            assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+
+            Ok(())
+        });
+
+        client.await?;
+        server.cancel().await;
+
+        Ok(())
+    })
+}
+
+#[test]
+fn subprotocol_not_offered() -> Result<()> {
+    smol::run(async {
+        let listen_addr = test_utils::find_listen_addr().await;
+        let server = Task::<Result<()>>::spawn(serve_values(listen_addr));
+
+        let client = Task::<Result<()>>::blocking(async move {
+            std::thread::sleep(Duration::from_millis(300));
+
+            let (mut socket, resp) =
+                tungstenite::connect(format!("ws://{}/subscriptions", listen_addr))?;
+            assert!(resp.headers().get("sec-websocket-protocol").is_none());
+
+            socket.close(None)?;
+            Ok(())
+        });
+
+        client.await?;
+        server.cancel().await;
+
+        Ok(())
+    })
+}
+
+#[test]
+fn fragmented_message() -> Result<()> {
+    smol::run(async {
+        let listen_addr = test_utils::find_listen_addr().await;
+        let server = Task::<Result<()>>::spawn(serve_values(listen_addr));
+
+        let client = Task::<Result<()>>::blocking(async move {
+            std::thread::sleep(Duration::from_millis(300));
+
+            let mut stream = connect_raw(listen_addr)?;
+            let message = json!({"type": "connection_init"}).to_string();
+            let (first, second) = message.split_at(10);
+
+            // A ping between the fragments of a message.
+            stream.write_all(&client_frame(0x01, first.as_bytes(), true))?;
+            stream.write_all(&client_frame(0x89, b"ping", true))?;
+            stream.write_all(&client_frame(0x80, second.as_bytes(), true))?;
+            assert_eq!(read_frame(&mut stream)?, Some((0x8a, b"ping".to_vec())));
+            assert_eq!(
+                read_frame(&mut stream)?,
+                Some((
+                    0x81,
+                    json!({"type": "connection_ack"}).to_string().into_bytes()
+                ))
+            );
+
+            // A new data frame before the end of the fragmented message is an error.
+            stream.write_all(&client_frame(0x01, first.as_bytes(), true))?;
+            stream.write_all(&client_frame(0x81, message.as_bytes(), true))?;
+            assert_closed(&mut stream)?;
+
+            Ok(())
+        });
+
+        client.await?;
+        server.cancel().await;
+
+        Ok(())
+    })
+}
+
+#[test]
+fn unmasked_frame() -> Result<()> {
+    smol::run(async {
+        let listen_addr = test_utils::find_listen_addr().await;
+        let server = Task::<Result<()>>::spawn(serve_values(listen_addr));
+
+        let client = Task::<Result<()>>::blocking(async move {
+            std::thread::sleep(Duration::from_millis(300));
+
+            let mut stream = connect_raw(listen_addr)?;
+            let message = json!({"type": "connection_init"}).to_string();
+            stream.write_all(&client_frame(0x81, message.as_bytes(), false))?;
+            assert_closed(&mut stream)?;
+
+            Ok(())
+        });
+
+        client.await?;
+        server.cancel().await;
+
+        Ok(())
+    })
+}
+
+async fn serve_values(listen_addr: SocketAddr) -> Result<()> {
+    use tide::Request;
+
+    struct QueryRoot;
+    #[Object]
+    impl QueryRoot {}
+
+    struct SubscriptionRoot;
+    #[Subscription]
+    impl SubscriptionRoot {
+        async fn values(&self) -> impl Stream<Item = i32> {
+            futures::stream::iter(0..3)
+        }
+    }
+
+    let mut app = tide::new();
+    app.at("/subscriptions").get(|req: Request<()>| async move {
+        let schema = Schema::new(QueryRoot, EmptyMutation, SubscriptionRoot);
+        async_graphql_tide::graphql_subscription(req, schema).await
+    });
+    app.listen(&listen_addr).await?;
+
+    Ok(())
+}
+
+/// Opens a WebSocket connection without a WebSocket client, to send frames a client would not.
+fn connect_raw(listen_addr: SocketAddr) -> Result<TcpStream> {
+    let mut stream = TcpStream::connect(listen_addr)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    write!(
+        stream,
+        "GET /subscriptions HTTP/1.1\r\n\
+         Host: {}\r\n\
+         Connection: Upgrade\r\n\
+         Upgrade: websocket\r\n\
+         Sec-WebSocket-Version: 13\r\n\
+         Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+         Sec-WebSocket-Protocol: graphql-ws\r\n\r\n",
+        listen_addr
+    )?;
+
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        let mut byte = [0u8];
+        stream.read_exact(&mut byte)?;
+        head.push(byte[0]);
+    }
+    assert!(head.starts_with(b"HTTP/1.1 101"));
+    Ok(stream)
+}
+
+fn client_frame(first_byte: u8, payload: &[u8], masked: bool) -> Vec<u8> {
+    assert!(payload.len() < 126);
+    let mask = [1u8, 2, 3, 4];
+    let mut frame = vec![first_byte];
+    if masked {
+        frame.push(0x80 | payload.len() as u8);
+        frame.extend_from_slice(&mask);
+        frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+    } else {
+        frame.push(payload.len() as u8);
+        frame.extend_from_slice(payload);
+    }
+    frame
+}
+
+/// Reads an unmasked frame sent by the server, returns `None` at the end of the stream.
+fn read_frame(stream: &mut TcpStream) -> Result<Option<(u8, Vec<u8>)>> {
+    let mut header = [0u8; 2];
+    if let Err(err) = stream.read_exact(&mut header) {
+        return match err.kind() {
+            std::io::ErrorKind::UnexpectedEof | std::io::ErrorKind::ConnectionReset => Ok(None),
+            _ => Err(err.into()),
+        };
+    }
+    let len = match header[1] & 0x7f {
+        126 => {
+            let mut len = [0u8; 2];
+            stream.read_exact(&mut len)?;
+            u16::from_be_bytes(len) as usize
+        }
+        len => len as usize,
+    };
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload)?;
+    Ok(Some((header[0], payload)))
+}
+
+/// Asserts that the server closes the connection, with or without a close frame.
+fn assert_closed(stream: &mut TcpStream) -> Result<()> {
+    loop {
+        match read_frame(stream)? {
+            None | Some((0x88, _)) => return Ok(()),
+            Some((0x81, _)) => panic!("unexpected message"),
+            Some(_) => {}
+        }
+    }
+}
+
+fn read_json<S: std::io::Read + std::io::Write>(
+    socket: &mut tungstenite::WebSocket<S>,
+) -> Result<serde_json::Value> {
+    loop {
+        if let Message::Text(text) = socket.read_message()? {
+            return Ok(serde_json::from_str(&text)?);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Async-graphql integration with Tower
+
+#![warn(missing_docs)]
+#![allow(clippy::type_complexity)]
+#![allow(clippy::needless_doctest_main)]
+#![forbid(unsafe_code)]
+
+use async_graphql::http::{handle_request_opts, ResponseBody, StreamBody};
+use async_graphql::{IntoQueryBuilderOpts, ObjectType, QueryBuilder, Schema, SubscriptionType};
+use futures::future::BoxFuture;
+use futures::task::{Context, Poll};
+use futures::StreamExt;
+use hyper::{Body, Request, Response};
+use std::convert::Infallible;
+use std::sync::Arc;
+use tower_service::Service;
+
+/// GraphQL service
+///
+/// It handles GraphQL requests with `async_graphql::http::handle_request`, so it supports GET and
+/// POST requests, batching, uploads and streaming responses.
+///
+/// # Examples
+///
+/// ```no_run
+/// use async_graphql::*;
+/// use async_graphql_tower::GraphQLService;
+/// use hyper::service::make_service_fn;
+/// use hyper::Server;
+/// use std::convert::Infallible;
+///
+/// struct QueryRoot;
+///
+/// #[Object]
+/// impl QueryRoot {
+///     #[field(desc = "Returns the sum of a and b")]
+///     async fn add(&self, a: i32, b: i32) -> i32 {
+///         a + b
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+///     let service = GraphQLService::new(schema);
+///     let make_service = make_service_fn(move |_| {
+///         let service = service.clone();
+///         async move { Ok::<_, Infallible>(service) }
+///     });
+///     Server::bind(&([0, 0, 0, 0], 8000).into())
+///         .serve(make_service)
+///         .await
+///         .unwrap();
+/// }
+/// ```
+pub struct GraphQLService<Query, Mutation, Subscription> {
+    schema: Schema<Query, Mutation, Subscription>,
+    opts: Arc<IntoQueryBuilderOpts>,
+    query_builder_configuration: Arc<dyn Fn(QueryBuilder) -> QueryBuilder + Send + Sync>,
+}
+
+impl<Query, Mutation, Subscription> Clone for GraphQLService<Query, Mutation, Subscription> {
+    fn clone(&self) -> Self {
+        Self {
+            schema: self.schema.clone(),
+            opts: self.opts.clone(),
+            query_builder_configuration: self.query_builder_configuration.clone(),
+        }
+    }
+}
+
+impl<Query, Mutation, Subscription> GraphQLService<Query, Mutation, Subscription>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+{
+    /// Create a GraphQL service.
+    pub fn new(schema: Schema<Query, Mutation, Subscription>) -> Self {
+        Self::new_opts(schema, Default::default())
+    }
+
+    /// Similar to new, but you can set the options `IntoQueryBuilderOpts`.
+    pub fn new_opts(
+        schema: Schema<Query, Mutation, Subscription>,
+        opts: IntoQueryBuilderOpts,
+    ) -> Self {
+        Self {
+            schema,
+            opts: Arc::new(opts),
+            query_builder_configuration: Arc::new(|builder| builder),
+        }
+    }
+
+    /// Set a function that configures the `QueryBuilder` of every request, such as adding
+    /// per-request data.
+    pub fn query_builder_configuration<F>(mut self, f: F) -> Self
+    where
+        F: Fn(QueryBuilder) -> QueryBuilder + Send + Sync + 'static,
+    {
+        self.query_builder_configuration = Arc::new(f);
+        self
+    }
+
+    /// Returns the schema of this service.
+    pub fn schema(&self) -> &Schema<Query, Mutation, Subscription> {
+        &self.schema
+    }
+}
+
+impl<Query, Mutation, Subscription> Service<Request<Body>>
+    for GraphQLService<Query, Mutation, Subscription>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+{
+    type Response = Response<Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let schema = self.schema.clone();
+        let opts = self.opts.clone();
+        let query_builder_configuration = self.query_builder_configuration.clone();
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+            let req = Request::from_parts(parts, StreamBody::new(body));
+            let resp = handle_request_opts(
+                &schema,
+                req,
+                |builder| query_builder_configuration(builder),
+                &opts,
+            )
+            .await;
+            Ok(resp.map(|body| match body {
+                ResponseBody::Bytes(bytes) => Body::from(bytes),
+                ResponseBody::Stream(stream) => {
+                    Body::wrap_stream(stream.map(Result::<_, Infallible>::Ok))
+                }
+            }))
+        })
+    }
+}
@@ -0,0 +1,149 @@
+use async_graphql::*;
+use async_graphql_tower::GraphQLService;
+use hyper::{Body, Request, StatusCode};
+use tower::ServiceExt;
+
+struct Token(&'static str);
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn token(&self, ctx: &Context<'_>) -> Option<&str> {
+        ctx.data_opt::<Token>().map(|token| token.0)
+    }
+
+    async fn add(&self, a: i32, b: i32) -> i32 {
+        a + b
+    }
+
+    async fn value(&self) -> Deferred<i32> {
+        10.into()
+    }
+}
+
+async fn body_string(body: Body) -> String {
+    let bytes = hyper::body::to_bytes(body).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn quickstart() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    let service = GraphQLService::new(schema);
+
+    let resp = service
+        .clone()
+        .oneshot(
+            Request::post("/")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"query":"{ add(a: 10, b: 20) }"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "application/json");
+    assert_eq!(
+        body_string(resp.into_body()).await,
+        json_string(serde_json::json!({"data": {"add": 30}}))
+    );
+
+    let resp = service
+        .oneshot(
+            Request::get("/?query=%7B%20add%28a%3A%201%2C%20b%3A%202%29%20%7D")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        body_string(resp.into_body()).await,
+        json_string(serde_json::json!({"data": {"add": 3}}))
+    );
+}
+
+#[tokio::test]
+async fn batch() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    let resp = GraphQLService::new(schema)
+        .oneshot(
+            Request::post("/")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    r#"[{"query":"{ add(a: 1, b: 2) }"},{"query":"{ add(a: 3, b: 4) }"}]"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        body_string(resp.into_body()).await,
+        json_string(serde_json::json!([
+            {"data": {"add": 3}},
+            {"data": {"add": 7}},
+        ]))
+    );
+}
+
+#[tokio::test]
+async fn defer() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    let resp = GraphQLService::new(schema)
+        .oneshot(
+            Request::post("/")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    r#"{"query":"{ add(a: 1, b: 2) value @defer }"}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers()["content-type"],
+        "multipart/mixed; boundary=\"-\"; deferSpec=20220824"
+    );
+    let body = body_string(resp.into_body()).await;
+    assert!(body.contains(r#"{"data":{"add":3,"value":null},"hasNext":true}"#));
+    assert!(body.contains(
+        r#"{"incremental":[{"data":{"value":10},"path":[],"label":"value"}],"hasNext":true}"#
+    ));
+    assert!(body.contains(r#"{"hasNext":false}"#));
+}
+
+#[tokio::test]
+async fn query_builder_configuration() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    let resp = GraphQLService::new(schema)
+        .query_builder_configuration(|builder| builder.data(Token("abc")))
+        .oneshot(
+            Request::post("/")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"query":"{ token }"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        body_string(resp.into_body()).await,
+        json_string(serde_json::json!({"data": {"token": "abc"}}))
+    );
+}
+
+#[tokio::test]
+async fn method_not_allowed() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    let resp = GraphQLService::new(schema)
+        .oneshot(Request::put("/").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
+
+fn json_string(value: serde_json::Value) -> String {
+    serde_json::to_string(&value).unwrap()
+}
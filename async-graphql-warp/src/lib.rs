@@ -5,7 +5,9 @@
 #![allow(clippy::needless_doctest_main)]
 #![forbid(unsafe_code)]
 
-use async_graphql::http::{multipart_stream, GQLRequest, StreamBody};
+use async_graphql::http::{
+    multipart_stream_with_format, GQLRequest, MultipartStreamFormat, ResponseFormat, StreamBody,
+};
 use async_graphql::{
     Data, FieldResult, IntoQueryBuilder, IntoQueryBuilderOpts, ObjectType, QueryBuilder,
     QueryResponse, Schema, StreamResponse, SubscriptionType, WebSocketTransport,
@@ -13,8 +15,8 @@ use async_graphql::{
 use bytes::Bytes;
 use futures::select;
 use futures::{SinkExt, StreamExt};
-use hyper::header::HeaderValue;
-use hyper::{Body, Method};
+use hyper::header::{HeaderMap, HeaderValue, ACCEPT};
+use hyper::{Body, Method, StatusCode};
 use std::convert::Infallible;
 use std::sync::Arc;
 use warp::filters::ws::Message;
@@ -36,9 +38,37 @@ impl std::fmt::Debug for BadRequest {
 
 impl Reject for BadRequest {}
 
+/// The formats of the response selected from the `Accept` headers of the request
+///
+/// It is extracted by the `graphql` filter, create `GQLResponse` or `GQLResponseStream` with it to
+/// reply in the format accepted by the client.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Accept {
+    /// The format of a complete response.
+    pub format: ResponseFormat,
+
+    /// The format of a multipart response.
+    pub stream_format: MultipartStreamFormat,
+}
+
+impl Accept {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let accept = headers
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(",");
+        Accept {
+            format: ResponseFormat::from_accept(Some(&accept)),
+            stream_format: MultipartStreamFormat::from_accept(Some(&accept)),
+        }
+    }
+}
+
 /// GraphQL request filter
 ///
-/// It outputs a tuple containing the `Schema` and `QuertBuilder`.
+/// It outputs a tuple containing the `Schema`, the `QuertBuilder` and the `Accept` formats.
 ///
 /// # Examples
 /// *[Full Example](<https://github.com/async-graphql/examples/blob/master/warp/starwars/src/main.rs>)*
@@ -63,15 +93,15 @@ impl Reject for BadRequest {}
 /// #[tokio::main]
 /// async fn main() {
 ///     let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
-///     let filter = async_graphql_warp::graphql(schema).and_then(|(schema, builder): (_, QueryBuilder)| async move {
-///         Ok::<_, Infallible>(GQLResponse::from(builder.execute(&schema).await))
+///     let filter = async_graphql_warp::graphql(schema).and_then(|(schema, builder, accept): (_, QueryBuilder, Accept)| async move {
+///         Ok::<_, Infallible>(GQLResponse::from((builder.execute(&schema).await, accept)))
 ///     });
 ///     warp::serve(filter).run(([0, 0, 0, 0], 8000)).await;
 /// }
 /// ```
 pub fn graphql<Query, Mutation, Subscription>(
     schema: Schema<Query, Mutation, Subscription>,
-) -> BoxedFilter<((Schema<Query, Mutation, Subscription>, QueryBuilder),)>
+) -> BoxedFilter<((Schema<Query, Mutation, Subscription>, QueryBuilder, Accept),)>
 where
     Query: ObjectType + Send + Sync + 'static,
     Mutation: ObjectType + Send + Sync + 'static,
@@ -84,7 +114,7 @@ where
 pub fn graphql_opts<Query, Mutation, Subscription>(
     schema: Schema<Query, Mutation, Subscription>,
     opts: IntoQueryBuilderOpts,
-) -> BoxedFilter<((Schema<Query, Mutation, Subscription>, QueryBuilder),)>
+) -> BoxedFilter<((Schema<Query, Mutation, Subscription>, QueryBuilder, Accept),)>
 where
     Query: ObjectType + Send + Sync + 'static,
     Mutation: ObjectType + Send + Sync + 'static,
@@ -95,6 +125,9 @@ where
         .and(warp::method())
         .and(warp::query::raw().or(warp::any().map(String::new)).unify())
         .and(warp::header::optional::<String>("content-type"))
+        .and(
+            warp::header::headers_cloned().map(|headers: HeaderMap| Accept::from_headers(&headers)),
+        )
         .and(warp::body::stream())
         .and(warp::any().map(move || opts.clone()))
         .and(warp::any().map(move || schema.clone()))
@@ -102,24 +135,24 @@ where
             |method,
              query: String,
              content_type,
+             accept,
              body,
              opts: Arc<IntoQueryBuilderOpts>,
              schema| async move {
                 if method == Method::GET {
-                    let gql_request: GQLRequest =
-                        serde_urlencoded::from_str(&query)
-                            .map_err(|err| warp::reject::custom(BadRequest(err.into())))?;
+                    let gql_request: GQLRequest = serde_urlencoded::from_str(&query)
+                        .map_err(|err| warp::reject::custom(BadRequest(err.into())))?;
                     let builder = gql_request
                         .into_query_builder_opts(&opts)
                         .await
                         .map_err(|err| warp::reject::custom(BadRequest(err.into())))?;
-                    Ok::<_, Rejection>((schema, builder))
+                    Ok::<_, Rejection>((schema, builder, accept))
                 } else {
                     let builder = (content_type, StreamBody::new(body))
                         .into_query_builder_opts(&opts)
                         .await
                         .map_err(|err| warp::reject::custom(BadRequest(err.into())))?;
-                    Ok::<_, Rejection>((schema, builder))
+                    Ok::<_, Rejection>((schema, builder, accept))
                 }
             },
         )
@@ -281,11 +314,27 @@ where
 }
 
 /// GraphQL reply
-pub struct GQLResponse(async_graphql::Result<QueryResponse>);
+pub struct GQLResponse(async_graphql::Result<QueryResponse>, ResponseFormat);
+
+impl GQLResponse {
+    /// Specify the format of the response, the default is `ResponseFormat::Json`.
+    ///
+    /// A response created from the `Accept` extracted by the `graphql` filter already has the
+    /// format accepted by the client.
+    pub fn with_format(self, format: ResponseFormat) -> Self {
+        GQLResponse(self.0, format)
+    }
+}
 
 impl From<async_graphql::Result<QueryResponse>> for GQLResponse {
     fn from(resp: async_graphql::Result<QueryResponse>) -> Self {
-        GQLResponse(resp)
+        GQLResponse(resp, Default::default())
+    }
+}
+
+impl From<(async_graphql::Result<QueryResponse>, Accept)> for GQLResponse {
+    fn from((resp, accept): (async_graphql::Result<QueryResponse>, Accept)) -> Self {
+        GQLResponse(resp, accept.format)
     }
 }
 
@@ -301,11 +350,16 @@ fn add_cache_control(http_resp: &mut Response, resp: &async_graphql::Result<Quer
 
 impl Reply for GQLResponse {
     fn into_response(self) -> Response {
+        let format = self.1;
         let gql_resp = async_graphql::http::GQLResponse(self.0);
-        let mut resp = warp::reply::with_header(
-            warp::reply::json(&gql_resp),
-            "content-type",
-            "application/json",
+        let status = StatusCode::from_u16(gql_resp.status_code(format)).unwrap_or(StatusCode::OK);
+        let mut resp = warp::reply::with_status(
+            warp::reply::with_header(
+                warp::reply::json(&gql_resp),
+                "content-type",
+                format.content_type(),
+            ),
+            status,
         )
         .into_response();
         add_cache_control(&mut resp, &gql_resp.0);
@@ -314,25 +368,49 @@ impl Reply for GQLResponse {
 }
 
 /// GraphQL streaming reply
-pub struct GQLResponseStream(StreamResponse);
+pub struct GQLResponseStream(StreamResponse, Accept);
+
+impl GQLResponseStream {
+    /// Specify the format of the multipart response, the default is `MultipartStreamFormat::Incremental`.
+    ///
+    /// A response created from the `Accept` extracted by the `graphql` filter already has the
+    /// format accepted by the client.
+    pub fn with_format(self, format: MultipartStreamFormat) -> Self {
+        GQLResponseStream(
+            self.0,
+            Accept {
+                stream_format: format,
+                ..self.1
+            },
+        )
+    }
+}
 
 impl From<StreamResponse> for GQLResponseStream {
     fn from(resp: StreamResponse) -> Self {
-        GQLResponseStream(resp)
+        GQLResponseStream(resp, Default::default())
+    }
+}
+
+impl From<(StreamResponse, Accept)> for GQLResponseStream {
+    fn from((resp, accept): (StreamResponse, Accept)) -> Self {
+        GQLResponseStream(resp, accept)
     }
 }
 
 impl Reply for GQLResponseStream {
     fn into_response(self) -> Response {
+        let stream_format = self.1.stream_format;
         match self.0 {
-            StreamResponse::Single(resp) => GQLResponse(resp).into_response(),
+            StreamResponse::Single(resp) => GQLResponse::from((resp, self.1)).into_response(),
             StreamResponse::Stream(stream) => {
                 let mut resp = Response::new(Body::wrap_stream(
-                    multipart_stream(stream).map(Result::<_, Infallible>::Ok),
+                    multipart_stream_with_format(stream, stream_format)
+                        .map(Result::<_, Infallible>::Ok),
                 ));
                 resp.headers_mut().insert(
                     "content-type",
-                    HeaderValue::from_static("multipart/mixed; boundary=\"-\""),
+                    HeaderValue::from_static(stream_format.content_type()),
                 );
                 resp
             }
@@ -0,0 +1,39 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use graphql_benchmark::{parse, parse_with_grammar};
+
+const KITCHEN_SINK: &str =
+    include_str!("../../async-graphql-parser/tests/queries/kitchen-sink.graphql");
+
+fn large_query() -> String {
+    let mut q = String::from("query Large($id: ID!, $first: Int = 10) {\n");
+    for i in 0..500 {
+        q.push_str(&format!(
+            "  field{i}: node(id: $id, first: $first, filter: {{ name: \"item{i}\", tags: [A, B, C] }}) @include(if: true) {{\n    id\n    ... on User {{ name email friends(first: 5) {{ id name }} }}\n    ...Fields\n  }}\n",
+            i = i
+        ));
+    }
+    q.push_str("}\n\nfragment Fields on Node { id __typename }\n");
+    q
+}
+
+pub fn bench(c: &mut Criterion) {
+    let large = large_query();
+    let queries = [
+        ("simple", simple::Q),
+        ("chat", chat::Q),
+        ("kitchen sink", KITCHEN_SINK),
+        ("large", &large),
+    ];
+    for (name, query) in &queries {
+        c.bench_function(&format!("parse {}", name), |b| {
+            b.iter(|| parse(black_box(query)))
+        });
+        // The grammar-based parser is the baseline of the hand-written one.
+        c.bench_function(&format!("parse {} with grammar", name), |b| {
+            b.iter(|| parse_with_grammar(black_box(query)))
+        });
+    }
+}
+
+criterion_group!(parser, bench);
+criterion_main!(parser);
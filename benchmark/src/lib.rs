@@ -1,6 +1,6 @@
 pub use async_graphql::http::GQLResponse;
 use async_graphql::{ObjectType, QueryResponse, Schema, SubscriptionType};
-use async_graphql_parser::{parse_query, query::Document};
+use async_graphql_parser::{parse_query, parse_query_with_grammar, query::Document};
 use async_std::task;
 
 #[cfg(feature = "jemalloc")]
@@ -23,6 +23,10 @@ pub fn parse(q: &str) -> Document {
     parse_query(q).unwrap()
 }
 
+pub fn parse_with_grammar(q: &str) -> Document {
+    parse_query_with_grammar(q).unwrap()
+}
+
 // pub fn validate() {
 //     check_rules(&S.env.registry, &D, S.validation_mode).unwrap();
 // }
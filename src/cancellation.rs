@@ -0,0 +1,186 @@
+use parking_lot::Mutex;
+use slab::Slab;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll, Waker};
+
+#[derive(Default)]
+struct State {
+    wakers: Slab<Waker>,
+    children: Vec<Weak<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    state: Mutex<State>,
+}
+
+impl Inner {
+    fn cancel(&self) {
+        if self.cancelled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let state = std::mem::take(&mut *self.state.lock());
+        for (_, waker) in state.wakers {
+            waker.wake();
+        }
+        for child in state.children {
+            if let Some(child) = child.upgrade() {
+                child.cancel();
+            }
+        }
+    }
+}
+
+/// A token for cancelling the execution of a query
+///
+/// It can be passed to `QueryBuilder::cancellation_token`, and resolvers can check it with
+/// `Context::is_cancelled` or wait for it with `Context::cancellation_token`. A query is also
+/// cancelled when its future or response stream is dropped before it has completed, such as when
+/// the client disconnects.
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<Inner>);
+
+impl CancellationToken {
+    /// Create a cancellation token.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create a child token that is cancelled when this token is cancelled.
+    ///
+    /// Cancelling the child token does not cancel this token.
+    pub fn child_token(&self) -> Self {
+        let child = CancellationToken::new();
+        let mut state = self.0.state.lock();
+        if self.is_cancelled() {
+            child.0.cancelled.store(true, Ordering::SeqCst);
+        } else {
+            state.children.retain(|child| child.strong_count() > 0);
+            state.children.push(Arc::downgrade(&child.0));
+        }
+        child
+    }
+
+    /// Cancel the token and all its child tokens.
+    pub fn cancel(&self) {
+        self.0.cancel();
+    }
+
+    /// Returns `true` if the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Returns a future that completes when the token is cancelled.
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled {
+            token: self.clone(),
+            key: None,
+        }
+    }
+}
+
+/// Future for `CancellationToken::cancelled`
+pub struct Cancelled {
+    token: CancellationToken,
+    key: Option<usize>,
+}
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+
+        let token = self.token.clone();
+        let mut state = token.0.state.lock();
+        // `cancel` sets the flag before taking the wakers, so check it again with the lock held.
+        if token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        match self.key {
+            Some(key) => {
+                if !state.wakers[key].will_wake(cx.waker()) {
+                    state.wakers[key] = cx.waker().clone();
+                }
+            }
+            None => self.key = Some(state.wakers.insert(cx.waker().clone())),
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Cancelled {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            let mut state = self.token.0.state.lock();
+            if state.wakers.contains(key) {
+                state.wakers.remove(key);
+            }
+        }
+    }
+}
+
+/// Cancels the token when dropped, unless it has been disarmed.
+pub(crate) struct CancelOnDrop(Option<CancellationToken>);
+
+impl CancelOnDrop {
+    pub(crate) fn new(token: CancellationToken) -> Self {
+        Self(Some(token))
+    }
+
+    pub(crate) fn disarm(&mut self) {
+        self.0.take();
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if let Some(token) = self.0.take() {
+            token.cancel();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[async_std::test]
+    async fn test_cancellation_token() {
+        let parent = CancellationToken::new();
+        let child = parent.child_token();
+        let other_child = parent.child_token();
+
+        child.cancel();
+        assert!(child.is_cancelled());
+        assert!(!parent.is_cancelled());
+        assert!(!other_child.is_cancelled());
+
+        let task = async_std::task::spawn({
+            let other_child = other_child.clone();
+            async move { other_child.cancelled().await }
+        });
+        parent.cancel();
+        task.await;
+        assert!(other_child.is_cancelled());
+        assert!(parent.child_token().is_cancelled());
+    }
+
+    #[test]
+    fn test_cancel_on_drop() {
+        let token = CancellationToken::new();
+        drop(CancelOnDrop::new(token.clone()));
+        assert!(token.is_cancelled());
+
+        let token = CancellationToken::new();
+        CancelOnDrop::new(token.clone()).disarm();
+        assert!(!token.is_cancelled());
+    }
+}
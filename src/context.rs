@@ -2,17 +2,18 @@ use crate::extensions::Extensions;
 use crate::parser::query::{Directive, Field, SelectionSet};
 use crate::schema::SchemaEnv;
 use crate::{
-    InputValueType, Lookahead, Pos, Positioned, QueryError, QueryResponse, Result, Type, Value,
+    CancellationToken, InputValueType, Lookahead, Pos, Positioned, QueryError, QueryResponse,
+    Result, Span, Type, Value,
 };
 use async_graphql_parser::query::Document;
-use async_graphql_parser::UploadValue;
+use async_graphql_parser::{UploadContent, UploadValue};
+use async_lock::{Semaphore, SemaphoreGuard};
 use fnv::FnvHashMap;
 use futures::Future;
 use parking_lot::Mutex;
 use std::any::{Any, TypeId};
 use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
-use std::fs::File;
 use std::ops::{Deref, DerefMut};
 use std::pin::Pin;
 use std::sync::atomic::AtomicUsize;
@@ -71,7 +72,7 @@ impl Variables {
         var_path: &str,
         filename: String,
         content_type: Option<String>,
-        content: File,
+        content: UploadContent,
     ) {
         let mut it = var_path.split('.').peekable();
 
@@ -131,6 +132,13 @@ impl Data {
     pub fn insert<D: Any + Send + Sync>(&mut self, data: D) {
         self.0.insert(TypeId::of::<D>(), Box::new(data));
     }
+
+    /// Gets a reference to the data of the specified type.
+    pub fn get<D: Any + Send + Sync>(&self) -> Option<&D> {
+        self.0
+            .get(&TypeId::of::<D>())
+            .and_then(|d| d.downcast_ref::<D>())
+    }
 }
 
 /// Context for `SelectionSet`
@@ -249,17 +257,33 @@ pub type BoxDeferFuture =
     Pin<Box<dyn Future<Output = Result<(QueryResponse, DeferList)>> + Send + 'static>>;
 
 #[doc(hidden)]
+pub struct DeferFuture {
+    /// Position of the deferred field.
+    pub pos: Pos,
+
+    /// Path of the payload, relative to the path prefix of the list.
+    pub path: Vec<serde_json::Value>,
+
+    pub future: BoxDeferFuture,
+}
+
+#[doc(hidden)]
+#[derive(Default)]
 pub struct DeferList {
     pub path_prefix: Vec<serde_json::Value>,
-    pub futures: Mutex<Vec<BoxDeferFuture>>,
+    pub futures: Mutex<Vec<DeferFuture>>,
 }
 
 impl DeferList {
-    pub(crate) fn append<F>(&self, fut: F)
+    pub(crate) fn append<F>(&self, pos: Pos, path: Vec<serde_json::Value>, fut: F)
     where
         F: Future<Output = Result<(QueryResponse, DeferList)>> + Send + 'static,
     {
-        self.futures.lock().push(Box::pin(fut));
+        self.futures.lock().push(DeferFuture {
+            pos,
+            path,
+            future: Box::pin(fut),
+        });
     }
 }
 
@@ -287,10 +311,23 @@ impl<'a, T> Deref for ContextBase<'a, T> {
 
 #[doc(hidden)]
 pub struct QueryEnvInner {
-    pub extensions: spin::Mutex<Extensions>,
+    pub extensions: Arc<spin::Mutex<Extensions>>,
     pub variables: Variables,
     pub document: Document,
     pub ctx_data: Arc<Data>,
+    pub cancellation_token: CancellationToken,
+    pub limits: QueryLimits,
+    pub response_size: AtomicUsize,
+    /// Shared by all the resolvers of the query, sized from `QueryLimits::concurrency`.
+    pub resolvers: Option<Semaphore>,
+}
+
+#[doc(hidden)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct QueryLimits {
+    pub concurrency: Option<usize>,
+    pub response_size: Option<usize>,
+    pub list_items: Option<usize>,
 }
 
 #[doc(hidden)]
@@ -308,16 +345,22 @@ impl Deref for QueryEnv {
 impl QueryEnv {
     #[doc(hidden)]
     pub fn new(
-        extensions: spin::Mutex<Extensions>,
+        extensions: Arc<spin::Mutex<Extensions>>,
         variables: Variables,
         document: Document,
         ctx_data: Arc<Data>,
+        cancellation_token: CancellationToken,
+        limits: QueryLimits,
     ) -> QueryEnv {
         QueryEnv(Arc::new(QueryEnvInner {
             extensions,
             variables,
             document,
             ctx_data,
+            cancellation_token,
+            limits,
+            response_size: AtomicUsize::new(0),
+            resolvers: limits.concurrency.map(|limit| Semaphore::new(limit.max(1))),
         }))
     }
 
@@ -343,6 +386,16 @@ impl QueryEnv {
 }
 
 impl<'a, T> ContextBase<'a, T> {
+    /// Wait until a resolver can run, the permit must be held while the resolver of a field runs
+    /// and dropped before its value is resolved, so that the sub-fields can get their own permits.
+    #[doc(hidden)]
+    pub async fn resolver_permit(&self) -> Option<SemaphoreGuard<'a>> {
+        match &self.query_env.resolvers {
+            Some(resolvers) => Some(resolvers.acquire().await),
+            None => None,
+        }
+    }
+
     fn get_child_resolve_id(&self) -> ResolveId {
         let id = self
             .inc_resolve_id
@@ -417,6 +470,21 @@ impl<'a, T> ContextBase<'a, T> {
             .and_then(|d| d.downcast_ref::<D>())
     }
 
+    /// Returns `true` if the query has been cancelled.
+    ///
+    /// Expensive resolvers can check it to stop early, the result is discarded anyway.
+    pub fn is_cancelled(&self) -> bool {
+        self.query_env.cancellation_token.is_cancelled()
+    }
+
+    /// Gets the cancellation token of the query.
+    ///
+    /// It can be used to wait for the cancellation with `CancellationToken::cancelled`, or be
+    /// passed to the spawned tasks.
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.query_env.cancellation_token
+    }
+
     fn var_value(&self, name: &str, pos: Pos) -> Result<Value> {
         let def = self
             .query_env
@@ -476,12 +544,14 @@ impl<'a, T> ContextBase<'a, T> {
             if directive.name.node == "skip" {
                 if let Some(value) = directive.get_argument("if") {
                     let mut inner_value = value.clone_inner();
-                    self.resolve_input_value(&mut inner_value, value.pos)?;
+                    self.resolve_input_value(&mut inner_value, value.position())?;
                     match InputValueType::parse(Some(inner_value)) {
                         Ok(true) => return Ok(true),
                         Ok(false) => {}
                         Err(err) => {
-                            return Err(err.into_error(value.pos, bool::qualified_type_name()))
+                            return Err(
+                                err.into_error(value.position(), bool::qualified_type_name())
+                            )
                         }
                     }
                 } else {
@@ -495,12 +565,14 @@ impl<'a, T> ContextBase<'a, T> {
             } else if directive.name.node == "include" {
                 if let Some(value) = directive.get_argument("if") {
                     let mut inner_value = value.clone_inner();
-                    self.resolve_input_value(&mut inner_value, value.pos)?;
+                    self.resolve_input_value(&mut inner_value, value.position())?;
                     match InputValueType::parse(Some(inner_value)) {
                         Ok(false) => return Ok(true),
                         Ok(true) => {}
                         Err(err) => {
-                            return Err(err.into_error(value.pos, bool::qualified_type_name()))
+                            return Err(
+                                err.into_error(value.position(), bool::qualified_type_name())
+                            )
                         }
                     }
                 } else {
@@ -578,6 +650,18 @@ impl<'a> ContextBase<'a, &'a Positioned<Field>> {
         }
     }
 
+    pub(crate) fn resolved_arguments(&self) -> Result<Vec<(&'a str, Value)>> {
+        self.item
+            .arguments
+            .iter()
+            .map(|(name, value)| {
+                let mut new_value = value.clone_inner();
+                self.resolve_input_value(&mut new_value, value.position())?;
+                Ok((name.as_str(), new_value))
+            })
+            .collect()
+    }
+
     #[doc(hidden)]
     pub fn result_name(&self) -> &str {
         self.item
@@ -589,7 +673,12 @@ impl<'a> ContextBase<'a, &'a Positioned<Field>> {
 
     /// Get the position of the current field in the query code.
     pub fn position(&self) -> Pos {
-        self.pos
+        self.item.position()
+    }
+
+    /// Get the range of the current field in the query code.
+    pub fn span(&self) -> Span {
+        self.item.span()
     }
 
     /// Creates a uniform interface to inspect the forthcoming selections.
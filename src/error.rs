@@ -1,4 +1,4 @@
-use crate::{Pos, Value};
+use crate::{Pos, Span, Value};
 use std::fmt::{Debug, Display};
 
 /// Input Value Error
@@ -270,6 +270,33 @@ pub enum QueryError {
     #[error("Too deep")]
     TooDeep,
 
+    #[error("Response size exceeds the limit of {limit} bytes")]
+    ResponseTooLarge {
+        /// Maximum size of the response
+        limit: usize,
+    },
+
+    #[error("List has {len} items, exceeding the limit of {limit}")]
+    TooManyListItems {
+        /// Number of items of the list
+        len: usize,
+
+        /// Maximum number of items
+        limit: usize,
+    },
+
+    #[error("Query timed out")]
+    Timeout,
+
+    #[error("Query cancelled")]
+    Cancelled,
+
+    #[error("Field resolver panicked: {message}")]
+    ResolverPanicked {
+        /// Panic message
+        message: String,
+    },
+
     #[error("Failed to resolve field: {err}")]
     FieldError {
         err: String,
@@ -303,6 +330,9 @@ pub enum ParseRequestError {
     #[error("Invalid request: {0}")]
     InvalidRequest(serde_json::Error),
 
+    #[error("Invalid UTF-8 string: {0}")]
+    InvalidUtf8(std::string::FromUtf8Error),
+
     #[error("Invalid files map: {0}")]
     InvalidFilesMap(serde_json::Error),
 
@@ -323,12 +353,31 @@ pub enum ParseRequestError {
 
     #[error("Payload too large")]
     PayloadTooLarge,
+
+    #[error("Request too large")]
+    RequestTooLarge,
+
+    #[error("Too many files")]
+    TooManyFiles,
+
+    #[error("Content type \"{0}\" is not allowed")]
+    ContentTypeNotAllowed(String),
+
+    #[error("File too large for variable \"{var_path}\"")]
+    VariableFileTooLarge { var_path: String },
+
+    #[error("Content type \"{content_type}\" is not allowed for variable \"{var_path}\"")]
+    VariableContentTypeNotAllowed {
+        var_path: String,
+        content_type: String,
+    },
 }
 
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq)]
 pub struct RuleError {
     pub locations: Vec<Pos>,
+    pub spans: Vec<Span>,
     pub message: String,
 }
 
@@ -347,4 +396,52 @@ pub enum Error {
 
     #[error("Rule error")]
     Rule { errors: Vec<RuleError> },
+
+    /// The error of a payload delivered after the first response by `@defer` or `@stream`, with
+    /// the label and path of the payload.
+    #[error("{err}")]
+    Incremental {
+        label: Option<String>,
+        path: Vec<serde_json::Value>,
+        err: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Prepend a path to the path of a query error, the fields of a deferred value are resolved
+    /// relatively to it.
+    pub(crate) fn with_path_prefix(self, prefix: &[serde_json::Value]) -> Self {
+        match self {
+            Error::Query { pos, path, err } if !prefix.is_empty() => {
+                let mut full_path = prefix.to_vec();
+                if let Some(serde_json::Value::Array(path)) = path {
+                    full_path.extend(path);
+                }
+                Error::Query {
+                    pos,
+                    path: Some(full_path.into()),
+                    err,
+                }
+            }
+            Error::Incremental { label, path, err } if !prefix.is_empty() => {
+                let mut full_path = prefix.to_vec();
+                full_path.extend(path);
+                Error::Incremental {
+                    label,
+                    path: full_path,
+                    err: Box::new(err.with_path_prefix(prefix)),
+                }
+            }
+            err => err,
+        }
+    }
+
+    /// Wrap the error of a deferred or streamed payload resolved at the specified path.
+    pub(crate) fn into_incremental(self, path: &[serde_json::Value]) -> Self {
+        Error::Incremental {
+            label: None,
+            path: path.to_vec(),
+            err: Box::new(self.with_path_prefix(path)),
+        }
+    }
 }
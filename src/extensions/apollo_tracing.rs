@@ -1,5 +1,5 @@
 use crate::extensions::{Extension, ResolveInfo};
-use crate::Variables;
+use crate::{Error, Variables};
 use chrono::{DateTime, Utc};
 use serde::ser::SerializeMap;
 use serde::{Serialize, Serializer};
@@ -92,7 +92,11 @@ impl Extension for ApolloTracing {
         );
     }
 
-    fn resolve_end(&mut self, info: &ResolveInfo<'_>) {
+    fn resolve_end(
+        &mut self,
+        info: &ResolveInfo<'_>,
+        _result: std::result::Result<&serde_json::Value, &Error>,
+    ) {
         if let Some(pending_resolve) = self.pending_resolves.remove(&info.resolve_id.current) {
             let start_offset = (pending_resolve.start_time - self.start_time)
                 .num_nanoseconds()
@@ -69,11 +69,15 @@ impl Extension for Logger {
         trace!(target: "async-graphql", "[ResolveStart] id: \"{}\", path: \"{}\"", self.id, info.path_node);
     }
 
-    fn resolve_end(&mut self, info: &ResolveInfo<'_>) {
+    fn resolve_end(
+        &mut self,
+        info: &ResolveInfo<'_>,
+        result: std::result::Result<&serde_json::Value, &Error>,
+    ) {
         if !self.enabled {
             return;
         }
-        trace!(target: "async-graphql", "[ResolveEnd] id: \"{}\", path: \"{}\"", self.id, info.path_node);
+        trace!(target: "async-graphql", "[ResolveEnd] id: \"{}\", path: \"{}\", success: {}", self.id, info.path_node, result.is_ok());
     }
 
     fn error(&mut self, err: &Error) {
@@ -110,6 +114,7 @@ impl Extension for Logger {
                     error!(target: "async-graphql", "[ValidationError] id: \"{}\", pos: [{}], query: \"{}\", variables: {}, {}", self.id, locations, self.query, self.variables, error.message)
                 }
             }
+            Error::Incremental { err, .. } => self.error(err),
         }
     }
 }
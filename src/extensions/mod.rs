@@ -5,18 +5,19 @@ mod logger;
 mod tracing;
 
 use crate::context::{QueryPathNode, ResolveId};
-use crate::{Result, Variables};
+use crate::parser::query::Directive;
+use crate::{Context, Data, Positioned, QueryBuilder, QueryResponse, Result, Value, Variables};
 
 pub use self::apollo_tracing::ApolloTracing;
 pub use self::logger::Logger;
 pub use self::tracing::Tracing;
 use crate::Error;
 use async_graphql_parser::query::Document;
-use serde_json::Value;
 
 pub(crate) type BoxExtension = Box<dyn Extension>;
 
 #[doc(hidden)]
+#[derive(Default)]
 pub struct Extensions(pub(crate) Vec<BoxExtension>);
 
 /// Parameters for `Extension::resolve_field_start`
@@ -33,16 +34,69 @@ pub struct ResolveInfo<'a> {
 
     /// Current return type, is qualified name.
     pub return_type: &'a str,
+
+    /// The alias of the field.
+    pub alias: Option<&'a str>,
+
+    /// The directives of the field.
+    pub directives: &'a [Positioned<Directive>],
+
+    pub(crate) ctx: &'a Context<'a>,
+}
+
+impl<'a> ResolveInfo<'a> {
+    /// Returns the arguments of the field in the query, the variables are replaced with their values.
+    ///
+    /// The arguments are resolved on each call.
+    pub fn arguments(&self) -> Result<Vec<(&'a str, Value)>> {
+        self.ctx.resolved_arguments()
+    }
 }
 
 /// Represents a GraphQL extension
+///
+/// The asynchronous hooks `prepare_request`, `before_execute` and `after_execute` are called when
+/// executing a query or mutation with `QueryBuilder`, the extension must be implemented with
+/// `#[async_trait::async_trait]` to override them.
 #[allow(unused_variables)]
+#[async_trait::async_trait]
 pub trait Extension: Sync + Send + 'static {
     /// If this extension needs to output data to query results, you need to specify a name.
     fn name(&self) -> Option<&'static str> {
         None
     }
 
+    /// Called before the request is parsed.
+    ///
+    /// The request can be modified, such as adding context data or rewriting variables. The time
+    /// limit, the cancellation token and the extensions of the returned request are used to
+    /// execute it, the extensions added here are not called for this hook. If an error is
+    /// returned, the request is rejected.
+    async fn prepare_request(&mut self, request: QueryBuilder) -> Result<QueryBuilder> {
+        Ok(request)
+    }
+
+    /// Called after the validation, before the operation is executed.
+    ///
+    /// The document only contains the operation to be executed. The variables and the context data
+    /// can be modified, if an error is returned, the operation is not executed.
+    async fn before_execute(
+        &mut self,
+        document: &Document,
+        variables: &mut Variables,
+        data: &mut Data,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called after the operation is executed, the result can be rewritten.
+    ///
+    /// It is also called if the request failed before the execution. For a query that contains
+    /// `@defer` or `@stream`, only the first response is passed to this hook.
+    async fn after_execute(&mut self, result: Result<QueryResponse>) -> Result<QueryResponse> {
+        result
+    }
+
     /// Called at the begin of the parse.
     fn parse_start(&mut self, query_source: &str, variables: &Variables) {}
 
@@ -64,8 +118,14 @@ pub trait Extension: Sync + Send + 'static {
     /// Called at the begin of the resolve field.
     fn resolve_start(&mut self, info: &ResolveInfo<'_>) {}
 
-    /// Called at the end of the resolve field.
-    fn resolve_end(&mut self, info: &ResolveInfo<'_>) {}
+    /// Called at the end of the resolve field, `result` is the value returned by the resolver or
+    /// the error.
+    fn resolve_end(
+        &mut self,
+        info: &ResolveInfo<'_>,
+        result: std::result::Result<&serde_json::Value, &Error>,
+    ) {
+    }
 
     /// Called when an error occurs.
     fn error(&mut self, err: &Error) {}
@@ -89,7 +149,34 @@ impl<T> ErrorLogger for Result<T> {
     }
 }
 
+#[async_trait::async_trait]
 impl Extension for Extensions {
+    async fn prepare_request(&mut self, mut request: QueryBuilder) -> Result<QueryBuilder> {
+        for e in &mut self.0 {
+            request = e.prepare_request(request).await?;
+        }
+        Ok(request)
+    }
+
+    async fn before_execute(
+        &mut self,
+        document: &Document,
+        variables: &mut Variables,
+        data: &mut Data,
+    ) -> Result<()> {
+        for e in &mut self.0 {
+            e.before_execute(document, variables, data).await?;
+        }
+        Ok(())
+    }
+
+    async fn after_execute(&mut self, mut result: Result<QueryResponse>) -> Result<QueryResponse> {
+        for e in &mut self.0 {
+            result = e.after_execute(result).await;
+        }
+        result
+    }
+
     fn parse_start(&mut self, query_source: &str, variables: &Variables) {
         self.0
             .iter_mut()
@@ -120,15 +207,19 @@ impl Extension for Extensions {
         self.0.iter_mut().for_each(|e| e.resolve_start(info));
     }
 
-    fn resolve_end(&mut self, resolve_id: &ResolveInfo<'_>) {
-        self.0.iter_mut().for_each(|e| e.resolve_end(resolve_id));
+    fn resolve_end(
+        &mut self,
+        info: &ResolveInfo<'_>,
+        result: std::result::Result<&serde_json::Value, &Error>,
+    ) {
+        self.0.iter_mut().for_each(|e| e.resolve_end(info, result));
     }
 
     fn error(&mut self, err: &Error) {
         self.0.iter_mut().for_each(|e| e.error(err));
     }
 
-    fn result(&mut self) -> Option<Value> {
+    fn result(&mut self) -> Option<serde_json::Value> {
         if !self.0.is_empty() {
             let value = self
                 .0
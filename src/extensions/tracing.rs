@@ -1,5 +1,5 @@
 use crate::extensions::{Extension, ResolveInfo};
-use crate::{QueryPathSegment, Variables};
+use crate::{Error, QueryPathSegment, Variables};
 use std::collections::BTreeMap;
 use tracing::{span, Id, Level};
 
@@ -60,7 +60,11 @@ impl Extension for Tracing {
         }
     }
 
-    fn resolve_end(&mut self, info: &ResolveInfo<'_>) {
+    fn resolve_end(
+        &mut self,
+        info: &ResolveInfo<'_>,
+        _result: std::result::Result<&serde_json::Value, &Error>,
+    ) {
         if let Some(id) = self.fields.remove(&info.resolve_id.current) {
             tracing::dispatcher::get_default(|d| d.exit(&id));
         }
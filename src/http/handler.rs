@@ -0,0 +1,333 @@
+use crate::http::{
+    multipart_stream_with_format, parse_variables, GQLRequest, GQLResponse, MultipartStreamFormat,
+    ResponseFormat,
+};
+use crate::parser::query::OperationType;
+use crate::parser::{parse_query_with_options, ParserOptions};
+use crate::query::{IntoQueryBuilder, IntoQueryBuilderOpts};
+use crate::{
+    CacheControl, ObjectType, ParseRequestError, QueryBuilder, QueryResponse, Result, Schema,
+    StreamResponse, SubscriptionType,
+};
+use bytes::Bytes;
+use futures::task::{Context, Poll};
+use futures::{AsyncRead, AsyncReadExt, Stream};
+use http::header::{HeaderValue, ACCEPT, ALLOW, CACHE_CONTROL, CONTENT_TYPE};
+use http::{Method, Request, Response, StatusCode};
+use std::pin::Pin;
+
+/// The body of a response produced by `handle_request`.
+pub enum ResponseBody {
+    /// A complete body.
+    Bytes(Bytes),
+
+    /// A multipart body, returned for queries that contain `@defer` or `@stream`.
+    Stream(Pin<Box<dyn Stream<Item = Bytes> + Send + 'static>>),
+}
+
+impl ResponseBody {
+    /// Returns the content of a complete body, or `None` for a multipart body.
+    pub fn into_bytes(self) -> Option<Bytes> {
+        match self {
+            ResponseBody::Bytes(bytes) => Some(bytes),
+            ResponseBody::Stream(_) => None,
+        }
+    }
+}
+
+impl Stream for ResponseBody {
+    type Item = Bytes;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match &mut *self {
+            ResponseBody::Bytes(bytes) if bytes.is_empty() => Poll::Ready(None),
+            ResponseBody::Bytes(bytes) => Poll::Ready(Some(std::mem::take(bytes))),
+            ResponseBody::Stream(stream) => stream.as_mut().poll_next(cx),
+        }
+    }
+}
+
+/// The parameters of a GET request.
+#[derive(Deserialize)]
+struct GetRequest {
+    query: String,
+    #[serde(rename = "operationName")]
+    operation_name: Option<String>,
+    variables: Option<String>,
+}
+
+/// Handle a GraphQL request.
+///
+/// This is a framework-agnostic handler, integrations only need to convert their request to
+/// `http::Request` and the returned `http::Response` back.
+///
+/// - `GET` requests read the query from the query string, mutations are not allowed.
+/// - `POST` requests accept JSON, `application/graphql` and multipart upload bodies.
+///   A JSON array of requests is executed as a batch and responded with an array.
+/// - Queries with `@defer` or `@stream` are responded with a multipart body.
+/// - The `Accept` header selects the `ResponseFormat` and the `MultipartStreamFormat`.
+///
+/// `query_builder_configuration` is applied to every `QueryBuilder` before it is executed.
+pub async fn handle_request<Query, Mutation, Subscription, B, F>(
+    schema: &Schema<Query, Mutation, Subscription>,
+    req: Request<B>,
+    query_builder_configuration: F,
+) -> Response<ResponseBody>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+    B: AsyncRead + Send + Unpin + 'static,
+    F: Fn(QueryBuilder) -> QueryBuilder,
+{
+    handle_request_opts(
+        schema,
+        req,
+        query_builder_configuration,
+        &Default::default(),
+    )
+    .await
+}
+
+/// Similar to handle_request, but you can set the options `IntoQueryBuilderOpts`.
+pub async fn handle_request_opts<Query, Mutation, Subscription, B, F>(
+    schema: &Schema<Query, Mutation, Subscription>,
+    req: Request<B>,
+    query_builder_configuration: F,
+    opts: &IntoQueryBuilderOpts,
+) -> Response<ResponseBody>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+    B: AsyncRead + Send + Unpin + 'static,
+    F: Fn(QueryBuilder) -> QueryBuilder,
+{
+    let accept = req
+        .headers()
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>()
+        .join(",");
+    let format = ResponseFormat::from_accept(Some(&accept));
+    let stream_format = MultipartStreamFormat::from_accept(Some(&accept));
+
+    let (builders, is_batch) = match *req.method() {
+        Method::GET => match get_query_builder(&req, &schema.parser_options) {
+            Ok(Some(builder)) => (vec![builder], false),
+            Ok(None) => return method_not_allowed("POST"),
+            Err(err) => return bad_request(err),
+        },
+        Method::POST => match post_query_builders(req, opts).await {
+            Ok(res) => res,
+            Err(err) => return bad_request(err),
+        },
+        _ => return method_not_allowed("GET, POST"),
+    };
+
+    let mut builders = builders.into_iter().map(query_builder_configuration);
+
+    if !is_batch {
+        let builder = builders.next().unwrap();
+        return match builder.execute_stream(schema).await {
+            StreamResponse::Single(res) => json_response(res, format),
+            StreamResponse::Stream(stream) => {
+                let mut resp = Response::new(ResponseBody::Stream(Box::pin(
+                    multipart_stream_with_format(stream, stream_format),
+                )));
+                resp.headers_mut().insert(
+                    CONTENT_TYPE,
+                    HeaderValue::from_static(stream_format.content_type()),
+                );
+                resp
+            }
+        };
+    }
+
+    let mut responses = Vec::new();
+    for builder in builders {
+        responses.push(builder.execute(schema).await);
+    }
+    batch_response(responses, format)
+}
+
+/// Returns `None` if the selected operation is a mutation, which must not be executed by a GET request.
+fn get_query_builder<B>(
+    req: &Request<B>,
+    parser_options: &ParserOptions,
+) -> std::result::Result<Option<QueryBuilder>, ParseRequestError> {
+    let get_request: GetRequest = serde_urlencoded::from_str(req.uri().query().unwrap_or_default())
+        .map_err(|err| ParseRequestError::InvalidRequest(serde::de::Error::custom(err)))?;
+
+    if let Ok(mut document) = parse_query_with_options(&get_request.query, parser_options) {
+        if document.retain_operation(get_request.operation_name.as_deref())
+            && document.current_operation().ty == OperationType::Mutation
+        {
+            return Ok(None);
+        }
+    }
+
+    let mut builder = QueryBuilder::new(get_request.query);
+    if let Some(operation_name) = get_request.operation_name {
+        builder = builder.operation_name(operation_name);
+    }
+    if let Some(variables) = get_request.variables {
+        let variables =
+            serde_json::from_str(&variables).map_err(ParseRequestError::InvalidRequest)?;
+        builder = builder.variables(parse_variables(variables)?);
+    }
+    Ok(Some(builder))
+}
+
+/// Returns the query builders and whether the body is a batch, a JSON array of requests.
+async fn post_query_builders<B>(
+    req: Request<B>,
+    opts: &IntoQueryBuilderOpts,
+) -> std::result::Result<(Vec<QueryBuilder>, bool), ParseRequestError>
+where
+    B: AsyncRead + Send + Unpin + 'static,
+{
+    let content_type = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(ToString::to_string);
+    let is_json = content_type
+        .as_deref()
+        .and_then(|value| value.parse::<mime::Mime>().ok())
+        .map(|mime| mime.subtype() == mime::JSON || mime.suffix() == Some(mime::JSON))
+        .unwrap_or(true);
+    let mut body = req.into_body();
+
+    if !is_json {
+        return Ok((
+            vec![(content_type, body).into_query_builder_opts(opts).await?],
+            false,
+        ));
+    }
+
+    let mut data = Vec::new();
+    match opts.max_request_size {
+        Some(max_request_size) => {
+            (&mut body)
+                .take(max_request_size as u64 + 1)
+                .read_to_end(&mut data)
+                .await?;
+            if data.len() > max_request_size {
+                return Err(ParseRequestError::RequestTooLarge);
+            }
+        }
+        None => {
+            body.read_to_end(&mut data).await?;
+        }
+    }
+
+    let is_batch = data.iter().find(|c| !c.is_ascii_whitespace()) == Some(&b'[');
+    let requests = if is_batch {
+        let requests: Vec<GQLRequest> =
+            serde_json::from_slice(&data).map_err(ParseRequestError::InvalidRequest)?;
+        if requests.is_empty() {
+            return Err(ParseRequestError::InvalidRequest(serde::de::Error::custom(
+                "empty batch request",
+            )));
+        }
+        requests
+    } else {
+        vec![serde_json::from_slice(&data).map_err(ParseRequestError::InvalidRequest)?]
+    };
+
+    let mut builders = Vec::with_capacity(requests.len());
+    for request in requests {
+        builders.push(request.into_query_builder_opts(opts).await?);
+    }
+    Ok((builders, is_batch))
+}
+
+fn json_response(res: Result<QueryResponse>, format: ResponseFormat) -> Response<ResponseBody> {
+    let cache_control = res
+        .as_ref()
+        .ok()
+        .and_then(|resp| resp.cache_control.value());
+    let gql_resp = GQLResponse(res);
+    let status = StatusCode::from_u16(gql_resp.status_code(format)).unwrap_or(StatusCode::OK);
+    let mut resp = body_response(
+        status,
+        format.content_type(),
+        serde_json::to_vec(&gql_resp).unwrap_or_default(),
+    );
+    if let Some(value) = cache_control.and_then(|value| HeaderValue::from_str(&value).ok()) {
+        resp.headers_mut().insert(CACHE_CONTROL, value);
+    }
+    resp
+}
+
+fn batch_response(
+    responses: Vec<Result<QueryResponse>>,
+    format: ResponseFormat,
+) -> Response<ResponseBody> {
+    // A batch is only cacheable if all of its responses are.
+    let mut cache_control: Option<CacheControl> = None;
+    let mut cacheable = true;
+    for res in &responses {
+        match res {
+            Ok(resp) if resp.cache_control.max_age > 0 => match &mut cache_control {
+                Some(cache_control) => cache_control.merge(&resp.cache_control),
+                None => cache_control = Some(resp.cache_control),
+            },
+            _ => cacheable = false,
+        }
+    }
+
+    let responses = responses.into_iter().map(GQLResponse).collect::<Vec<_>>();
+    let mut resp = body_response(
+        StatusCode::OK,
+        format.content_type(),
+        serde_json::to_vec(&responses).unwrap_or_default(),
+    );
+    if let Some(value) = cache_control
+        .filter(|_| cacheable)
+        .and_then(|cache_control| cache_control.value())
+        .and_then(|value| HeaderValue::from_str(&value).ok())
+    {
+        resp.headers_mut().insert(CACHE_CONTROL, value);
+    }
+    resp
+}
+
+fn bad_request(err: ParseRequestError) -> Response<ResponseBody> {
+    let status = match err {
+        ParseRequestError::PayloadTooLarge
+        | ParseRequestError::RequestTooLarge
+        | ParseRequestError::VariableFileTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+        _ => StatusCode::BAD_REQUEST,
+    };
+    body_response(
+        status,
+        "application/json",
+        serde_json::to_vec(&serde_json::json!({
+            "errors": [{ "message": err.to_string() }]
+        }))
+        .unwrap_or_default(),
+    )
+}
+
+fn method_not_allowed(allow: &'static str) -> Response<ResponseBody> {
+    let mut resp = Response::new(ResponseBody::Bytes(Bytes::new()));
+    *resp.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
+    resp.headers_mut()
+        .insert(ALLOW, HeaderValue::from_static(allow));
+    resp
+}
+
+fn body_response(
+    status: StatusCode,
+    content_type: &'static str,
+    body: Vec<u8>,
+) -> Response<ResponseBody> {
+    let mut resp = Response::new(ResponseBody::Bytes(body.into()));
+    *resp.status_mut() = status;
+    resp.headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    resp
+}
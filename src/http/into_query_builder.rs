@@ -1,18 +1,23 @@
 use crate::http::GQLRequest;
-use crate::query::{IntoQueryBuilder, IntoQueryBuilderOpts};
+use crate::query::{IntoQueryBuilder, IntoQueryBuilderOpts, UploadConstraints};
 use crate::{ParseRequestError, QueryBuilder};
+use async_graphql_parser::UploadContent;
 use bytes::Bytes;
 use futures::{AsyncRead, AsyncReadExt, Stream};
 use mime::Mime;
-use multer::{Constraints, Multipart, SizeLimit};
+use multer::{Constraints, Field, Multipart, SizeLimit};
 use std::collections::HashMap;
+use std::fs::File;
 use std::io::{Seek, SeekFrom, Write};
 
 impl From<multer::Error> for ParseRequestError {
     fn from(err: multer::Error) -> Self {
         match err {
-            multer::Error::FieldSizeExceeded { .. } | multer::Error::StreamSizeExceeded { .. } => {
-                ParseRequestError::PayloadTooLarge
+            multer::Error::FieldSizeExceeded { .. } => ParseRequestError::PayloadTooLarge,
+            multer::Error::StreamSizeExceeded { .. } => ParseRequestError::RequestTooLarge,
+            multer::Error::StreamReadFailed(err) if err.is::<multer::Error>() => {
+                // The size limit errors of the stream are wrapped by `multer`.
+                ParseRequestError::from(*err.downcast::<multer::Error>().unwrap())
             }
             _ => ParseRequestError::InvalidMultipart(err),
         }
@@ -29,18 +34,16 @@ where
         mut self,
         opts: &IntoQueryBuilderOpts,
     ) -> std::result::Result<QueryBuilder, ParseRequestError> {
-        if let Some(boundary) = self
-            .0
-            .and_then(|value| value.as_ref().parse::<Mime>().ok())
-            .and_then(|ct| {
-                if ct.essence_str() == mime::MULTIPART_FORM_DATA {
-                    ct.get_param("boundary")
-                        .map(|boundary| boundary.to_string())
-                } else {
-                    None
-                }
-            })
-        {
+        let content_type = self.0.and_then(|value| value.as_ref().parse::<Mime>().ok());
+
+        if let Some(boundary) = content_type.as_ref().and_then(|ct| {
+            if ct.essence_str() == mime::MULTIPART_FORM_DATA {
+                ct.get_param("boundary")
+                    .map(|boundary| boundary.to_string())
+            } else {
+                None
+            }
+        }) {
             // multipart
             let stream = reader_stream(self.1);
 
@@ -49,10 +52,8 @@ where
                 boundary,
                 Constraints::new().size_limit({
                     let mut limit = SizeLimit::new();
-                    if let (Some(max_file_size), Some(max_num_files)) =
-                        (opts.max_file_size, opts.max_file_size)
-                    {
-                        limit = limit.whole_stream((max_file_size * max_num_files) as u64);
+                    if let Some(max_request_size) = opts.max_request_size {
+                        limit = limit.whole_stream(max_request_size as u64);
                     }
                     if let Some(max_file_size) = opts.max_file_size {
                         limit = limit.per_field(max_file_size as u64);
@@ -83,15 +84,26 @@ where
                     _ => {
                         if let Some(name) = field.name().map(ToString::to_string) {
                             if let Some(filename) = field.file_name().map(ToString::to_string) {
+                                if let Some(max_num_files) = opts.max_num_files {
+                                    if files.len() >= max_num_files {
+                                        return Err(ParseRequestError::TooManyFiles);
+                                    }
+                                }
+
                                 let content_type =
                                     field.content_type().map(|mime| mime.to_string());
-                                let mut file =
-                                    tempfile::tempfile().map_err(ParseRequestError::Io)?;
-                                while let Some(chunk) = field.chunk().await.unwrap() {
-                                    file.write(&chunk).map_err(ParseRequestError::Io)?;
-                                }
-                                file.seek(SeekFrom::Start(0))?;
-                                files.push((name, filename, content_type, file));
+
+                                // The "map" part is usually sent before the files, so the
+                                // constraints of the variables can be checked while reading.
+                                let var_paths = map
+                                    .as_ref()
+                                    .and_then(|map| map.get(&name))
+                                    .cloned()
+                                    .unwrap_or_default();
+                                check_content_type(opts, &var_paths, content_type.as_deref())?;
+                                let (content, size) =
+                                    read_upload(&mut field, opts, &var_paths).await?;
+                                files.push((name, filename, content_type, content, size));
                             }
                         }
                     }
@@ -107,14 +119,16 @@ where
                 None => return Err(ParseRequestError::MissingMapPart),
             };
 
-            for (name, filename, content_type, file) in files {
+            for (name, filename, content_type, content, size) in files {
                 if let Some(var_paths) = map.remove(&name) {
+                    check_content_type(opts, &var_paths, content_type.as_deref())?;
+                    check_file_size(opts, &var_paths, size)?;
                     for var_path in var_paths {
                         builder.set_upload(
                             &var_path,
                             filename.clone(),
                             content_type.clone(),
-                            file.try_clone().unwrap(),
+                            content.clone(),
                         );
                     }
                 }
@@ -127,10 +141,31 @@ where
             Ok(builder)
         } else {
             let mut data = Vec::new();
-            self.1
-                .read_to_end(&mut data)
-                .await
-                .map_err(ParseRequestError::Io)?;
+            match opts.max_request_size {
+                Some(max_request_size) => {
+                    (&mut self.1)
+                        .take(max_request_size as u64 + 1)
+                        .read_to_end(&mut data)
+                        .await
+                        .map_err(ParseRequestError::Io)?;
+                    if data.len() > max_request_size {
+                        return Err(ParseRequestError::RequestTooLarge);
+                    }
+                }
+                None => {
+                    self.1
+                        .read_to_end(&mut data)
+                        .await
+                        .map_err(ParseRequestError::Io)?;
+                }
+            }
+            if content_type.as_ref().map(|ct| ct.essence_str()) == Some("application/graphql") {
+                // The body is the query source.
+                let query_source =
+                    String::from_utf8(data).map_err(ParseRequestError::InvalidUtf8)?;
+                return Ok(QueryBuilder::new(query_source));
+            }
+
             let gql_request: GQLRequest =
                 serde_json::from_slice(&data).map_err(ParseRequestError::InvalidRequest)?;
             gql_request.into_query_builder().await
@@ -138,6 +173,152 @@ where
     }
 }
 
+/// Returns the constraints of the variables that a file is uploaded to.
+fn variable_constraints<'a>(
+    opts: &'a IntoQueryBuilderOpts,
+    var_paths: &'a [String],
+) -> impl Iterator<Item = (&'a str, &'a UploadConstraints)> + 'a {
+    var_paths.iter().flat_map(move |var_path| {
+        opts.variable_constraints
+            .iter()
+            .filter(move |(key, _)| {
+                var_path.starts_with(key.as_str())
+                    && (var_path.len() == key.len() || var_path[key.len()..].starts_with('.'))
+            })
+            .map(move |(_, constraints)| (var_path.as_str(), constraints))
+    })
+}
+
+fn is_content_type_allowed(allowed_content_types: &[String], content_type: &Mime) -> bool {
+    allowed_content_types
+        .iter()
+        .filter_map(|allowed| allowed.parse::<Mime>().ok())
+        .any(|allowed| {
+            (allowed.type_() == mime::STAR || allowed.type_() == content_type.type_())
+                && (allowed.subtype() == mime::STAR || allowed.subtype() == content_type.subtype())
+        })
+}
+
+fn check_content_type(
+    opts: &IntoQueryBuilderOpts,
+    var_paths: &[String],
+    content_type: Option<&str>,
+) -> std::result::Result<(), ParseRequestError> {
+    // The default content type of a part is `text/plain`. (RFC 7578, Section 4.4)
+    let content_type = content_type.unwrap_or("text/plain");
+    let mime = content_type.parse::<Mime>().ok();
+
+    if let Some(allowed_content_types) = &opts.allowed_content_types {
+        if !mime
+            .as_ref()
+            .map(|mime| is_content_type_allowed(allowed_content_types, mime))
+            .unwrap_or_default()
+        {
+            return Err(ParseRequestError::ContentTypeNotAllowed(
+                content_type.to_string(),
+            ));
+        }
+    }
+
+    for (var_path, constraints) in variable_constraints(opts, var_paths) {
+        if let Some(allowed_content_types) = &constraints.allowed_content_types {
+            if !mime
+                .as_ref()
+                .map(|mime| is_content_type_allowed(allowed_content_types, mime))
+                .unwrap_or_default()
+            {
+                return Err(ParseRequestError::VariableContentTypeNotAllowed {
+                    var_path: var_path.to_string(),
+                    content_type: content_type.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn check_file_size(
+    opts: &IntoQueryBuilderOpts,
+    var_paths: &[String],
+    size: usize,
+) -> std::result::Result<(), ParseRequestError> {
+    if let Some(max_file_size) = opts.max_file_size {
+        if size > max_file_size {
+            return Err(ParseRequestError::PayloadTooLarge);
+        }
+    }
+
+    for (var_path, constraints) in variable_constraints(opts, var_paths) {
+        if let Some(max_file_size) = constraints.max_file_size {
+            if size > max_file_size {
+                return Err(ParseRequestError::VariableFileTooLarge {
+                    var_path: var_path.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the content of an uploaded file, the file is kept in memory until it becomes larger than `max_in_memory_file_size`.
+///
+/// Returns the content and the size of the file.
+async fn read_upload(
+    field: &mut Field,
+    opts: &IntoQueryBuilderOpts,
+    var_paths: &[String],
+) -> std::result::Result<(UploadContent, usize), ParseRequestError> {
+    let max_in_memory_size = opts.max_in_memory_file_size.unwrap_or_default();
+    let max_size = opts
+        .max_file_size
+        .into_iter()
+        .chain(
+            variable_constraints(opts, var_paths)
+                .filter_map(|(_, constraints)| constraints.max_file_size),
+        )
+        .min();
+    let mut size = 0;
+    let mut data = Vec::new();
+    let mut file: Option<File> = None;
+
+    while let Some(chunk) = field.chunk().await? {
+        size += chunk.len();
+        if let Some(max_size) = max_size {
+            if size > max_size {
+                // Stop reading as soon as the file is known to be too large.
+                check_file_size(opts, var_paths, size)?;
+            }
+        }
+
+        match &mut file {
+            Some(file) => file.write_all(&chunk)?,
+            None if data.len() + chunk.len() > max_in_memory_size => {
+                let mut tmp_file = tempfile::tempfile()?;
+                tmp_file.write_all(&data)?;
+                tmp_file.write_all(&chunk)?;
+                data.clear();
+                file = Some(tmp_file);
+            }
+            None => data.extend_from_slice(&chunk),
+        }
+    }
+
+    let content = match file {
+        Some(mut file) => {
+            file.seek(SeekFrom::Start(0))?;
+            UploadContent::File(file)
+        }
+        None if max_in_memory_size == 0 => {
+            // Keep the previous behavior of storing all files in temporary files.
+            UploadContent::File(tempfile::tempfile()?)
+        }
+        None => UploadContent::from(data),
+    };
+    Ok((content, size))
+}
+
 fn reader_stream(
     mut r: impl AsyncRead + Send + Unpin + 'static,
 ) -> impl Stream<Item = std::io::Result<Bytes>> + 'static {
@@ -1,16 +1,20 @@
 //! A helper module that supports HTTP
 
 mod graphiql_source;
+mod handler;
 mod into_query_builder;
 mod multipart_stream;
 mod playground_source;
+mod response_format;
 mod stream_body;
 
 use itertools::Itertools;
 
 pub use graphiql_source::graphiql_source;
-pub use multipart_stream::multipart_stream;
+pub use handler::{handle_request, handle_request_opts, ResponseBody};
+pub use multipart_stream::{multipart_stream, multipart_stream_with_format, MultipartStreamFormat};
 pub use playground_source::{playground_source, GraphQLPlaygroundConfig};
+pub use response_format::ResponseFormat;
 pub use stream_body::StreamBody;
 
 use crate::query::{IntoQueryBuilder, IntoQueryBuilderOpts};
@@ -45,17 +49,59 @@ impl IntoQueryBuilder for GQLRequest {
             builder = builder.operation_name(operation_name);
         }
         if let Some(variables) = self.variables {
-            if let Ok(variables) = Variables::parse_from_json(variables) {
-                builder = builder.variables(variables);
-            }
+            builder = builder.variables(parse_variables(variables)?);
         }
         Ok(builder)
     }
 }
 
+/// Parse the `variables` of a request, which must be an object or `null`.
+pub(crate) fn parse_variables(
+    value: serde_json::Value,
+) -> std::result::Result<Variables, ParseRequestError> {
+    match value {
+        serde_json::Value::Object(_) | serde_json::Value::Null => {
+            Ok(Variables::parse_from_json(value).unwrap_or_default())
+        }
+        _ => Err(ParseRequestError::InvalidRequest(serde::de::Error::custom(
+            "variables must be an object",
+        ))),
+    }
+}
+
 /// Serializable GraphQL Response object
 pub struct GQLResponse(pub Result<QueryResponse>);
 
+impl GQLResponse {
+    /// Returns the HTTP status code of this response in the specified format.
+    ///
+    /// With `ResponseFormat::GraphQLResponseJson`, requests that cannot be executed (parse and
+    /// validation errors, unknown operations, and queries that are too complex or too deep) get
+    /// `400 Bad Request`. All other responses, including those with field errors, get `200 OK`.
+    pub fn status_code(&self, format: ResponseFormat) -> u16 {
+        match (format, &self.0) {
+            (ResponseFormat::GraphQLResponseJson, Err(err)) if is_request_error(err) => 400,
+            _ => 200,
+        }
+    }
+}
+
+fn is_request_error(err: &Error) -> bool {
+    match err {
+        Error::Parse(_) | Error::Rule { .. } => true,
+        Error::Incremental { .. } => false,
+        Error::Query { err, .. } => matches!(
+            err,
+            QueryError::MissingOperation
+                | QueryError::UnknownOperationNamed { .. }
+                | QueryError::NotConfiguredMutations
+                | QueryError::NotConfiguredSubscriptions
+                | QueryError::TooComplex
+                | QueryError::TooDeep
+        ),
+    }
+}
+
 impl Serialize for GQLResponse {
     fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
         match &self.0 {
@@ -79,6 +125,14 @@ impl Serialize for GQLResponse {
             }
             Err(err) => {
                 let mut map = serializer.serialize_map(None)?;
+                if let Error::Incremental { label, path, .. } = err {
+                    if let Some(label) = label {
+                        map.serialize_key("label")?;
+                        map.serialize_value(label)?;
+                    }
+                    map.serialize_key("path")?;
+                    map.serialize_value(path)?;
+                }
                 map.serialize_key("errors")?;
                 map.serialize_value(&GQLError(err))?;
                 map.end()
@@ -139,13 +193,25 @@ impl<'a> Serialize for GQLError<'a> {
             Error::Rule { errors } => {
                 let mut seq = serializer.serialize_seq(Some(1))?;
                 for error in errors {
-                    seq.serialize_element(&serde_json::json!({
+                    let mut value = serde_json::json!({
                         "message": error.message,
                         "locations": error.locations.iter().map(|pos| serde_json::json!({"line": pos.line, "column": pos.column})).collect_vec(),
-                    }))?;
+                    });
+                    if !error.spans.is_empty() {
+                        value["extensions"] = serde_json::json!({
+                            "spans": error.spans.iter().map(|span| serde_json::json!({
+                                "start": {"line": span.start.line, "column": span.start.column},
+                                "end": {"line": span.end.line, "column": span.end.column},
+                                "startOffset": span.start_offset,
+                                "endOffset": span.end_offset,
+                            })).collect_vec(),
+                        });
+                    }
+                    seq.serialize_element(&value)?;
                 }
                 seq.end()
             }
+            Error::Incremental { err, .. } => GQLError(err).serialize(serializer),
         }
     }
 }
@@ -167,7 +233,7 @@ impl<'a> Serialize for GQLErrorPos<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Pos;
+    use crate::{Pos, RuleError, Span};
     use serde_json::json;
 
     #[test]
@@ -267,6 +333,73 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_response_rule_error() {
+        let span = Span {
+            start: Pos { line: 1, column: 3 },
+            end: Pos { line: 1, column: 8 },
+            start_offset: 2,
+            end_offset: 7,
+        };
+        let resp = GQLResponse(Err(Error::Rule {
+            errors: vec![RuleError {
+                locations: vec![span.start],
+                spans: vec![span],
+                message: "MyErrorMessage".to_owned(),
+            }],
+        }));
+        assert_eq!(
+            serde_json::to_value(resp).unwrap(),
+            json!({
+                "errors": [{
+                    "message": "MyErrorMessage",
+                    "locations": [{"line": 1, "column": 3}],
+                    "extensions": {
+                        "spans": [{
+                            "start": {"line": 1, "column": 3},
+                            "end": {"line": 1, "column": 8},
+                            "startOffset": 2,
+                            "endOffset": 7,
+                        }]
+                    }
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_response_status_code() {
+        let ok = GQLResponse(Ok(QueryResponse {
+            label: None,
+            path: None,
+            data: json!({"ok": true}),
+            extensions: None,
+            cache_control: Default::default(),
+        }));
+        assert_eq!(ok.status_code(ResponseFormat::Json), 200);
+        assert_eq!(ok.status_code(ResponseFormat::GraphQLResponseJson), 200);
+
+        let rule_error = GQLResponse(Err(Error::Rule { errors: Vec::new() }));
+        assert_eq!(rule_error.status_code(ResponseFormat::Json), 200);
+        assert_eq!(
+            rule_error.status_code(ResponseFormat::GraphQLResponseJson),
+            400
+        );
+
+        let field_error = GQLResponse(Err(Error::Query {
+            pos: Pos::default(),
+            path: None,
+            err: QueryError::FieldError {
+                err: "MyErrorMessage".to_owned(),
+                extended_error: None,
+            },
+        }));
+        assert_eq!(
+            field_error.status_code(ResponseFormat::GraphQLResponseJson),
+            200
+        );
+    }
+
     #[test]
     fn test_response_error_with_pos() {
         let resp = GQLResponse(Err(Error::Query {
@@ -1,14 +1,110 @@
-use crate::http::GQLResponse;
-use crate::{QueryResponse, Result};
+use crate::http::{GQLError, GQLResponse};
+use crate::{Error, QueryResponse, Result};
 use bytes::{buf::BufExt, Buf, Bytes};
+use futures::future::Either;
 use futures::{Stream, StreamExt};
 
-/// Create a multipart response data stream.
+/// The format of the payloads written by `multipart_stream_with_format`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MultipartStreamFormat {
+    /// Incremental delivery format.
+    ///
+    /// Every payload carries a `hasNext` flag, and subsequent payloads are wrapped in an `incremental` list.
+    /// This is the format expected by current Relay and urql clients.
+    ///
+    /// https://github.com/graphql/graphql-spec/blob/main/rfcs/DeferStream.md
+    Incremental,
+
+    /// The format used by earlier versions, every payload is a complete response with `label` and `path`.
+    Legacy,
+}
+
+impl Default for MultipartStreamFormat {
+    fn default() -> Self {
+        MultipartStreamFormat::Incremental
+    }
+}
+
+impl MultipartStreamFormat {
+    /// The value of the `Content-Type` header for this format.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            MultipartStreamFormat::Incremental => {
+                "multipart/mixed; boundary=\"-\"; deferSpec=20220824"
+            }
+            MultipartStreamFormat::Legacy => "multipart/mixed; boundary=\"-\"",
+        }
+    }
+
+    /// Select the format from the value of the `Accept` header.
+    ///
+    /// Clients that accept `multipart/mixed` without the `deferSpec` parameter get the legacy format.
+    pub fn from_accept(accept: Option<&str>) -> Self {
+        let mut multipart = accept
+            .unwrap_or_default()
+            .split(',')
+            .map(|media_range| media_range.split(';').map(str::trim))
+            .filter(|params| {
+                params
+                    .clone()
+                    .next()
+                    .map(|media_type| media_type.eq_ignore_ascii_case("multipart/mixed"))
+                    .unwrap_or_default()
+            })
+            .peekable();
+
+        if multipart.peek().is_none() {
+            return MultipartStreamFormat::Incremental;
+        }
+        if multipart.any(|mut params| params.any(|param| param.starts_with("deferSpec="))) {
+            MultipartStreamFormat::Incremental
+        } else {
+            MultipartStreamFormat::Legacy
+        }
+    }
+}
+
+/// Create a multipart response data stream in the incremental delivery format.
 pub fn multipart_stream(s: impl Stream<Item = Result<QueryResponse>>) -> impl Stream<Item = Bytes> {
-    s.map(|res| serde_json::to_vec(&GQLResponse(res)).unwrap())
-        .map(|data| {
+    multipart_stream_with_format(s, MultipartStreamFormat::Incremental)
+}
+
+/// Create a multipart response data stream in the specified format.
+pub fn multipart_stream_with_format(
+    s: impl Stream<Item = Result<QueryResponse>>,
+    format: MultipartStreamFormat,
+) -> impl Stream<Item = Bytes> {
+    let payloads = match format {
+        MultipartStreamFormat::Incremental => {
+            let mut is_first = true;
+            let payloads = s
+                .map(move |res| {
+                    let payload = incremental_payload(res, is_first);
+                    is_first = false;
+                    payload
+                })
+                // The next part is not known when a part is written, so the end of the stream
+                // is signalled by a final payload.
+                .chain(futures::stream::once(async move {
+                    serde_json::json!({ "hasNext": false })
+                }))
+                .map(|payload| serde_json::to_vec(&payload).unwrap());
+            Either::Left(payloads)
+        }
+        MultipartStreamFormat::Legacy => {
+            Either::Right(s.map(|res| serde_json::to_vec(&GQLResponse(res)).unwrap()))
+        }
+    };
+
+    payloads
+        .map(move |data| {
+            let content_type = match format {
+                MultipartStreamFormat::Incremental => "application/json; charset=utf-8",
+                MultipartStreamFormat::Legacy => "application/json",
+            };
             Bytes::from(format!(
-                "\r\n---\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+                "\r\n---\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n",
+                content_type,
                 data.len()
             ))
             .chain(Bytes::from(data))
@@ -18,3 +114,236 @@ pub fn multipart_stream(s: impl Stream<Item = Result<QueryResponse>>) -> impl St
             Bytes::from_static(b"\r\n-----\r\n")
         }))
 }
+
+fn incremental_payload(res: Result<QueryResponse>, is_first: bool) -> serde_json::Value {
+    let mut payload = serde_json::Map::new();
+    match res {
+        Ok(resp) if is_first => {
+            payload.insert("data".to_string(), resp.data);
+            if let Some(extensions) = resp.extensions {
+                payload.insert("extensions".to_string(), extensions);
+            }
+        }
+        Ok(resp) => {
+            let mut path = resp.path.unwrap_or_default();
+            let mut item = serde_json::Map::new();
+
+            // Items of a `@stream` list are addressed by their index, while the value of a
+            // `@defer` field is merged into the object at the parent path.
+            if let Some(serde_json::Value::Number(_)) = path.last() {
+                item.insert(
+                    "items".to_string(),
+                    serde_json::Value::Array(vec![resp.data]),
+                );
+            } else {
+                let data = match path.pop() {
+                    Some(serde_json::Value::String(key)) => {
+                        let mut data = serde_json::Map::new();
+                        data.insert(key, resp.data);
+                        serde_json::Value::Object(data)
+                    }
+                    _ => resp.data,
+                };
+                item.insert("data".to_string(), data);
+            }
+            item.insert("path".to_string(), serde_json::Value::Array(path));
+            if let Some(label) = resp.label {
+                item.insert("label".to_string(), label.into());
+            }
+            if let Some(extensions) = resp.extensions {
+                item.insert("extensions".to_string(), extensions);
+            }
+            payload.insert(
+                "incremental".to_string(),
+                serde_json::Value::Array(vec![serde_json::Value::Object(item)]),
+            );
+        }
+        Err(Error::Incremental {
+            label,
+            mut path,
+            err,
+        }) if !is_first => {
+            let mut item = serde_json::Map::new();
+
+            // Like a successful payload, a failed `@stream` item is addressed by its index, and a
+            // failed `@defer` field by the path of its parent.
+            if let Some(serde_json::Value::Number(_)) = path.last() {
+                item.insert("items".to_string(), serde_json::Value::Null);
+            } else {
+                path.pop();
+                item.insert("data".to_string(), serde_json::Value::Null);
+            }
+            item.insert("path".to_string(), serde_json::Value::Array(path));
+            if let Some(label) = label {
+                item.insert("label".to_string(), label.into());
+            }
+            item.insert(
+                "errors".to_string(),
+                serde_json::to_value(GQLError(&err)).unwrap(),
+            );
+            payload.insert(
+                "incremental".to_string(),
+                serde_json::Value::Array(vec![serde_json::Value::Object(item)]),
+            );
+        }
+        Err(err) => {
+            payload.insert(
+                "errors".to_string(),
+                serde_json::to_value(GQLError(&err)).unwrap(),
+            );
+        }
+    }
+    payload.insert("hasNext".to_string(), true.into());
+    serde_json::Value::Object(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse_parts(data: &[u8]) -> Vec<serde_json::Value> {
+        let data = String::from_utf8(data.to_vec()).unwrap();
+        let data = data.strip_suffix("\r\n-----\r\n").unwrap();
+        data.split("\r\n---\r\n")
+            .skip(1)
+            .map(|part| {
+                let body = part.split_once("\r\n\r\n").unwrap().1;
+                serde_json::from_str(body).unwrap()
+            })
+            .collect()
+    }
+
+    fn responses() -> Vec<Result<QueryResponse>> {
+        vec![
+            Ok(QueryResponse {
+                label: None,
+                path: None,
+                data: json!({"a": 1, "b": null, "c": []}),
+                extensions: None,
+                cache_control: Default::default(),
+            }),
+            Ok(QueryResponse {
+                label: Some("b".to_string()),
+                path: Some(vec![json!("b")]),
+                data: json!({"value": 10}),
+                extensions: None,
+                cache_control: Default::default(),
+            }),
+            Ok(QueryResponse {
+                label: Some("c$0".to_string()),
+                path: Some(vec![json!("c"), json!(0)]),
+                data: json!({"value": 20}),
+                extensions: None,
+                cache_control: Default::default(),
+            }),
+        ]
+    }
+
+    async fn collect(format: MultipartStreamFormat) -> Vec<u8> {
+        multipart_stream_with_format(futures::stream::iter(responses()), format)
+            .collect::<Vec<_>>()
+            .await
+            .concat()
+    }
+
+    #[test]
+    fn test_from_accept() {
+        assert_eq!(
+            MultipartStreamFormat::from_accept(None),
+            MultipartStreamFormat::Incremental
+        );
+        assert_eq!(
+            MultipartStreamFormat::from_accept(Some("application/json")),
+            MultipartStreamFormat::Incremental
+        );
+        assert_eq!(
+            MultipartStreamFormat::from_accept(Some(
+                "multipart/mixed; deferSpec=20220824, application/json"
+            )),
+            MultipartStreamFormat::Incremental
+        );
+        assert_eq!(
+            MultipartStreamFormat::from_accept(Some("multipart/mixed, application/json")),
+            MultipartStreamFormat::Legacy
+        );
+    }
+
+    #[async_std::test]
+    async fn test_incremental_format() {
+        let data = collect(MultipartStreamFormat::Incremental).await;
+        assert_eq!(
+            parse_parts(&data),
+            vec![
+                json!({"data": {"a": 1, "b": null, "c": []}, "hasNext": true}),
+                json!({
+                    "incremental": [{"data": {"b": {"value": 10}}, "path": [], "label": "b"}],
+                    "hasNext": true
+                }),
+                json!({
+                    "incremental": [{"items": [{"value": 20}], "path": ["c", 0], "label": "c$0"}],
+                    "hasNext": true
+                }),
+                json!({"hasNext": false}),
+            ]
+        );
+    }
+
+    #[async_std::test]
+    async fn test_incremental_format_with_errors() {
+        let mut responses = responses();
+        responses.truncate(1);
+        responses.push(Err(Error::Incremental {
+            label: Some("b".to_string()),
+            path: vec![json!("b")],
+            err: Box::new(Error::Query {
+                pos: crate::Pos {
+                    line: 1,
+                    column: 10,
+                },
+                path: Some(json!(["b", "inner", "value"])),
+                err: crate::QueryError::FieldError {
+                    err: "failed".to_string(),
+                    extended_error: None,
+                },
+            }),
+        }));
+        let data = multipart_stream(futures::stream::iter(responses))
+            .collect::<Vec<_>>()
+            .await
+            .concat();
+        assert_eq!(
+            parse_parts(&data),
+            vec![
+                json!({"data": {"a": 1, "b": null, "c": []}, "hasNext": true}),
+                json!({
+                    "incremental": [{
+                        "data": null,
+                        "path": [],
+                        "label": "b",
+                        "errors": [{
+                            "message": "failed",
+                            "locations": [{"line": 1, "column": 10}],
+                            "path": ["b", "inner", "value"]
+                        }]
+                    }],
+                    "hasNext": true
+                }),
+                json!({"hasNext": false}),
+            ]
+        );
+    }
+
+    #[async_std::test]
+    async fn test_legacy_format() {
+        let data = collect(MultipartStreamFormat::Legacy).await;
+        assert_eq!(
+            parse_parts(&data),
+            vec![
+                json!({"data": {"a": 1, "b": null, "c": []}}),
+                json!({"label": "b", "path": ["b"], "data": {"value": 10}}),
+                json!({"label": "c$0", "path": ["c", 0], "data": {"value": 20}}),
+            ]
+        );
+    }
+}
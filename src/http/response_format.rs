@@ -0,0 +1,128 @@
+/// The format of a GraphQL response body
+///
+/// **Reference:** <https://github.com/graphql/graphql-over-http/blob/main/spec/GraphQLOverHTTP.md>
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ResponseFormat {
+    /// `application/json`
+    ///
+    /// The status code is always `200 OK`, this is what legacy clients expect.
+    Json,
+
+    /// `application/graphql-response+json`
+    ///
+    /// Requests that cannot be executed, such as those with parse or validation errors,
+    /// are responded with `400 Bad Request`.
+    GraphQLResponseJson,
+}
+
+impl Default for ResponseFormat {
+    fn default() -> Self {
+        ResponseFormat::Json
+    }
+}
+
+impl ResponseFormat {
+    /// Select the format from the value of the `Accept` header.
+    ///
+    /// The media type with the highest quality is selected, and `application/graphql-response+json`
+    /// is preferred when the qualities are the same. If the header is missing or no supported media
+    /// type is acceptable, `application/json` is used.
+    pub fn from_accept(accept: Option<&str>) -> Self {
+        let mut selected: Option<(f32, ResponseFormat)> = None;
+
+        for media_range in accept.unwrap_or_default().split(',') {
+            let mut params = media_range.split(';');
+            let media_type = params.next().unwrap_or_default().trim();
+            let quality = params
+                .filter_map(|param| {
+                    let mut kv = param.splitn(2, '=');
+                    match (kv.next().map(str::trim), kv.next()) {
+                        (Some("q"), Some(value)) => value.trim().parse::<f32>().ok(),
+                        _ => None,
+                    }
+                })
+                .next()
+                .unwrap_or(1.0);
+
+            let format = if media_type.eq_ignore_ascii_case("application/graphql-response+json") {
+                ResponseFormat::GraphQLResponseJson
+            } else if media_type.eq_ignore_ascii_case("application/json")
+                || media_type.eq_ignore_ascii_case("application/*")
+                || media_type == "*/*"
+            {
+                ResponseFormat::Json
+            } else {
+                continue;
+            };
+
+            if quality <= 0.0 {
+                continue;
+            }
+
+            selected = match selected {
+                Some((selected_quality, selected_format))
+                    if selected_quality > quality
+                        || (selected_quality == quality
+                            && selected_format == ResponseFormat::GraphQLResponseJson) =>
+                {
+                    Some((selected_quality, selected_format))
+                }
+                _ => Some((quality, format)),
+            };
+        }
+
+        selected.map(|(_, format)| format).unwrap_or_default()
+    }
+
+    /// The value of the `Content-Type` header for this format.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ResponseFormat::Json => "application/json",
+            ResponseFormat::GraphQLResponseJson => "application/graphql-response+json",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_accept() {
+        assert_eq!(ResponseFormat::from_accept(None), ResponseFormat::Json);
+        assert_eq!(
+            ResponseFormat::from_accept(Some("*/*")),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            ResponseFormat::from_accept(Some("text/html")),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            ResponseFormat::from_accept(Some("application/graphql-response+json")),
+            ResponseFormat::GraphQLResponseJson
+        );
+        assert_eq!(
+            ResponseFormat::from_accept(Some(
+                "application/json, application/graphql-response+json"
+            )),
+            ResponseFormat::GraphQLResponseJson
+        );
+        assert_eq!(
+            ResponseFormat::from_accept(Some(
+                "application/graphql-response+json;q=0.9, application/json"
+            )),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            ResponseFormat::from_accept(Some(
+                "application/graphql-response+json, application/json;q=0.9"
+            )),
+            ResponseFormat::GraphQLResponseJson
+        );
+        assert_eq!(
+            ResponseFormat::from_accept(Some("application/graphql-response+json;q=0")),
+            ResponseFormat::Json
+        );
+    }
+}
@@ -107,6 +107,7 @@ extern crate serde_derive;
 extern crate log;
 
 mod base;
+mod cancellation;
 mod context;
 mod error;
 mod look_ahead;
@@ -122,6 +123,7 @@ mod validation;
 
 pub mod extensions;
 pub mod guard;
+pub mod middleware;
 pub mod validators;
 
 #[doc(hidden)]
@@ -136,11 +138,14 @@ pub use futures;
 #[doc(hidden)]
 pub use indexmap;
 #[doc(hidden)]
+pub use once_cell;
+#[doc(hidden)]
 pub use serde_json;
 
 pub mod http;
 
 pub use base::{ScalarType, Type};
+pub use cancellation::{CancellationToken, Cancelled};
 pub use context::{
     Context, ContextBase, Data, QueryEnv, QueryPathNode, QueryPathSegment, Variables,
 };
@@ -149,9 +154,10 @@ pub use error::{
     ParseRequestError, QueryError, ResultExt, RuleError,
 };
 pub use look_ahead::Lookahead;
-pub use parser::{Pos, Positioned, Value};
+pub use parser::{Pos, Positioned, Span, Value};
 pub use query::{
     IntoQueryBuilder, IntoQueryBuilderOpts, QueryBuilder, QueryResponse, StreamResponse,
+    UploadConstraints,
 };
 pub use registry::CacheControl;
 pub use scalars::{Any, Json, ID};
@@ -162,7 +168,7 @@ pub use subscription::{
 pub use types::{
     connection, Deferred, EmptyMutation, EmptySubscription, MaybeUndefined, Streamed, Upload,
 };
-pub use validation::ValidationMode;
+pub use validation::{ValidationMode, Visitor, VisitorContext};
 
 /// Result type
 pub type Result<T> = std::result::Result<T, Error>;
@@ -206,6 +212,8 @@ pub use types::{EnumItem, EnumType};
 /// | provides      | Annotate the expected returned fieldset from a field on a base type that is guaranteed to be selectable by the gateway. | string | Y |
 /// | requires      | Annotate the required input fieldset from a base type for a resolver. It is used to develop a query plan where the required fields may not be needed by the client, but the service may need additional information from other services. | string | Y |
 /// | guard         | Field of guard            | [`Guard`](guard/trait.Guard.html) | Y        |
+/// | middleware    | Field middleware, can be specified multiple times, the expression is evaluated once | [`FieldMiddleware`](middleware/trait.FieldMiddleware.html) expression string | Y |
+/// | timeout       | Time limit of resolving the field, such as "500ms", "3s" or "1m" | string | Y |
 /// | feature       | It's like a `#[cfg(feature = "foo")]` attribute but instead of not compiling this field it will just return a proper `FieldError` to tell you this feature is not enabled | string ("feature1,feature2") | Y |
 ///
 /// # Field argument parameters
@@ -316,6 +324,8 @@ pub use async_graphql_derive::Object;
 /// | provides      | Annotate the expected returned fieldset from a field on a base type that is guaranteed to be selectable by the gateway. | string | Y |
 /// | requires      | Annotate the required input fieldset from a base type for a resolver. It is used to develop a query plan where the required fields may not be needed by the client, but the service may need additional information from other services. | string | Y |
 /// | guard         | Field of guard            | [`Guard`](guard/trait.Guard.html) | Y        |
+/// | middleware    | Field middleware, can be specified multiple times, the expression is evaluated once | [`FieldMiddleware`](middleware/trait.FieldMiddleware.html) expression string | Y |
+/// | timeout       | Time limit of resolving the field, such as "500ms", "3s" or "1m" | string | Y |
 /// | feature       | It's like a `#[cfg(feature = "foo")]` attribute but instead of not compiling this field it will just return a proper `FieldError` to tell you this feature is not enabled | string ("feature1,feature2") | Y |
 ///
 /// # Examples
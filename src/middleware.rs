@@ -0,0 +1,176 @@
+//! Field middlewares
+
+use crate::{Context, Error, ObjectType, QueryError, Result};
+use futures::future::{self, BoxFuture, Either};
+use futures::FutureExt;
+use futures_timer::Delay;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Field middleware
+///
+/// A middleware wraps the resolver of a field, it can do something before and after calling the
+/// resolver with `next`, transform the resolved value, or not call the resolver at all.
+///
+/// Middlewares can be added to all fields with `SchemaBuilder::field_middleware`, or to a single
+/// field with the `middleware` attribute of `#[field]`.
+///
+/// ```rust
+/// use async_graphql::*;
+/// use async_graphql::middleware::{FieldMiddleware, NextFieldResolve};
+///
+/// struct Uppercase;
+///
+/// #[async_trait::async_trait]
+/// impl FieldMiddleware for Uppercase {
+///     async fn resolve(&self, ctx: &Context<'_>, next: NextFieldResolve<'_>) -> Result<serde_json::Value> {
+///         match next.run().await? {
+///             serde_json::Value::String(s) => Ok(s.to_uppercase().into()),
+///             value => Ok(value),
+///         }
+///     }
+/// }
+///
+/// struct QueryRoot;
+///
+/// #[Object]
+/// impl QueryRoot {
+///     #[field(middleware = "Uppercase")]
+///     async fn value(&self) -> &str {
+///         "abc"
+///     }
+/// }
+///
+/// async_std::task::block_on(async move {
+///     let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+///     let res = schema.execute("{ value }").await.unwrap().data;
+///     assert_eq!(res, serde_json::json!({ "value": "ABC" }));
+/// });
+/// ```
+#[async_trait::async_trait]
+pub trait FieldMiddleware: Send + Sync + 'static {
+    /// Resolve the field, call `next.run()` to execute the next middleware or the resolver.
+    async fn resolve(
+        &self,
+        ctx: &Context<'_>,
+        next: NextFieldResolve<'_>,
+    ) -> Result<serde_json::Value>;
+}
+
+type ResolveFn<'a> = dyn Fn() -> BoxFuture<'a, Result<serde_json::Value>> + Send + Sync + 'a;
+
+/// The continuation passed to `FieldMiddleware::resolve`
+pub struct NextFieldResolve<'a> {
+    ctx: &'a Context<'a>,
+    middlewares: &'a [Arc<dyn FieldMiddleware>],
+    resolve: &'a ResolveFn<'a>,
+}
+
+impl<'a> NextFieldResolve<'a> {
+    #[doc(hidden)]
+    pub fn new(
+        ctx: &'a Context<'a>,
+        middlewares: &'a [Arc<dyn FieldMiddleware>],
+        resolve: &'a ResolveFn<'a>,
+    ) -> Self {
+        Self {
+            ctx,
+            middlewares,
+            resolve,
+        }
+    }
+
+    /// Execute the next middleware, or the resolver if this is the last one.
+    pub async fn run(self) -> Result<serde_json::Value> {
+        match self.middlewares.split_first() {
+            Some((middleware, middlewares)) => {
+                middleware
+                    .resolve(
+                        self.ctx,
+                        NextFieldResolve {
+                            ctx: self.ctx,
+                            middlewares,
+                            resolve: self.resolve,
+                        },
+                    )
+                    .await
+            }
+            None => (self.resolve)().await,
+        }
+    }
+}
+
+/// A middleware that fails the field if it is not resolved within the specified duration
+///
+/// The resolver is dropped when the timeout fires. It is used by the `timeout` attribute of
+/// `#[field]`, and can be added with `SchemaBuilder::field_middleware` to limit all fields.
+pub struct Timeout(pub Duration);
+
+#[async_trait::async_trait]
+impl FieldMiddleware for Timeout {
+    async fn resolve(
+        &self,
+        ctx: &Context<'_>,
+        next: NextFieldResolve<'_>,
+    ) -> Result<serde_json::Value> {
+        match future::select(Box::pin(next.run()), Delay::new(self.0)).await {
+            Either::Left((res, _)) => res,
+            Either::Right(_) => Err(Error::Query {
+                pos: ctx.position(),
+                path: ctx.path_node.as_ref().map(|path| path.to_json().into()),
+                err: QueryError::FieldError {
+                    err: "Field timed out".to_string(),
+                    extended_error: None,
+                },
+            }),
+        }
+    }
+}
+
+/// Resolve a field of `root` with the middlewares added to the schema.
+///
+/// If `SchemaBuilder::catch_panics` is enabled, a panic of the middlewares or the resolver is
+/// turned into an error of the field.
+pub(crate) async fn resolve_field<T: ObjectType + Send + Sync>(
+    ctx: &Context<'_>,
+    root: &T,
+) -> Result<serde_json::Value> {
+    if !ctx.schema_env.catch_panics {
+        return resolve_field_with_middlewares(ctx, root).await;
+    }
+    match AssertUnwindSafe(resolve_field_with_middlewares(ctx, root))
+        .catch_unwind()
+        .await
+    {
+        Ok(res) => res,
+        Err(payload) => {
+            let message = if let Some(s) = payload.downcast_ref::<&str>() {
+                s.to_string()
+            } else if let Some(s) = payload.downcast_ref::<String>() {
+                s.clone()
+            } else {
+                "Box<dyn Any>".to_string()
+            };
+            Err(Error::Query {
+                pos: ctx.position(),
+                path: ctx.path_node.as_ref().map(|path| path.to_json().into()),
+                err: QueryError::ResolverPanicked { message },
+            })
+        }
+    }
+}
+
+async fn resolve_field_with_middlewares<T: ObjectType + Send + Sync>(
+    ctx: &Context<'_>,
+    root: &T,
+) -> Result<serde_json::Value> {
+    let middlewares = &ctx.schema_env.field_middlewares;
+    if middlewares.is_empty() {
+        return root.resolve_field(ctx).await;
+    }
+    let resolve = || root.resolve_field(ctx);
+    NextFieldResolve::new(ctx, middlewares, &resolve)
+        .run()
+        .await
+}
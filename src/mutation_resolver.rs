@@ -1,5 +1,6 @@
 use crate::extensions::{ErrorLogger, Extension, ResolveInfo};
 use crate::parser::query::{Selection, TypeCondition};
+use crate::resolver::add_response_size;
 use crate::{ContextSelectionSet, Error, ObjectType, QueryError, Result};
 use std::future::Future;
 use std::pin::Pin;
@@ -74,6 +75,9 @@ fn do_resolve<'a, T: ObjectType + Send + Sync>(
                                 });
                             }
                         },
+                        alias: field.alias.as_ref().map(|alias| alias.as_str()),
+                        directives: &field.directives,
+                        ctx: &ctx_field,
                     };
 
                     ctx_field
@@ -81,17 +85,20 @@ fn do_resolve<'a, T: ObjectType + Send + Sync>(
                         .extensions
                         .lock()
                         .resolve_start(&resolve_info);
-                    let value = root
-                        .resolve_field(&ctx_field)
+                    let res = crate::middleware::resolve_field(&ctx_field, root)
                         .await
-                        .log_error(&ctx.query_env.extensions)?;
-                    values.insert(field_name, value);
+                        .and_then(|value| {
+                            add_response_size(&ctx_field, &field_name, &value)?;
+                            Ok(value)
+                        });
 
                     ctx_field
                         .query_env
                         .extensions
                         .lock()
-                        .resolve_end(&resolve_info);
+                        .resolve_end(&resolve_info, res.as_ref());
+                    let value = res.log_error(&ctx.query_env.extensions)?;
+                    values.insert(field_name, value);
                 }
                 Selection::FragmentSpread(fragment_spread) => {
                     if ctx.is_skip(&fragment_spread.directives)? {
@@ -1,21 +1,26 @@
-use crate::context::{Data, DeferList, ResolveId};
+use crate::cancellation::CancelOnDrop;
+use crate::context::{Data, DeferList, QueryLimits, ResolveId};
 use crate::error::ParseRequestError;
-use crate::extensions::{BoxExtension, ErrorLogger, Extension};
+use crate::extensions::{BoxExtension, ErrorLogger, Extension, Extensions};
 use crate::mutation_resolver::do_mutation_resolve;
 use crate::registry::CacheControl;
 use crate::{
-    do_resolve, ContextBase, Error, ObjectType, Pos, QueryEnv, QueryError, Result, Schema,
-    SubscriptionType, Variables,
+    do_resolve, CancellationToken, ContextBase, Error, ObjectType, Pos, QueryEnv, QueryError,
+    Result, Schema, SubscriptionType, Variables,
 };
 use async_graphql_parser::query::OperationType;
-use futures::{Stream, StreamExt};
+use async_graphql_parser::UploadContent;
+use futures::future::{self, Either};
+use futures::{Future, Stream, StreamExt};
+use futures_timer::Delay;
 use itertools::Itertools;
 use std::any::Any;
 use std::borrow::Cow;
-use std::fs::File;
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// IntoQueryBuilder options
 #[derive(Default, Clone)]
@@ -25,6 +30,37 @@ pub struct IntoQueryBuilderOpts {
 
     /// Maximum number of files.
     pub max_num_files: Option<usize>,
+
+    /// Maximum size of the whole request body.
+    pub max_request_size: Option<usize>,
+
+    /// Allowed content types of uploaded files, such as `image/png` or `image/*`.
+    ///
+    /// If it is `None`, all content types are allowed.
+    pub allowed_content_types: Option<Vec<String>>,
+
+    /// Constraints of the files uploaded to the specified variables.
+    ///
+    /// The key is a variable path in the same form as the `map` part of the upload request, such as
+    /// `variables.avatar`. A constraint also applies to the items of a list, so `variables.files`
+    /// matches `variables.files.0`.
+    pub variable_constraints: HashMap<String, UploadConstraints>,
+
+    /// Maximum size of a file kept in memory.
+    ///
+    /// Files no larger than this are kept in memory, larger files are stored in temporary files.
+    /// If it is `None`, all files are stored in temporary files.
+    pub max_in_memory_file_size: Option<usize>,
+}
+
+/// Constraints of the files uploaded to a variable
+#[derive(Default, Clone, Debug)]
+pub struct UploadConstraints {
+    /// Maximum file size.
+    pub max_file_size: Option<usize>,
+
+    /// Allowed content types, such as `image/png` or `image/*`.
+    pub allowed_content_types: Option<Vec<String>>,
 }
 
 #[allow(missing_docs)]
@@ -70,18 +106,7 @@ impl QueryResponse {
             self.path = Some(prefix);
         }
 
-        self.label = self.path.as_ref().map(|path| {
-            path.iter()
-                .map(|value| {
-                    if let serde_json::Value::String(s) = value {
-                        Cow::Borrowed(s.as_str())
-                    } else {
-                        Cow::Owned(value.to_string())
-                    }
-                })
-                .join("$")
-        });
-
+        self.label = self.path.as_deref().map(incremental_label);
         self
     }
 
@@ -141,6 +166,9 @@ pub struct QueryBuilder {
     pub(crate) operation_name: Option<String>,
     pub(crate) variables: Variables,
     pub(crate) ctx_data: Option<Data>,
+    timeout: Option<Duration>,
+    cancellation_token: Option<CancellationToken>,
+    concurrency: Option<usize>,
     extensions: Vec<Box<dyn Fn() -> BoxExtension + Send + Sync>>,
 }
 
@@ -152,6 +180,9 @@ impl QueryBuilder {
             operation_name: None,
             variables: Default::default(),
             ctx_data: None,
+            timeout: None,
+            cancellation_token: None,
+            concurrency: None,
             extensions: Default::default(),
         }
     }
@@ -169,6 +200,47 @@ impl QueryBuilder {
         QueryBuilder { variables, ..self }
     }
 
+    /// Set the time limit of executing this query, it overrides `SchemaBuilder::query_timeout`.
+    ///
+    /// The limit covers the incremental results of `@defer` and `@stream`. When it is exceeded,
+    /// the pending resolvers are dropped and an error is returned.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        QueryBuilder {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Set the cancellation token of this query.
+    ///
+    /// When the token is cancelled, the pending resolvers, including the incremental results of
+    /// `@defer` and `@stream`, are dropped and an error is returned. Resolvers can check for it
+    /// with `Context::is_cancelled`.
+    ///
+    /// The query always runs with a child token of this one, which is also cancelled when the
+    /// query future or the response stream is dropped before it completes, so a token can be
+    /// shared by many queries.
+    pub fn cancellation_token(self, token: CancellationToken) -> Self {
+        QueryBuilder {
+            cancellation_token: Some(token),
+            ..self
+        }
+    }
+
+    /// Set the maximum number of field resolvers that run concurrently in the query, it overrides
+    /// `SchemaBuilder::limit_concurrency`.
+    pub fn limit_concurrency(self, concurrency: usize) -> Self {
+        QueryBuilder {
+            concurrency: Some(concurrency),
+            ..self
+        }
+    }
+
+    /// Get a mutable reference to the variables.
+    pub fn variables_mut(&mut self) -> &mut Variables {
+        &mut self.variables
+    }
+
     /// Add an extension
     pub fn extension<F: Fn() -> E + Send + Sync + 'static, E: Extension>(
         mut self,
@@ -193,16 +265,23 @@ impl QueryBuilder {
         self
     }
 
+    /// Get a mutable reference to the context data of this query.
+    pub fn data_mut(&mut self) -> &mut Data {
+        self.ctx_data.get_or_insert_with(Default::default)
+    }
+
     /// Set uploaded file path
-    pub fn set_upload(
+    ///
+    /// The content can be a `File` or a `Vec<u8>` kept in memory.
+    pub fn set_upload<T: Into<UploadContent>>(
         &mut self,
         var_path: &str,
         filename: String,
         content_type: Option<String>,
-        content: File,
+        content: T,
     ) {
         self.variables
-            .set_upload(var_path, filename, content_type, content);
+            .set_upload(var_path, filename, content_type, content.into());
     }
 
     /// Execute the query, returns a stream, the first result being the query result,
@@ -218,11 +297,45 @@ impl QueryBuilder {
         Subscription: SubscriptionType + Send + Sync + 'static,
     {
         let schema = schema.clone();
-        match self.execute_first(&schema).await {
+        let extensions = schema.create_extensions(&self.extensions);
+        let query_extensions = self.extensions.len();
+        let mut current_extensions = std::mem::take(&mut *extensions.lock());
+        let res = current_extensions.prepare_request(self).await;
+        *extensions.lock() = current_extensions;
+        let builder = match res.log_error(&extensions) {
+            Ok(builder) => builder,
+            Err(err) => {
+                let mut current_extensions = std::mem::take(&mut *extensions.lock());
+                let res = current_extensions.after_execute(Err(err)).await;
+                *extensions.lock() = current_extensions;
+                return StreamResponse::Single(res);
+            }
+        };
+
+        // The limits and the extensions are taken from the request returned by `prepare_request`.
+        extensions.lock().0.extend(
+            builder.extensions[query_extensions..]
+                .iter()
+                .map(|factory| factory()),
+        );
+        let mut deadline = builder.timeout.or(schema.query_timeout).map(Delay::new);
+        let token = match &builder.cancellation_token {
+            Some(token) => token.child_token(),
+            None => CancellationToken::new(),
+        };
+        let mut guard = CancelOnDrop::new(token.clone());
+        match builder
+            .execute_first(&schema, extensions, &mut deadline, &token)
+            .await
+        {
             Ok((first_resp, defer_list)) if defer_list.futures.lock().is_empty() => {
+                guard.disarm();
                 StreamResponse::Single(Ok(first_resp))
             }
-            Err(err) => StreamResponse::Single(Err(err)),
+            Err(err) => {
+                guard.disarm();
+                StreamResponse::Single(Err(err))
+            }
             Ok((first_resp, defer_list)) => {
                 let stream = async_stream::try_stream! {
                     yield first_resp;
@@ -235,44 +348,72 @@ impl QueryBuilder {
                     loop {
                         let mut next_defer_list = Vec::new();
                         for (path_prefix, defer) in current_defer_list {
-                            let (res, mut defer_list) = defer.await?;
+                            let (pos, path) = (defer.pos, defer.path);
+                            let (res, mut defer_list) = with_limits(defer.future, &mut deadline, &token)
+                                .await
+                                .map_err(|err| incremental_error(err, pos, &path, &path_prefix))?;
                             for fut in defer_list.futures.into_inner() {
                                 let mut next_path_prefix = path_prefix.clone();
                                 next_path_prefix.extend(defer_list.path_prefix.clone());
                                 next_defer_list.push((next_path_prefix, fut));
                             }
-                            let mut new_res = res.apply_path_prefix(path_prefix);
-                            new_res.label = new_res.path.as_ref().map(|path| path.iter().map(|value| {
-                                if let serde_json::Value::String(s) = value {
-                                    s.to_string()
-                                } else {
-                                    value.to_string()
-                                }
-                            }).join("$"));
-                            yield new_res;
+                            yield res.apply_path_prefix(path_prefix);
                         }
                         if next_defer_list.is_empty() {
                             break;
                         }
                         current_defer_list = next_defer_list;
                     }
+
+                    guard.disarm();
                 };
                 StreamResponse::Stream(Box::pin(stream))
             }
         }
     }
 
-    async fn execute_first<'a, Query, Mutation, Subscription>(
+    async fn execute_first<Query, Mutation, Subscription>(
+        self,
+        schema: &Schema<Query, Mutation, Subscription>,
+        extensions: Arc<spin::Mutex<Extensions>>,
+        deadline: &mut Option<Delay>,
+        token: &CancellationToken,
+    ) -> Result<(QueryResponse, DeferList)>
+    where
+        Query: ObjectType + Send + Sync + 'static,
+        Mutation: ObjectType + Send + Sync + 'static,
+        Subscription: SubscriptionType + Send + Sync + 'static,
+    {
+        let res = with_limits(
+            self.execute_operation(schema, extensions.clone(), token.clone()),
+            deadline,
+            token,
+        )
+        .await;
+
+        let mut current_extensions = std::mem::take(&mut *extensions.lock());
+        let (res, defer_list) = match res {
+            Ok((resp, defer_list)) => (Ok(resp), defer_list),
+            Err(err) => (Err(err), DeferList::default()),
+        };
+        let res = current_extensions.after_execute(res).await;
+        *extensions.lock() = current_extensions;
+        res.map(|resp| (resp, defer_list))
+    }
+
+    async fn execute_operation<Query, Mutation, Subscription>(
         self,
         schema: &Schema<Query, Mutation, Subscription>,
+        extensions: Arc<spin::Mutex<Extensions>>,
+        token: CancellationToken,
     ) -> Result<(QueryResponse, DeferList)>
     where
         Query: ObjectType + Send + Sync + 'static,
         Mutation: ObjectType + Send + Sync + 'static,
         Subscription: SubscriptionType + Send + Sync + 'static,
     {
-        let (mut document, cache_control, extensions) =
-            schema.prepare_query(&self.query_source, &self.variables, &self.extensions)?;
+        let (mut document, cache_control) =
+            schema.prepare_query(&self.query_source, &self.variables, &extensions)?;
 
         // execute
         let inc_resolve_id = AtomicUsize::default();
@@ -295,16 +436,27 @@ impl QueryBuilder {
             .log_error(&extensions);
         }
 
+        let mut variables = self.variables;
+        let mut ctx_data = self.ctx_data.unwrap_or_default();
+        let mut current_extensions = std::mem::take(&mut *extensions.lock());
+        let res = current_extensions
+            .before_execute(&document, &mut variables, &mut ctx_data)
+            .await;
+        *extensions.lock() = current_extensions;
+        res.log_error(&extensions)?;
+
         let env = QueryEnv::new(
             extensions,
-            self.variables,
+            variables,
             document,
-            Arc::new(self.ctx_data.unwrap_or_default()),
+            Arc::new(ctx_data),
+            token,
+            QueryLimits {
+                concurrency: self.concurrency.or(schema.limits.concurrency),
+                ..schema.limits
+            },
         );
-        let defer_list = DeferList {
-            path_prefix: Vec::new(),
-            futures: Default::default(),
-        };
+        let defer_list = DeferList::default();
         let ctx = ContextBase {
             path_node: None,
             resolve_id: ResolveId::root(),
@@ -368,3 +520,74 @@ impl QueryBuilder {
         &self.query_source
     }
 }
+
+/// Converts the error of a deferred payload resolved at `path`, relatively to `path_prefix`. When the
+/// query times out while the payload is resolved, the deferred field at `pos` is the one that timed out.
+fn incremental_error(
+    err: Error,
+    pos: Pos,
+    path: &[serde_json::Value],
+    path_prefix: &[serde_json::Value],
+) -> Error {
+    let err = match err {
+        Error::Query {
+            err: QueryError::Timeout,
+            ..
+        } => Error::Query {
+            pos,
+            path: None,
+            err: QueryError::FieldError {
+                err: "Field timed out".to_string(),
+                extended_error: None,
+            },
+        }
+        .into_incremental(path),
+        err => err,
+    };
+    match err.with_path_prefix(path_prefix) {
+        Error::Incremental { path, err, .. } => Error::Incremental {
+            label: Some(incremental_label(&path)),
+            path,
+            err,
+        },
+        err => err,
+    }
+}
+
+/// The label of an incremental payload, the segments of its path joined with `$`.
+fn incremental_label(path: &[serde_json::Value]) -> String {
+    path.iter()
+        .map(|value| {
+            if let serde_json::Value::String(s) = value {
+                Cow::Borrowed(s.as_str())
+            } else {
+                Cow::Owned(value.to_string())
+            }
+        })
+        .join("$")
+}
+
+/// Wait for the future, returns an error if the deadline is reached or the query is cancelled first.
+async fn with_limits<T>(
+    fut: impl Future<Output = Result<T>>,
+    deadline: &mut Option<Delay>,
+    token: &CancellationToken,
+) -> Result<T> {
+    let limit = async {
+        match deadline {
+            Some(deadline) => match future::select(deadline, token.cancelled()).await {
+                Either::Left(_) => QueryError::Timeout,
+                Either::Right(_) => QueryError::Cancelled,
+            },
+            None => {
+                token.cancelled().await;
+                QueryError::Cancelled
+            }
+        }
+    };
+    // Poll the limit first, so that an already cancelled query never succeeds.
+    match future::select(Box::pin(limit), Box::pin(fut)).await {
+        Either::Left((err, _)) => Err(err.into_error(Pos::default())),
+        Either::Right((res, _)) => res,
+    }
+}
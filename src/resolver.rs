@@ -1,8 +1,9 @@
 use crate::base::BoxFieldFuture;
 use crate::extensions::{ErrorLogger, Extension, ResolveInfo};
 use crate::parser::query::{Selection, TypeCondition};
-use crate::{ContextSelectionSet, Error, ObjectType, QueryError, Result};
+use crate::{Context, ContextSelectionSet, Error, ObjectType, QueryError, Result};
 use futures::{future, TryFutureExt};
+use std::sync::atomic::Ordering;
 
 #[allow(missing_docs)]
 pub async fn do_resolve<'a, T: ObjectType + Send + Sync>(
@@ -11,7 +12,7 @@ pub async fn do_resolve<'a, T: ObjectType + Send + Sync>(
 ) -> Result<serde_json::Value> {
     let mut futures = Vec::new();
     collect_fields(ctx, root, &mut futures)?;
-    let res = futures::future::try_join_all(futures).await?;
+    let res = future::try_join_all(futures).await?;
     let mut map = serde_json::Map::new();
     for (name, value) in res {
         if let serde_json::Value::Object(b) = value {
@@ -27,6 +28,77 @@ pub async fn do_resolve<'a, T: ObjectType + Send + Sync>(
     Ok(map.into())
 }
 
+/// Add the approximate serialized size of a field to the response size, returns an error if it
+/// exceeds the limit.
+///
+/// The values of the fields with a selection set are not counted here, because their fields are
+/// counted when they are resolved.
+pub(crate) fn add_response_size(
+    ctx: &Context<'_>,
+    name: &str,
+    value: &serde_json::Value,
+) -> Result<()> {
+    let limit = match ctx.query_env.limits.response_size {
+        Some(limit) => limit,
+        None => return Ok(()),
+    };
+
+    // `"name":` and `,`
+    let mut size = name.len() + 4;
+    if ctx.item.selection_set.items.is_empty() {
+        size += estimate_size(value);
+    }
+
+    let total = ctx
+        .query_env
+        .response_size
+        .fetch_add(size, Ordering::Relaxed)
+        + size;
+    if total > limit {
+        return Err(Error::Query {
+            pos: ctx.position(),
+            path: ctx.path_node.as_ref().map(|path| path.to_json().into()),
+            err: QueryError::ResponseTooLarge { limit },
+        });
+    }
+    Ok(())
+}
+
+/// Estimate the serialized size of a value, the escape sequences of the strings are not counted
+/// and the floats are counted as their maximum length.
+fn estimate_size(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::Null => 4,
+        serde_json::Value::Bool(true) => 4,
+        serde_json::Value::Bool(false) => 5,
+        serde_json::Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(n), _) => digits(n),
+            (None, Some(n)) => 1 + digits(n.unsigned_abs()),
+            (None, None) => 24,
+        },
+        serde_json::Value::String(s) => s.len() + 2,
+        serde_json::Value::Array(items) => {
+            2 + items.len().saturating_sub(1) + items.iter().map(estimate_size).sum::<usize>()
+        }
+        serde_json::Value::Object(map) => {
+            2 + map.len().saturating_sub(1)
+                + map
+                    .iter()
+                    .map(|(name, value)| name.len() + 3 + estimate_size(value))
+                    .sum::<usize>()
+        }
+    }
+}
+
+fn digits(mut n: u64) -> usize {
+    let mut digits = 1;
+    while n >= 10 {
+        n /= 10;
+        digits += 1;
+    }
+    digits
+}
+
 #[allow(missing_docs)]
 pub fn collect_fields<'a, T: ObjectType + Send + Sync>(
     ctx: &ContextSelectionSet<'a>,
@@ -93,6 +165,9 @@ pub fn collect_fields<'a, T: ObjectType + Send + Sync>(
                                     });
                                 }
                             },
+                            alias: field.alias.as_ref().map(|alias| alias.as_str()),
+                            directives: &field.directives,
+                            ctx: &ctx_field,
                         };
 
                         ctx_field
@@ -101,18 +176,20 @@ pub fn collect_fields<'a, T: ObjectType + Send + Sync>(
                             .lock()
                             .resolve_start(&resolve_info);
 
-                        let res = root
-                            .resolve_field(&ctx_field)
-                            .map_ok(move |value| (field_name, value))
+                        let res = crate::middleware::resolve_field(&ctx_field, root)
                             .await
-                            .log_error(&ctx_field.query_env.extensions)?;
+                            .and_then(|value| {
+                                add_response_size(&ctx_field, &field_name, &value)?;
+                                Ok(value)
+                            });
 
                         ctx_field
                             .query_env
                             .extensions
                             .lock()
-                            .resolve_end(&resolve_info);
-                        Ok(res)
+                            .resolve_end(&resolve_info, res.as_ref());
+                        let value = res.log_error(&ctx_field.query_env.extensions)?;
+                        Ok((field_name, value))
                     }
                 }))
             }
@@ -1,30 +1,34 @@
-use crate::context::Data;
+use crate::cancellation::CancelOnDrop;
+use crate::context::{Data, QueryLimits};
 use crate::extensions::{BoxExtension, ErrorLogger, Extension, Extensions};
+use crate::middleware::FieldMiddleware;
 use crate::model::__DirectiveLocation;
-use crate::parser::parse_query;
+use crate::parser::{parse_query_with_options, ParserOptions};
 use crate::query::{QueryBuilder, StreamResponse};
 use crate::registry::{MetaDirective, MetaInputValue, Registry};
 use crate::subscription::{create_connection, create_subscription_stream, SubscriptionTransport};
 use crate::types::QueryRoot;
-use crate::validation::{check_rules, CheckResult, ValidationMode};
+use crate::validation::{check_rules, BoxValidationRule, CheckResult, ValidationMode};
 use crate::{
-    CacheControl, Error, ObjectType, Pos, QueryEnv, QueryError, QueryResponse, Result,
-    SubscriptionType, Type, Variables, ID,
+    CacheControl, CancellationToken, Error, ObjectType, Pos, QueryEnv, QueryError, QueryResponse,
+    Result, SubscriptionType, Type, Variables, Visitor, ID,
 };
 use async_graphql_parser::query::{Document, OperationType};
 use bytes::Bytes;
 use futures::channel::mpsc;
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use indexmap::map::IndexMap;
 use itertools::Itertools;
 use std::any::Any;
 use std::ops::Deref;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Schema builder
 pub struct SchemaBuilder<Query, Mutation, Subscription> {
     validation_mode: ValidationMode,
+    validation_rules: Vec<BoxValidationRule>,
     query: QueryRoot<Query>,
     mutation: Mutation,
     subscription: Subscription,
@@ -32,7 +36,12 @@ pub struct SchemaBuilder<Query, Mutation, Subscription> {
     data: Data,
     complexity: Option<usize>,
     depth: Option<usize>,
+    query_timeout: Option<Duration>,
+    limits: QueryLimits,
+    parser_options: ParserOptions,
     extensions: Vec<Box<dyn Fn() -> BoxExtension + Send + Sync>>,
+    field_middlewares: Vec<Arc<dyn FieldMiddleware>>,
+    catch_panics: bool,
     enable_federation: bool,
 }
 
@@ -63,6 +72,51 @@ impl<Query: ObjectType, Mutation: ObjectType, Subscription: SubscriptionType>
         self
     }
 
+    /// Set the time limit of executing a query or mutation, Default no limit.
+    ///
+    /// When the time limit is exceeded, the pending resolvers are dropped and an error is returned.
+    /// It can be overridden with `QueryBuilder::timeout`.
+    pub fn query_timeout(mut self, timeout: Duration) -> Self {
+        self.query_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum number of field resolvers that run concurrently, Default no limit.
+    ///
+    /// The limit applies to the whole query, a resolver waits for a slot before it runs and
+    /// releases it before the sub-fields of its value are resolved. The order of the results is
+    /// preserved. It can be overridden with `QueryBuilder::limit_concurrency`.
+    pub fn limit_concurrency(mut self, concurrency: usize) -> Self {
+        self.limits.concurrency = Some(concurrency);
+        self
+    }
+
+    /// Set the maximum size of the response in bytes, Default no limit.
+    ///
+    /// The size is measured approximately as the size of the serialized JSON while the results
+    /// are assembled, the execution is aborted with an error once it is exceeded.
+    pub fn limit_response_size(mut self, bytes: usize) -> Self {
+        self.limits.response_size = Some(bytes);
+        self
+    }
+
+    /// Set the maximum number of items of a list in the response, Default no limit.
+    ///
+    /// The execution is aborted with an error when a resolver returns a longer list.
+    pub fn limit_list_items(mut self, items: usize) -> Self {
+        self.limits.list_items = Some(items);
+        self
+    }
+
+    /// Set the limits of the query parser, Default no limit.
+    ///
+    /// Unlike `limit_depth` and `limit_complexity`, they are checked while parsing, so they
+    /// protect the parser itself from deeply nested or very large queries.
+    pub fn parser_options(mut self, options: ParserOptions) -> Self {
+        self.parser_options = options;
+        self
+    }
+
     /// Add an extension
     pub fn extension<F: Fn() -> E + Send + Sync + 'static, E: Extension>(
         mut self,
@@ -73,6 +127,25 @@ impl<Query: ObjectType, Mutation: ObjectType, Subscription: SubscriptionType>
         self
     }
 
+    /// Add a middleware that wraps the resolvers of all fields.
+    ///
+    /// The middlewares are called in the order in which they were added, before the middlewares
+    /// specified on the fields.
+    pub fn field_middleware<M: FieldMiddleware>(mut self, middleware: M) -> Self {
+        self.field_middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Catch the panics of field resolvers, and turn them into errors of the fields.
+    ///
+    /// By default, a panicking resolver unwinds through the executor and aborts the whole request.
+    /// With this enabled, the error carries the path and position of the field, and is reported to
+    /// the extensions with `Extension::error`.
+    pub fn catch_panics(mut self) -> Self {
+        self.catch_panics = true;
+        self
+    }
+
     /// Add a global data that can be accessed in the `Schema`, you access it with `Context::data`.
     pub fn data<D: Any + Send + Sync>(mut self, data: D) -> Self {
         self.data.insert(data);
@@ -85,6 +158,69 @@ impl<Query: ObjectType, Mutation: ObjectType, Subscription: SubscriptionType>
         self
     }
 
+    /// Add a custom validation rule, which checks the documents in addition to the rules of the
+    /// specification.
+    ///
+    /// The rule is cloned for each document, so it can keep the state of one traversal. It runs
+    /// after the built-in rules in both `ValidationMode::Strict` and `ValidationMode::Fast`, and
+    /// the document is rejected with an `Error::Rule` if it reports any error.
+    ///
+    /// ```rust
+    /// use async_graphql::*;
+    /// use async_graphql::parser::query::{OperationDefinition, Selection};
+    ///
+    /// /// Limit the number of root fields of the operations.
+    /// #[derive(Clone)]
+    /// struct MaxRootFields(usize);
+    ///
+    /// impl<'a> Visitor<'a> for MaxRootFields {
+    ///     fn enter_operation_definition(
+    ///         &mut self,
+    ///         ctx: &mut VisitorContext<'a>,
+    ///         operation_definition: &'a Positioned<OperationDefinition>,
+    ///     ) {
+    ///         let selection_set = match &operation_definition.node {
+    ///             OperationDefinition::SelectionSet(selection_set) => selection_set,
+    ///             OperationDefinition::Query(query) => &query.selection_set,
+    ///             OperationDefinition::Mutation(mutation) => &mutation.selection_set,
+    ///             OperationDefinition::Subscription(subscription) => &subscription.selection_set,
+    ///         };
+    ///         let fields = selection_set
+    ///             .items
+    ///             .iter()
+    ///             .filter(|selection| matches!(selection.node, Selection::Field(_)))
+    ///             .count();
+    ///         if fields > self.0 {
+    ///             ctx.report_error(
+    ///                 vec![operation_definition.span()],
+    ///                 format!("The operation selects more than {} root fields", self.0),
+    ///             );
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// struct Query;
+    ///
+    /// #[Object]
+    /// impl Query {
+    ///     async fn value(&self) -> i32 {
+    ///         10
+    ///     }
+    /// }
+    ///
+    /// let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+    ///     .validation_rule(MaxRootFields(2))
+    ///     .finish();
+    /// ```
+    pub fn validation_rule<V>(mut self, rule: V) -> Self
+    where
+        V: for<'a> Visitor<'a> + Clone + Send + Sync + 'static,
+    {
+        self.validation_rules
+            .push(Box::new(move || Box::new(rule.clone())));
+        self
+    }
+
     /// Enable federation, which is automatically enabled if the Query has least one entity definition.
     pub fn enable_federation(mut self) -> Self {
         self.enable_federation = true;
@@ -100,15 +236,21 @@ impl<Query: ObjectType, Mutation: ObjectType, Subscription: SubscriptionType>
 
         Schema(Arc::new(SchemaInner {
             validation_mode: self.validation_mode,
+            validation_rules: self.validation_rules,
             query: self.query,
             mutation: self.mutation,
             subscription: self.subscription,
             complexity: self.complexity,
             depth: self.depth,
+            query_timeout: self.query_timeout,
+            limits: self.limits,
+            parser_options: self.parser_options,
             extensions: self.extensions,
             env: SchemaEnv(Arc::new(SchemaEnvInner {
                 registry: self.registry,
                 data: self.data,
+                field_middlewares: self.field_middlewares,
+                catch_panics: self.catch_panics,
             })),
         }))
     }
@@ -118,6 +260,8 @@ impl<Query: ObjectType, Mutation: ObjectType, Subscription: SubscriptionType>
 pub struct SchemaEnvInner {
     pub registry: Registry,
     pub data: Data,
+    pub field_middlewares: Vec<Arc<dyn FieldMiddleware>>,
+    pub catch_panics: bool,
 }
 
 #[doc(hidden)]
@@ -135,11 +279,15 @@ impl Deref for SchemaEnv {
 #[doc(hidden)]
 pub struct SchemaInner<Query, Mutation, Subscription> {
     pub(crate) validation_mode: ValidationMode,
+    pub(crate) validation_rules: Vec<BoxValidationRule>,
     pub(crate) query: QueryRoot<Query>,
     pub(crate) mutation: Mutation,
     pub(crate) subscription: Subscription,
     pub(crate) complexity: Option<usize>,
     pub(crate) depth: Option<usize>,
+    pub(crate) query_timeout: Option<Duration>,
+    pub(crate) limits: QueryLimits,
+    pub(crate) parser_options: ParserOptions,
     pub(crate) extensions: Vec<Box<dyn Fn() -> BoxExtension + Send + Sync>>,
     pub(crate) env: SchemaEnv,
 }
@@ -272,6 +420,7 @@ where
 
         SchemaBuilder {
             validation_mode: ValidationMode::Strict,
+            validation_rules: Default::default(),
             query: QueryRoot {
                 inner: query,
                 disable_introspection: false,
@@ -282,7 +431,12 @@ where
             data: Default::default(),
             complexity: None,
             depth: None,
+            query_timeout: None,
+            limits: Default::default(),
+            parser_options: Default::default(),
             extensions: Default::default(),
+            field_middlewares: Default::default(),
+            catch_panics: false,
             enable_federation: false,
         }
     }
@@ -308,26 +462,30 @@ where
         QueryBuilder::new(query_source).execute_stream(self).await
     }
 
-    pub(crate) fn prepare_query(
+    pub(crate) fn create_extensions(
         &self,
-        source: &str,
-        variables: &Variables,
         query_extensions: &[Box<dyn Fn() -> BoxExtension + Send + Sync>],
-    ) -> Result<(Document, CacheControl, spin::Mutex<Extensions>)> {
-        // create extension instances
-        let extensions = spin::Mutex::new(Extensions(
+    ) -> Arc<spin::Mutex<Extensions>> {
+        Arc::new(spin::Mutex::new(Extensions(
             self.0
                 .extensions
                 .iter()
                 .chain(query_extensions)
                 .map(|factory| factory())
                 .collect_vec(),
-        ));
+        )))
+    }
 
-        extensions.lock().parse_start(source, &variables);
-        let document = parse_query(source)
+    pub(crate) fn prepare_query(
+        &self,
+        source: &str,
+        variables: &Variables,
+        extensions: &spin::Mutex<Extensions>,
+    ) -> Result<(Document, CacheControl)> {
+        extensions.lock().parse_start(source, variables);
+        let document = parse_query_with_options(source, &self.parser_options)
             .map_err(Into::<Error>::into)
-            .log_error(&extensions)?;
+            .log_error(extensions)?;
         extensions.lock().parse_end(&document);
 
         // check rules
@@ -336,25 +494,30 @@ where
             cache_control,
             complexity,
             depth,
-        } = check_rules(&self.env.registry, &document, self.validation_mode)
-            .log_error(&extensions)?;
+        } = check_rules(
+            &self.env.registry,
+            &document,
+            self.validation_mode,
+            &self.validation_rules,
+        )
+        .log_error(extensions)?;
         extensions.lock().validation_end();
 
         // check limit
         if let Some(limit_complexity) = self.complexity {
             if complexity > limit_complexity {
                 return Err(QueryError::TooComplex.into_error(Pos::default()))
-                    .log_error(&extensions);
+                    .log_error(extensions);
             }
         }
 
         if let Some(limit_depth) = self.depth {
             if depth > limit_depth {
-                return Err(QueryError::TooDeep.into_error(Pos::default())).log_error(&extensions);
+                return Err(QueryError::TooDeep.into_error(Pos::default())).log_error(extensions);
             }
         }
 
-        Ok((document, cache_control, extensions))
+        Ok((document, cache_control))
     }
 
     /// Create subscription stream, typically called inside the `SubscriptionTransport::handle_request` method
@@ -365,7 +528,8 @@ where
         variables: Variables,
         ctx_data: Option<Arc<Data>>,
     ) -> Result<impl Stream<Item = Result<serde_json::Value>> + Send> {
-        let (mut document, _, extensions) = self.prepare_query(source, &variables, &Vec::new())?;
+        let extensions = self.create_extensions(&[]);
+        let (mut document, _) = self.prepare_query(source, &variables, &extensions)?;
 
         if !document.retain_operation(operation_name) {
             return if let Some(name) = operation_name {
@@ -384,11 +548,14 @@ where
         }
 
         let resolve_id = AtomicUsize::default();
+        let cancellation_token = CancellationToken::new();
         let env = QueryEnv::new(
             extensions,
             variables,
             document,
             ctx_data.unwrap_or_default(),
+            cancellation_token.clone(),
+            self.limits,
         );
         let ctx = env.create_context(
             &self.env,
@@ -401,7 +568,13 @@ where
         create_subscription_stream(self, env.clone(), &ctx, &mut streams)
             .await
             .log_error(&ctx.query_env.extensions)?;
-        Ok(futures::stream::select_all(streams))
+
+        // Cancel the pending resolvers when the subscription is stopped.
+        let guard = CancelOnDrop::new(cancellation_token);
+        Ok(futures::stream::select_all(streams).map(move |item| {
+            let _guard = &guard;
+            item
+        }))
     }
 
     /// Create subscription connection, returns `Sink` and `Stream`.
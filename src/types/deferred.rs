@@ -59,7 +59,7 @@ impl<T: OutputValueType + Send + Sync + 'static> OutputValueType for Deferred<T>
                         .map(|path| path.to_json())
                         .unwrap_or_default();
 
-                    defer_list.append(async move {
+                    defer_list.append(field.position(), path_prefix.clone(), async move {
                         let inc_resolve_id = AtomicUsize::default();
                         let defer_list = DeferList {
                             path_prefix: path_prefix.clone(),
@@ -72,7 +72,10 @@ impl<T: OutputValueType + Send + Sync + 'static> OutputValueType for Deferred<T>
                             &inc_resolve_id,
                             Some(&defer_list),
                         );
-                        let data = obj.resolve(&ctx, &field).await?;
+                        let data = obj
+                            .resolve(&ctx, &field)
+                            .await
+                            .map_err(|err| err.into_incremental(&path_prefix))?;
 
                         Ok((
                             QueryResponse {
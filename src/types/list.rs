@@ -1,6 +1,6 @@
 use crate::{
-    registry, ContextSelectionSet, InputValueResult, InputValueType, OutputValueType, Positioned,
-    Result, Type, Value,
+    registry, ContextSelectionSet, Error, InputValueResult, InputValueType, OutputValueType,
+    Positioned, QueryError, Result, Type, Value,
 };
 use async_graphql_parser::query::Field;
 use std::borrow::Cow;
@@ -47,6 +47,7 @@ impl<T: OutputValueType + Send + Sync> OutputValueType for Vec<T> {
         ctx: &ContextSelectionSet<'_>,
         field: &Positioned<Field>,
     ) -> Result<serde_json::Value> {
+        check_list_items(ctx, field, self.len())?;
         let mut futures = Vec::with_capacity(self.len());
         for (idx, item) in self.iter().enumerate() {
             let ctx_idx = ctx.with_index(idx);
@@ -78,6 +79,7 @@ impl<T: OutputValueType + Send + Sync> OutputValueType for &[T] {
         ctx: &ContextSelectionSet<'_>,
         field: &Positioned<Field>,
     ) -> Result<serde_json::Value> {
+        check_list_items(ctx, field, self.len())?;
         let mut futures = Vec::with_capacity(self.len());
         for (idx, item) in (*self).iter().enumerate() {
             let ctx_idx = ctx.with_index(idx);
@@ -87,6 +89,21 @@ impl<T: OutputValueType + Send + Sync> OutputValueType for &[T] {
     }
 }
 
+fn check_list_items(
+    ctx: &ContextSelectionSet<'_>,
+    field: &Positioned<Field>,
+    len: usize,
+) -> Result<()> {
+    match ctx.query_env.limits.list_items {
+        Some(limit) if len > limit => Err(Error::Query {
+            pos: field.position(),
+            path: ctx.path_node.as_ref().map(|path| path.to_json().into()),
+            err: QueryError::TooManyListItems { len, limit },
+        }),
+        _ => Ok(()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::Type;
@@ -70,7 +70,7 @@ impl<T: OutputValueType + Send + Sync + 'static> OutputValueType for Streamed<T>
                         let schema_env = ctx.schema_env.clone();
                         let query_env = ctx.query_env.clone();
 
-                        defer_list.append(async move {
+                        defer_list.append(field.position(), path_prefix.clone(), async move {
                             let inc_resolve_id = AtomicUsize::default();
                             let defer_list = DeferList {
                                 path_prefix: path_prefix.clone(),
@@ -83,7 +83,10 @@ impl<T: OutputValueType + Send + Sync + 'static> OutputValueType for Streamed<T>
                                 &inc_resolve_id,
                                 Some(&defer_list),
                             );
-                            let data = item.resolve(&ctx, &field).await?;
+                            let data = item
+                                .resolve(&ctx, &field)
+                                .await
+                                .map_err(|err| err.into_incremental(&path_prefix))?;
 
                             Ok((
                                 QueryResponse {
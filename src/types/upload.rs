@@ -1,7 +1,12 @@
 use crate::{registry, InputValueError, InputValueResult, InputValueType, Type, Value};
-use async_graphql_parser::UploadValue;
+use async_graphql_parser::{UploadContent, UploadValue};
+use futures::AsyncRead;
 use std::borrow::Cow;
-use std::io::Read;
+use std::fs::File;
+use std::io::{Cursor, Read};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
 
 /// Uploaded file
 ///
@@ -55,11 +60,65 @@ impl Upload {
         self.0.content_type.as_deref()
     }
 
+    /// Returns `true` if the content of the file is kept in memory.
+    pub fn is_in_memory(&self) -> bool {
+        matches!(self.0.content, UploadContent::Memory(_))
+    }
+
     /// Convert to a `Read`.
     ///
     /// **Note**: this is a *synchronous/blocking* reader.
     pub fn into_read(self) -> impl Read + Sync + Send + 'static {
-        self.0.content
+        match self.0.content {
+            UploadContent::Memory(data) => UploadReader::Memory(Cursor::new(data)),
+            UploadContent::File(file) => UploadReader::File(file),
+        }
+    }
+
+    /// Convert to a `AsyncRead`.
+    ///
+    /// Files stored in temporary files are read on a thread pool, so it will not block the executor.
+    pub fn into_async_read(self) -> impl AsyncRead + Send + 'static {
+        match self.0.content {
+            UploadContent::Memory(data) => {
+                AsyncUploadReader::Memory(futures::io::Cursor::new(data))
+            }
+            UploadContent::File(file) => {
+                AsyncUploadReader::File(Box::new(blocking::Unblock::new(file)))
+            }
+        }
+    }
+}
+
+enum UploadReader {
+    Memory(Cursor<Arc<[u8]>>),
+    File(File),
+}
+
+impl Read for UploadReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            UploadReader::Memory(cursor) => cursor.read(buf),
+            UploadReader::File(file) => file.read(buf),
+        }
+    }
+}
+
+enum AsyncUploadReader {
+    Memory(futures::io::Cursor<Arc<[u8]>>),
+    File(Box<blocking::Unblock<File>>),
+}
+
+impl AsyncRead for AsyncUploadReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        match self.get_mut() {
+            AsyncUploadReader::Memory(cursor) => Pin::new(cursor).poll_read(cx, buf),
+            AsyncUploadReader::File(file) => Pin::new(file.as_mut()).poll_read(cx, buf),
+        }
     }
 }
 
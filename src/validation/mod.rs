@@ -10,7 +10,13 @@ mod test_harness;
 use crate::parser::query::Document;
 use crate::registry::Registry;
 use crate::{CacheControl, Error, Result};
-use visitor::{visit, VisitorContext, VisitorNil};
+use visitor::{visit, VisitorNil};
+
+pub use visitor::{Visitor, VisitorContext};
+
+/// A factory of the custom validation rules added with `SchemaBuilder::validation_rule`, each
+/// document is validated with a new rule.
+pub(crate) type BoxValidationRule = Box<dyn Fn() -> Box<dyn for<'a> Visitor<'a>> + Send + Sync>;
 
 pub struct CheckResult {
     pub cache_control: CacheControl,
@@ -32,6 +38,7 @@ pub fn check_rules(
     registry: &Registry,
     doc: &Document,
     mode: ValidationMode,
+    custom_rules: &[BoxValidationRule],
 ) -> Result<CheckResult> {
     let mut ctx = VisitorContext::new(registry, doc);
     let mut cache_control = CacheControl::default();
@@ -41,6 +48,7 @@ pub fn check_rules(
     match mode {
         ValidationMode::Strict => {
             let mut visitor = VisitorNil
+                .with(rules::ExecutableDefinitions)
                 .with(rules::ArgumentsOfCorrectType::default())
                 .with(rules::DefaultValuesOfCorrectType)
                 .with(rules::FieldsOnCorrectType)
@@ -54,16 +62,19 @@ pub fn check_rules(
                 .with(rules::NoUnusedFragments::default())
                 .with(rules::NoUnusedVariables::default())
                 .with(rules::UniqueArgumentNames::default())
+                .with(rules::UniqueDirectivesPerLocation)
                 .with(rules::UniqueFragmentNames::default())
+                .with(rules::UniqueInputFieldNames)
                 .with(rules::UniqueOperationNames::default())
                 .with(rules::UniqueVariableNames::default())
                 .with(rules::VariablesAreInputTypes)
                 .with(rules::VariableInAllowedPosition::default())
                 .with(rules::ScalarLeafs)
+                .with(rules::SingleFieldSubscriptions)
                 .with(rules::PossibleFragmentSpreads::default())
                 .with(rules::ProvidedNonNullArguments)
                 .with(rules::KnownDirectives::default())
-                .with(rules::OverlappingFieldsCanBeMerged)
+                .with(rules::OverlappingFieldsCanBeMerged::default())
                 .with(rules::UploadFile)
                 .with(visitors::CacheControlCalculate {
                     cache_control: &mut cache_control,
@@ -76,6 +87,7 @@ pub fn check_rules(
         }
         ValidationMode::Fast => {
             let mut visitor = VisitorNil
+                .with(rules::ExecutableDefinitions)
                 .with(rules::NoFragmentCycles::default())
                 .with(rules::UploadFile)
                 .with(visitors::CacheControlCalculate {
@@ -89,6 +101,10 @@ pub fn check_rules(
         }
     }
 
+    for rule in custom_rules {
+        visit(rule().as_mut(), &mut ctx, doc);
+    }
+
     if !ctx.errors.is_empty() {
         return Err(Error::Rule { errors: ctx.errors });
     }
@@ -45,7 +45,7 @@ impl<'a> Visitor<'a> for ArgumentsOfCorrectType<'a> {
             if let Some(validator) = &arg.validator {
                 if let Some(reason) = validator.is_valid(value) {
                     ctx.report_error(
-                        vec![name.position()],
+                        vec![name.span()],
                         format!("Invalid value for argument \"{}\", {}", arg.name, reason),
                     );
                     return;
@@ -62,7 +62,7 @@ impl<'a> Visitor<'a> for ArgumentsOfCorrectType<'a> {
                 },
             ) {
                 ctx.report_error(
-                    vec![name.position()],
+                    vec![name.span()],
                     format!("Invalid value for argument {}", reason),
                 );
             }
@@ -14,7 +14,7 @@ impl<'a> Visitor<'a> for DefaultValuesOfCorrectType {
     ) {
         if let Some(value) = &variable_definition.default_value {
             if let Type::NonNull(_) = &variable_definition.var_type.node {
-                ctx.report_error(vec![variable_definition.position()],format!(
+                ctx.report_error(vec![variable_definition.span()],format!(
                     "Argument \"{}\" has type \"{}\" and is not nullable, so it't can't have a default value",
                     variable_definition.name, variable_definition.var_type,
                 ));
@@ -28,7 +28,7 @@ impl<'a> Visitor<'a> for DefaultValuesOfCorrectType {
                 },
             ) {
                 ctx.report_error(
-                    vec![variable_definition.position()],
+                    vec![variable_definition.span()],
                     format!("Invalid default value for argument {}", reason),
                 )
             }
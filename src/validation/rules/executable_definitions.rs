@@ -0,0 +1,138 @@
+use crate::parser::query::Document;
+use crate::validation::visitor::{Visitor, VisitorContext};
+
+pub struct ExecutableDefinitions;
+
+impl<'a> Visitor<'a> for ExecutableDefinitions {
+    fn enter_document(&mut self, ctx: &mut VisitorContext<'a>, doc: &'a Document) {
+        for definition in doc.type_system_definitions() {
+            let message = match &definition.name {
+                Some(name) => format!("The \"{}\" definition is not executable.", name),
+                None => "The schema definition is not executable.".to_string(),
+            };
+            ctx.report_error(vec![definition.span()], message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::test_harness::validate;
+    use crate::{expect_passes_rule, Error};
+
+    pub fn factory() -> ExecutableDefinitions {
+        ExecutableDefinitions
+    }
+
+    fn expect_errors(query: &str, expected: Vec<(&str, usize, usize)>) {
+        let doc = crate::parser::parse_query(query).unwrap();
+        match validate(&doc, factory) {
+            Err(Error::Rule { errors }) => {
+                let errors = errors
+                    .iter()
+                    .map(|err| {
+                        (
+                            err.message.as_str(),
+                            err.locations[0].line,
+                            err.locations[0].column,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(errors, expected);
+            }
+            _ => panic!("Expected rule to fail"),
+        }
+    }
+
+    #[test]
+    fn with_only_operation() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          query Foo {
+            dog {
+              name
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn with_operation_and_fragment() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          query Foo {
+            dog {
+              name
+              ...Frag
+            }
+          }
+          fragment Frag on Dog {
+            name
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn with_type_definition() {
+        expect_errors(
+            r#"
+          query Foo {
+            dog {
+              name
+            }
+          }
+          type Cow {
+            name: String
+          }
+          extend type Dog {
+            color: String
+          }
+        "#,
+            vec![
+                ("The \"Cow\" definition is not executable.", 7, 11),
+                ("The \"Dog\" definition is not executable.", 10, 11),
+            ],
+        );
+    }
+
+    #[test]
+    fn with_schema_definition() {
+        expect_errors(
+            r#"
+          schema {
+            query: Query
+          }
+          type Query {
+            test: String
+          }
+          extend schema @directive
+        "#,
+            vec![
+                ("The schema definition is not executable.", 2, 11),
+                ("The \"Query\" definition is not executable.", 5, 11),
+                ("The schema definition is not executable.", 8, 11),
+            ],
+        );
+    }
+
+    #[test]
+    fn with_described_and_directive_definitions() {
+        expect_errors(
+            r#"
+          { dog { name } }
+          "A scalar"
+          scalar Date
+          directive @cached(ttl: Int = 10) repeatable on FIELD | QUERY
+        "#,
+            vec![
+                ("The \"Date\" definition is not executable.", 3, 11),
+                ("The \"cached\" definition is not executable.", 5, 11),
+            ],
+        );
+    }
+}
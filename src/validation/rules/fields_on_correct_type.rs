@@ -23,7 +23,7 @@ impl<'a> Visitor<'a> for FieldsOnCorrectType {
                 .is_none()
             {
                 ctx.report_error(
-                    vec![field.position()],
+                    vec![field.span()],
                     format!(
                         "Unknown field \"{}\" on type \"{}\".{}",
                         field.name,
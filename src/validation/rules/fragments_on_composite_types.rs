@@ -15,7 +15,7 @@ impl<'a> Visitor<'a> for FragmentsOnCompositeTypes {
             if !current_type.is_composite() {
                 let TypeCondition::On(name) = &fragment_definition.type_condition.node;
                 ctx.report_error(
-                    vec![fragment_definition.position()],
+                    vec![fragment_definition.span()],
                     format!(
                         "Fragment \"{}\" cannot condition non composite type \"{}\"",
                         fragment_definition.name, name
@@ -33,7 +33,7 @@ impl<'a> Visitor<'a> for FragmentsOnCompositeTypes {
         if let Some(current_type) = ctx.current_type() {
             if !current_type.is_composite() {
                 ctx.report_error(
-                    vec![inline_fragment.position()],
+                    vec![inline_fragment.span()],
                     format!(
                         "Fragment cannot condition non composite type \"{}\"",
                         current_type.name()
@@ -67,7 +67,7 @@ impl<'a> Visitor<'a> for KnownArgumentNames<'a> {
                         type_name,
                     } => {
                         ctx.report_error(
-                            vec![name.position()],
+                            vec![name.span()],
                             format!(
                                 "Unknown argument \"{}\" on field \"{}\" of type \"{}\".{}",
                                 name,
@@ -79,7 +79,7 @@ impl<'a> Visitor<'a> for KnownArgumentNames<'a> {
                     }
                     ArgsType::Directive(directive_name) => {
                         ctx.report_error(
-                            vec![name.position()],
+                            vec![name.span()],
                             format!(
                                 "Unknown argument \"{}\" on directive \"{}\".{}",
                                 name,
@@ -94,17 +94,17 @@ impl<'a> Visitor<'a> for KnownArgumentNames<'a> {
     }
 
     fn enter_field(&mut self, ctx: &mut VisitorContext<'a>, field: &'a Positioned<Field>) {
-        if let Some(parent_type) = ctx.parent_type() {
-            if let Some(schema_field) = parent_type.field_by_name(&field.name) {
-                self.current_args = Some((
+        self.current_args = ctx.parent_type().and_then(|parent_type| {
+            parent_type.field_by_name(&field.name).map(|schema_field| {
+                (
                     &schema_field.args,
                     ArgsType::Field {
                         field_name: &field.name,
-                        type_name: ctx.parent_type().unwrap().name(),
+                        type_name: parent_type.name(),
                     },
-                ));
-            }
-        }
+                )
+            })
+        });
     }
 
     fn exit_field(&mut self, _ctx: &mut VisitorContext<'a>, _field: &'a Positioned<Field>) {
@@ -214,6 +214,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn directive_args_are_known_on_fragments() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          {
+            dog {
+              ...DogFields @include(if: true)
+              ... on Dog @skip(if: false) {
+                name
+              }
+            }
+          }
+          fragment DogFields on Dog {
+            name
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn ignores_args_of_unknown_fields_nested_in_fields_with_args() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          {
+            human(id: 1) {
+              unknownField(unknownArg: 1)
+            }
+          }
+        "#,
+        );
+    }
+
     #[test]
     fn undirective_args_are_invalid() {
         expect_fails_rule!(
@@ -226,6 +260,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn misspelled_directive_args_are_reported() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          {
+            dog @skip(iff: true)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn unknown_directive_args_on_fragments() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          {
+            dog {
+              ...DogFields @include(unless: true)
+            }
+          }
+          fragment DogFields on Dog {
+            name
+          }
+        "#,
+        );
+        expect_fails_rule!(
+            factory,
+            r#"
+          {
+            dog {
+              ... on Dog @skip(unless: false) {
+                name
+              }
+            }
+          }
+        "#,
+        );
+    }
+
     #[test]
     fn invalid_arg_name() {
         expect_fails_rule!(
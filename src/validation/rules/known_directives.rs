@@ -59,7 +59,7 @@ impl<'a> Visitor<'a> for KnownDirectives {
             if let Some(current_location) = self.location_stack.last() {
                 if !schema_directive.locations.contains(current_location) {
                     ctx.report_error(
-                        vec![directive.position()],
+                        vec![directive.span()],
                         format!(
                             "Directive \"{}\" may not be used on \"{:?}\"",
                             directive.name, current_location
@@ -69,7 +69,7 @@ impl<'a> Visitor<'a> for KnownDirectives {
             }
         } else {
             ctx.report_error(
-                vec![directive.position()],
+                vec![directive.span()],
                 format!("Unknown directive \"{}\"", directive.name),
             );
         }
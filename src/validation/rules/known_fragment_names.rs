@@ -13,7 +13,7 @@ impl<'a> Visitor<'a> for KnownFragmentNames {
     ) {
         if !ctx.is_known_fragment(&fragment_spread.fragment_name) {
             ctx.report_error(
-                vec![fragment_spread.position()],
+                vec![fragment_spread.span()],
                 format!(r#"Unknown fragment: "{}""#, fragment_spread.fragment_name),
             );
         }
@@ -1,7 +1,7 @@
 use crate::parser::query::{FragmentDefinition, InlineFragment, TypeCondition, VariableDefinition};
 use crate::registry::MetaTypeName;
 use crate::validation::visitor::{Visitor, VisitorContext};
-use crate::{Pos, Positioned};
+use crate::{Positioned, Span};
 
 #[derive(Default)]
 pub struct KnownTypeNames;
@@ -13,7 +13,7 @@ impl<'a> Visitor<'a> for KnownTypeNames {
         fragment_definition: &'a Positioned<FragmentDefinition>,
     ) {
         let TypeCondition::On(name) = &fragment_definition.type_condition.node;
-        validate_type(ctx, name.as_str(), fragment_definition.position());
+        validate_type(ctx, name.as_str(), fragment_definition.span());
     }
 
     fn enter_variable_definition(
@@ -24,7 +24,7 @@ impl<'a> Visitor<'a> for KnownTypeNames {
         validate_type(
             ctx,
             MetaTypeName::concrete_typename(&variable_definition.var_type.to_string()),
-            variable_definition.position(),
+            variable_definition.span(),
         );
     }
 
@@ -36,14 +36,14 @@ impl<'a> Visitor<'a> for KnownTypeNames {
         if let Some(TypeCondition::On(name)) =
             inline_fragment.type_condition.as_ref().map(|c| &c.node)
         {
-            validate_type(ctx, name.as_str(), inline_fragment.position());
+            validate_type(ctx, name.as_str(), inline_fragment.span());
         }
     }
 }
 
-fn validate_type(ctx: &mut VisitorContext<'_>, type_name: &str, pos: Pos) {
+fn validate_type(ctx: &mut VisitorContext<'_>, type_name: &str, span: Span) {
     if ctx.registry.types.get(type_name).is_none() {
-        ctx.report_error(vec![pos], format!(r#"Unknown type "{}""#, type_name));
+        ctx.report_error(vec![span], format!(r#"Unknown type "{}""#, type_name));
     }
 }
 
@@ -26,16 +26,16 @@ impl<'a> Visitor<'a> for LoneAnonymousOperation {
         operation_definition: &'a Positioned<OperationDefinition>,
     ) {
         if let Some(operation_count) = self.operation_count {
-            let (err, pos) = match &operation_definition.node {
-                OperationDefinition::SelectionSet(s) => (operation_count > 1, s.position()),
+            let (err, span) = match &operation_definition.node {
+                OperationDefinition::SelectionSet(s) => (operation_count > 1, s.span()),
                 OperationDefinition::Query(query) if query.name.is_none() => {
-                    (operation_count > 1, query.position())
+                    (operation_count > 1, query.span())
                 }
                 OperationDefinition::Mutation(mutation) if mutation.name.is_none() => {
-                    (operation_count > 1, mutation.position())
+                    (operation_count > 1, mutation.span())
                 }
                 OperationDefinition::Subscription(subscription) if subscription.name.is_none() => {
-                    (operation_count > 1, subscription.position())
+                    (operation_count > 1, subscription.span())
                 }
                 _ => {
                     return;
@@ -44,7 +44,7 @@ impl<'a> Visitor<'a> for LoneAnonymousOperation {
 
             if err {
                 ctx.report_error(
-                    vec![pos],
+                    vec![span],
                     "This anonymous operation must be the only defined operation",
                 );
             }
@@ -1,5 +1,6 @@
 mod arguments_of_correct_type;
 mod default_values_of_correct_type;
+mod executable_definitions;
 mod fields_on_correct_type;
 mod fragments_on_composite_types;
 mod known_argument_names;
@@ -15,8 +16,11 @@ mod overlapping_fields_can_be_merged;
 mod possible_fragment_spreads;
 mod provided_non_null_arguments;
 mod scalar_leafs;
+mod single_field_subscriptions;
 mod unique_argument_names;
+mod unique_directives_per_location;
 mod unique_fragment_names;
+mod unique_input_field_names;
 mod unique_operation_names;
 mod unique_variable_names;
 mod upload_file;
@@ -25,6 +29,7 @@ mod variables_in_allowed_position;
 
 pub use arguments_of_correct_type::ArgumentsOfCorrectType;
 pub use default_values_of_correct_type::DefaultValuesOfCorrectType;
+pub use executable_definitions::ExecutableDefinitions;
 pub use fields_on_correct_type::FieldsOnCorrectType;
 pub use fragments_on_composite_types::FragmentsOnCompositeTypes;
 pub use known_argument_names::KnownArgumentNames;
@@ -40,8 +45,11 @@ pub use overlapping_fields_can_be_merged::OverlappingFieldsCanBeMerged;
 pub use possible_fragment_spreads::PossibleFragmentSpreads;
 pub use provided_non_null_arguments::ProvidedNonNullArguments;
 pub use scalar_leafs::ScalarLeafs;
+pub use single_field_subscriptions::SingleFieldSubscriptions;
 pub use unique_argument_names::UniqueArgumentNames;
+pub use unique_directives_per_location::UniqueDirectivesPerLocation;
 pub use unique_fragment_names::UniqueFragmentNames;
+pub use unique_input_field_names::UniqueInputFieldNames;
 pub use unique_operation_names::UniqueOperationNames;
 pub use unique_variable_names::UniqueVariableNames;
 pub use upload_file::UploadFile;
@@ -1,18 +1,18 @@
 use crate::error::RuleError;
 use crate::parser::query::{Document, FragmentDefinition, FragmentSpread};
 use crate::validation::visitor::{Visitor, VisitorContext};
-use crate::{Pos, Positioned};
+use crate::{Positioned, Span};
 use std::collections::{HashMap, HashSet};
 
 struct CycleDetector<'a> {
     visited: HashSet<&'a str>,
-    spreads: &'a HashMap<&'a str, Vec<(&'a str, Pos)>>,
+    spreads: &'a HashMap<&'a str, Vec<(&'a str, Span)>>,
     path_indices: HashMap<&'a str, usize>,
     errors: Vec<RuleError>,
 }
 
 impl<'a> CycleDetector<'a> {
-    fn detect_from(&mut self, from: &'a str, path: &mut Vec<(&'a str, Pos)>) {
+    fn detect_from(&mut self, from: &'a str, path: &mut Vec<(&'a str, Span)>) {
         self.visited.insert(from);
 
         if !self.spreads.contains_key(from) {
@@ -21,22 +21,23 @@ impl<'a> CycleDetector<'a> {
 
         self.path_indices.insert(from, path.len());
 
-        for (name, pos) in &self.spreads[from] {
+        for (name, span) in &self.spreads[from] {
             let index = self.path_indices.get(name).cloned();
 
             if let Some(index) = index {
-                let err_pos = if index < path.len() {
+                let err_span = if index < path.len() {
                     path[index].1
                 } else {
-                    *pos
+                    *span
                 };
 
                 self.errors.push(RuleError {
-                    locations: vec![err_pos],
+                    locations: vec![err_span.start],
+                    spans: vec![err_span],
                     message: format!("Cannot spread fragment \"{}\"", name),
                 });
             } else if !self.visited.contains(name) {
-                path.push((name, *pos));
+                path.push((name, *span));
                 self.detect_from(name, path);
                 path.pop();
             }
@@ -49,7 +50,7 @@ impl<'a> CycleDetector<'a> {
 #[derive(Default)]
 pub struct NoFragmentCycles<'a> {
     current_fragment: Option<&'a str>,
-    spreads: HashMap<&'a str, Vec<(&'a str, Pos)>>,
+    spreads: HashMap<&'a str, Vec<(&'a str, Span)>>,
     fragment_order: Vec<&'a str>,
 }
 
@@ -98,7 +99,7 @@ impl<'a> Visitor<'a> for NoFragmentCycles<'a> {
             self.spreads
                 .entry(current_fragment)
                 .or_insert_with(Vec::new)
-                .push((&fragment_spread.fragment_name, fragment_spread.position()));
+                .push((&fragment_spread.fragment_name, fragment_spread.span()));
         }
     }
 }
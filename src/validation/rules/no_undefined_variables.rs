@@ -3,13 +3,13 @@ use crate::parser::query::{
 };
 use crate::validation::utils::{operation_name, referenced_variables, Scope};
 use crate::validation::visitor::{Visitor, VisitorContext};
-use crate::{Pos, Positioned, Value};
+use crate::{Positioned, Span, Value};
 use std::collections::{HashMap, HashSet};
 
 #[derive(Default)]
 pub struct NoUndefinedVariables<'a> {
-    defined_variables: HashMap<Option<&'a str>, (Pos, HashSet<&'a str>)>,
-    used_variables: HashMap<Scope<'a>, HashMap<&'a str, Pos>>,
+    defined_variables: HashMap<Option<&'a str>, (Span, HashSet<&'a str>)>,
+    used_variables: HashMap<Scope<'a>, HashMap<&'a str, Span>>,
     current_scope: Option<Scope<'a>>,
     spreads: HashMap<Scope<'a>, Vec<&'a str>>,
 }
@@ -19,7 +19,7 @@ impl<'a> NoUndefinedVariables<'a> {
         &'a self,
         scope: &Scope<'a>,
         defined: &HashSet<&'a str>,
-        unused: &mut Vec<(&'a str, Pos)>,
+        unused: &mut Vec<(&'a str, Span)>,
         visited: &mut HashSet<Scope<'a>>,
     ) {
         if visited.contains(scope) {
@@ -29,9 +29,9 @@ impl<'a> NoUndefinedVariables<'a> {
         visited.insert(scope.clone());
 
         if let Some(used_vars) = self.used_variables.get(scope) {
-            for (var, pos) in used_vars {
+            for (var, span) in used_vars {
                 if !defined.contains(var) {
-                    unused.push((*var, *pos));
+                    unused.push((*var, *span));
                 }
             }
         }
@@ -46,7 +46,7 @@ impl<'a> NoUndefinedVariables<'a> {
 
 impl<'a> Visitor<'a> for NoUndefinedVariables<'a> {
     fn exit_document(&mut self, ctx: &mut VisitorContext<'a>, _doc: &'a Document) {
-        for (op_name, &(ref def_pos, ref def_vars)) in &self.defined_variables {
+        for (op_name, &(ref def_span, ref def_vars)) in &self.defined_variables {
             let mut unused = Vec::new();
             let mut visited = HashSet::new();
             self.find_undef_vars(
@@ -56,17 +56,17 @@ impl<'a> Visitor<'a> for NoUndefinedVariables<'a> {
                 &mut visited,
             );
 
-            for (var, pos) in unused {
+            for (var, span) in unused {
                 if let Some(op_name) = op_name {
                     ctx.report_error(
-                        vec![*def_pos, pos],
+                        vec![*def_span, span],
                         format!(
                             r#"Variable "${}" is not defined by operation "{}""#,
                             var, op_name
                         ),
                     );
                 } else {
-                    ctx.report_error(vec![pos], format!(r#"Variable "${}" is not defined"#, var));
+                    ctx.report_error(vec![span], format!(r#"Variable "${}" is not defined"#, var));
                 }
             }
         }
@@ -77,10 +77,10 @@ impl<'a> Visitor<'a> for NoUndefinedVariables<'a> {
         _ctx: &mut VisitorContext<'a>,
         operation_definition: &'a Positioned<OperationDefinition>,
     ) {
-        let (op_name, pos) = operation_name(&operation_definition);
+        let (op_name, span) = operation_name(&operation_definition);
         self.current_scope = Some(Scope::Operation(op_name));
         self.defined_variables
-            .insert(op_name, (pos, HashSet::new()));
+            .insert(op_name, (span, HashSet::new()));
     }
 
     fn enter_fragment_definition(
@@ -116,7 +116,7 @@ impl<'a> Visitor<'a> for NoUndefinedVariables<'a> {
                 .extend(
                     referenced_variables(value)
                         .into_iter()
-                        .map(|n| (n, name.position())),
+                        .map(|n| (n, name.span())),
                 );
         }
     }
@@ -3,13 +3,13 @@ use crate::parser::query::{
 };
 use crate::validation::utils::{operation_name, Scope};
 use crate::validation::visitor::{Visitor, VisitorContext};
-use crate::{Pos, Positioned};
+use crate::{Positioned, Span};
 use std::collections::{HashMap, HashSet};
 
 #[derive(Default)]
 pub struct NoUnusedFragments<'a> {
     spreads: HashMap<Scope<'a>, Vec<&'a str>>,
-    defined_fragments: HashSet<(&'a str, Pos)>,
+    defined_fragments: HashSet<(&'a str, Span)>,
     current_scope: Option<Scope<'a>>,
 }
 
@@ -42,10 +42,10 @@ impl<'a> Visitor<'a> for NoUnusedFragments<'a> {
             }
         }
 
-        for (fragment_name, pos) in &self.defined_fragments {
+        for (fragment_name, span) in &self.defined_fragments {
             if !reachable.contains(fragment_name) {
                 ctx.report_error(
-                    vec![*pos],
+                    vec![*span],
                     format!(r#"Fragment "{}" is never used"#, fragment_name),
                 );
             }
@@ -69,7 +69,7 @@ impl<'a> Visitor<'a> for NoUnusedFragments<'a> {
         self.current_scope = Some(Scope::Fragment(fragment_definition.name.as_str()));
         self.defined_fragments.insert((
             fragment_definition.name.as_str(),
-            fragment_definition.position(),
+            fragment_definition.span(),
         ));
     }
 
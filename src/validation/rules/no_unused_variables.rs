@@ -3,12 +3,12 @@ use crate::parser::query::{
 };
 use crate::validation::utils::{operation_name, referenced_variables, Scope};
 use crate::validation::visitor::{Visitor, VisitorContext};
-use crate::{Pos, Positioned, Value};
+use crate::{Positioned, Span, Value};
 use std::collections::{HashMap, HashSet};
 
 #[derive(Default)]
 pub struct NoUnusedVariables<'a> {
-    defined_variables: HashMap<Option<&'a str>, HashSet<(&'a str, Pos)>>,
+    defined_variables: HashMap<Option<&'a str>, HashSet<(&'a str, Span)>>,
     used_variables: HashMap<Scope<'a>, Vec<&'a str>>,
     current_scope: Option<Scope<'a>>,
     spreads: HashMap<Scope<'a>, Vec<&'a str>>,
@@ -56,17 +56,17 @@ impl<'a> Visitor<'a> for NoUnusedVariables<'a> {
                 &mut visited,
             );
 
-            for (var, pos) in def_vars.iter().filter(|(var, _)| !used.contains(var)) {
+            for (var, span) in def_vars.iter().filter(|(var, _)| !used.contains(var)) {
                 if let Some(op_name) = op_name {
                     ctx.report_error(
-                        vec![*pos],
+                        vec![*span],
                         format!(
                             r#"Variable "${}" is not used by operation "{}""#,
                             var, op_name
                         ),
                     );
                 } else {
-                    ctx.report_error(vec![*pos], format!(r#"Variable "${}" is not used"#, var));
+                    ctx.report_error(vec![*span], format!(r#"Variable "${}" is not used"#, var));
                 }
             }
         }
@@ -99,7 +99,7 @@ impl<'a> Visitor<'a> for NoUnusedVariables<'a> {
             if let Some(vars) = self.defined_variables.get_mut(name) {
                 vars.insert((
                     variable_definition.name.as_str(),
-                    variable_definition.position(),
+                    variable_definition.span(),
                 ));
             }
         }
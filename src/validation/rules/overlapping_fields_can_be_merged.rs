@@ -1,81 +1,1007 @@
-use crate::parser::query::{Field, Selection, SelectionSet};
+use crate::parser::query::{Field, FragmentDefinition, Selection, SelectionSet, TypeCondition};
+use crate::registry::{MetaField, MetaType, MetaTypeName};
 use crate::validation::visitor::{Visitor, VisitorContext};
-use crate::Positioned;
+use crate::{Positioned, Value};
+use indexmap::map::IndexMap;
 use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+use std::rc::Rc;
 
+/// Fields with the same response name must be merged into a single field when executing, so they
+/// must select the same field with the same arguments, unless their parent types are different
+/// object types, and their return types must be compatible.
+///
+/// The fields of the selection sets and the compared fragments are cached, so that each pair of
+/// fragments is only compared once.
 #[derive(Default)]
-pub struct OverlappingFieldsCanBeMerged;
+pub struct OverlappingFieldsCanBeMerged<'a> {
+    cached_fields_and_fragment_names: HashMap<*const SelectionSet, Rc<FieldsAndFragmentNames<'a>>>,
+    compared_fields_and_fragment_pairs: PairSet<(*const FieldsAndFragmentNames<'a>, &'a str)>,
+    compared_fragment_pairs: PairSet<(&'a str, &'a str)>,
+}
 
-impl<'a> Visitor<'a> for OverlappingFieldsCanBeMerged {
+impl<'a> Visitor<'a> for OverlappingFieldsCanBeMerged<'a> {
     fn enter_selection_set(
         &mut self,
         ctx: &mut VisitorContext<'a>,
         selection_set: &'a Positioned<SelectionSet>,
     ) {
-        let mut find_conflicts = FindConflicts {
-            outputs: Default::default(),
-            ctx,
-        };
-        find_conflicts.find(selection_set);
+        let conflicts = FindConflicts { rule: self, ctx }
+            .find_conflicts_within_selection_set(ctx.current_type(), selection_set);
+
+        for conflict in conflicts {
+            ctx.report_error(
+                conflict
+                    .fields1
+                    .iter()
+                    .chain(&conflict.fields2)
+                    .map(|field| field.span())
+                    .collect(),
+                format!(
+                    "Fields \"{}\" conflict because {}. Use different aliases on the fields to fetch both if this was intentional.",
+                    conflict.response_name, conflict.reason
+                ),
+            );
+        }
+    }
+}
+
+/// A field with the type it is selected on and its definition.
+#[derive(Clone, Copy)]
+struct FieldInfo<'a> {
+    parent_type: Option<&'a MetaType>,
+    field: &'a Positioned<Field>,
+    definition: Option<&'a MetaField>,
+}
+
+/// The fields of a selection set by response name, including the fields of the inline fragments,
+/// and the names of the fragments it spreads.
+struct FieldsAndFragmentNames<'a> {
+    fields: IndexMap<&'a str, Vec<FieldInfo<'a>>>,
+    fragment_names: Vec<&'a str>,
+}
+
+enum ConflictReason<'a> {
+    Message(String),
+    Subfields(Vec<(&'a str, ConflictReason<'a>)>),
+}
+
+impl<'a> Display for ConflictReason<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ConflictReason::Message(message) => f.write_str(message),
+            ConflictReason::Subfields(subfields) => {
+                for (idx, (response_name, reason)) in subfields.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(" and ")?;
+                    }
+                    write!(
+                        f,
+                        "subfields \"{}\" conflict because {}",
+                        response_name, reason
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+struct Conflict<'a> {
+    response_name: &'a str,
+    reason: ConflictReason<'a>,
+    fields1: Vec<&'a Positioned<Field>>,
+    fields2: Vec<&'a Positioned<Field>>,
+}
+
+/// Pairs which were compared, and whether they were compared as mutually exclusive.
+struct PairSet<K> {
+    data: HashMap<K, bool>,
+}
+
+impl<K> Default for PairSet<K> {
+    fn default() -> Self {
+        Self {
+            data: HashMap::new(),
+        }
     }
 }
 
-struct FindConflicts<'a, 'ctx> {
-    outputs: HashMap<&'a str, &'a Positioned<Field>>,
-    ctx: &'a mut VisitorContext<'ctx>,
+impl<K: Hash + Eq> PairSet<K> {
+    fn has(&self, key: &K, mutually_exclusive: bool) -> bool {
+        match self.data.get(key) {
+            // Comparing the pair when it is not mutually exclusive also covers the mutually
+            // exclusive case, but not the other way around.
+            Some(compared_exclusive) => mutually_exclusive || !compared_exclusive,
+            None => false,
+        }
+    }
+
+    fn add(&mut self, key: K, mutually_exclusive: bool) {
+        self.data.insert(key, mutually_exclusive);
+    }
 }
 
-impl<'a, 'ctx> FindConflicts<'a, 'ctx> {
-    pub fn find(&mut self, selection_set: &'a Positioned<SelectionSet>) {
+struct FindConflicts<'r, 'a> {
+    rule: &'r mut OverlappingFieldsCanBeMerged<'a>,
+    ctx: &'r VisitorContext<'a>,
+}
+
+impl<'r, 'a> FindConflicts<'r, 'a> {
+    fn find_conflicts_within_selection_set(
+        &mut self,
+        parent_type: Option<&'a MetaType>,
+        selection_set: &'a Positioned<SelectionSet>,
+    ) -> Vec<Conflict<'a>> {
+        let mut conflicts = Vec::new();
+        let fields = self.fields_and_fragment_names(parent_type, selection_set);
+
+        self.collect_conflicts_within(&mut conflicts, &fields.fields);
+        for (idx, fragment_name) in fields.fragment_names.iter().enumerate() {
+            self.collect_conflicts_between_fields_and_fragment(
+                &mut conflicts,
+                false,
+                &fields,
+                fragment_name,
+            );
+            for other_fragment_name in &fields.fragment_names[idx + 1..] {
+                self.collect_conflicts_between_fragments(
+                    &mut conflicts,
+                    false,
+                    fragment_name,
+                    other_fragment_name,
+                );
+            }
+        }
+
+        conflicts
+    }
+
+    fn fields_and_fragment_names(
+        &mut self,
+        parent_type: Option<&'a MetaType>,
+        selection_set: &'a Positioned<SelectionSet>,
+    ) -> Rc<FieldsAndFragmentNames<'a>> {
+        let key = &selection_set.node as *const SelectionSet;
+        if let Some(fields) = self.rule.cached_fields_and_fragment_names.get(&key) {
+            return fields.clone();
+        }
+
+        let mut fields = FieldsAndFragmentNames {
+            fields: IndexMap::new(),
+            fragment_names: Vec::new(),
+        };
+        self.collect_fields_and_fragment_names(parent_type, selection_set, &mut fields);
+        let fields = Rc::new(fields);
+        self.rule
+            .cached_fields_and_fragment_names
+            .insert(key, fields.clone());
+        fields
+    }
+
+    fn referenced_fields_and_fragment_names(
+        &mut self,
+        fragment: &'a Positioned<FragmentDefinition>,
+    ) -> Rc<FieldsAndFragmentNames<'a>> {
+        let fragment_type = match &fragment.type_condition.node {
+            TypeCondition::On(name) => self.ctx.registry.types.get(name.as_str()),
+        };
+        self.fields_and_fragment_names(fragment_type, &fragment.selection_set)
+    }
+
+    fn collect_fields_and_fragment_names(
+        &self,
+        parent_type: Option<&'a MetaType>,
+        selection_set: &'a Positioned<SelectionSet>,
+        fields: &mut FieldsAndFragmentNames<'a>,
+    ) {
         for selection in &selection_set.items {
             match &selection.node {
                 Selection::Field(field) => {
-                    let output_name = field
-                        .alias
-                        .as_ref()
-                        .map(|name| name.as_str())
-                        .unwrap_or_else(|| field.name.as_str());
-                    self.add_output(&output_name, field);
+                    let response_name = field.alias.as_ref().unwrap_or(&field.name).as_str();
+                    fields
+                        .fields
+                        .entry(response_name)
+                        .or_default()
+                        .push(FieldInfo {
+                            parent_type,
+                            field,
+                            definition: parent_type.and_then(|ty| ty.field_by_name(&field.name)),
+                        });
+                }
+                Selection::FragmentSpread(fragment_spread) => {
+                    let name = fragment_spread.fragment_name.as_str();
+                    if !fields.fragment_names.contains(&name) {
+                        fields.fragment_names.push(name);
+                    }
                 }
                 Selection::InlineFragment(inline_fragment) => {
-                    self.find(&inline_fragment.selection_set);
+                    let fragment_type = match inline_fragment.type_condition.as_ref() {
+                        Some(type_condition) => match &type_condition.node {
+                            TypeCondition::On(name) => self.ctx.registry.types.get(name.as_str()),
+                        },
+                        None => parent_type,
+                    };
+                    self.collect_fields_and_fragment_names(
+                        fragment_type,
+                        &inline_fragment.selection_set,
+                        fields,
+                    );
                 }
-                Selection::FragmentSpread(fragment_spread) => {
-                    if let Some(fragment) = self.ctx.fragment(&fragment_spread.fragment_name) {
-                        self.find(&fragment.selection_set);
+            }
+        }
+    }
+
+    /// Collect the conflicts between the fields and the fields of a fragment, and the fragments
+    /// it spreads.
+    fn collect_conflicts_between_fields_and_fragment(
+        &mut self,
+        conflicts: &mut Vec<Conflict<'a>>,
+        mutually_exclusive: bool,
+        fields: &Rc<FieldsAndFragmentNames<'a>>,
+        fragment_name: &'a str,
+    ) {
+        let key = (Rc::as_ptr(fields), fragment_name);
+        if self
+            .rule
+            .compared_fields_and_fragment_pairs
+            .has(&key, mutually_exclusive)
+        {
+            return;
+        }
+        self.rule
+            .compared_fields_and_fragment_pairs
+            .add(key, mutually_exclusive);
+
+        let fragment = match self.ctx.fragment(fragment_name) {
+            Some(fragment) => fragment,
+            None => return,
+        };
+        let fragment_fields = self.referenced_fields_and_fragment_names(fragment);
+        // Do not compare the fields of a fragment to themselves.
+        if Rc::ptr_eq(fields, &fragment_fields) {
+            return;
+        }
+
+        self.collect_conflicts_between(
+            conflicts,
+            mutually_exclusive,
+            &fields.fields,
+            &fragment_fields.fields,
+        );
+        for referenced_fragment_name in &fragment_fields.fragment_names {
+            self.collect_conflicts_between_fields_and_fragment(
+                conflicts,
+                mutually_exclusive,
+                fields,
+                referenced_fragment_name,
+            );
+        }
+    }
+
+    /// Collect the conflicts between the fields of two fragments, and the fragments they spread.
+    fn collect_conflicts_between_fragments(
+        &mut self,
+        conflicts: &mut Vec<Conflict<'a>>,
+        mutually_exclusive: bool,
+        fragment_name1: &'a str,
+        fragment_name2: &'a str,
+    ) {
+        if fragment_name1 == fragment_name2 {
+            return;
+        }
+        let key = if fragment_name1 < fragment_name2 {
+            (fragment_name1, fragment_name2)
+        } else {
+            (fragment_name2, fragment_name1)
+        };
+        if self
+            .rule
+            .compared_fragment_pairs
+            .has(&key, mutually_exclusive)
+        {
+            return;
+        }
+        self.rule
+            .compared_fragment_pairs
+            .add(key, mutually_exclusive);
+
+        let (fragment1, fragment2) = match (
+            self.ctx.fragment(fragment_name1),
+            self.ctx.fragment(fragment_name2),
+        ) {
+            (Some(fragment1), Some(fragment2)) => (fragment1, fragment2),
+            _ => return,
+        };
+        let fields1 = self.referenced_fields_and_fragment_names(fragment1);
+        let fields2 = self.referenced_fields_and_fragment_names(fragment2);
+
+        self.collect_conflicts_between(
+            conflicts,
+            mutually_exclusive,
+            &fields1.fields,
+            &fields2.fields,
+        );
+        for referenced_fragment_name in &fields2.fragment_names {
+            self.collect_conflicts_between_fragments(
+                conflicts,
+                mutually_exclusive,
+                fragment_name1,
+                referenced_fragment_name,
+            );
+        }
+        for referenced_fragment_name in &fields1.fragment_names {
+            self.collect_conflicts_between_fragments(
+                conflicts,
+                mutually_exclusive,
+                referenced_fragment_name,
+                fragment_name2,
+            );
+        }
+    }
+
+    /// Collect the conflicts between the fields of a selection set and its sub-selections.
+    fn collect_conflicts_within(
+        &mut self,
+        conflicts: &mut Vec<Conflict<'a>>,
+        fields: &IndexMap<&'a str, Vec<FieldInfo<'a>>>,
+    ) {
+        for (response_name, fields) in fields {
+            for (idx, field1) in fields.iter().enumerate() {
+                for field2 in &fields[idx + 1..] {
+                    if let Some(conflict) = self.find_conflict(false, response_name, field1, field2)
+                    {
+                        conflicts.push(conflict);
                     }
                 }
             }
         }
     }
 
-    fn add_output(&mut self, name: &'a str, field: &'a Positioned<Field>) {
-        if let Some(prev_field) = self.outputs.get(name) {
-            if prev_field.name != field.name {
-                self.ctx.report_error(
-                    vec![prev_field.position(), field.position()],
-                    format!("Fields \"{}\" conflict because \"{}\" and \"{}\" are different fields. Use different aliases on the fields to fetch both if this was intentional.",
-                            name, prev_field.name, field.name));
+    /// Collect the conflicts between the fields of two different selection sets.
+    fn collect_conflicts_between(
+        &mut self,
+        conflicts: &mut Vec<Conflict<'a>>,
+        mutually_exclusive: bool,
+        fields1: &IndexMap<&'a str, Vec<FieldInfo<'a>>>,
+        fields2: &IndexMap<&'a str, Vec<FieldInfo<'a>>>,
+    ) {
+        for (response_name, fields1) in fields1 {
+            if let Some(fields2) = fields2.get(response_name) {
+                for field1 in fields1 {
+                    for field2 in fields2 {
+                        if let Some(conflict) =
+                            self.find_conflict(mutually_exclusive, response_name, field1, field2)
+                        {
+                            conflicts.push(conflict);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn find_conflict(
+        &mut self,
+        parent_fields_mutually_exclusive: bool,
+        response_name: &'a str,
+        field1: &FieldInfo<'a>,
+        field2: &FieldInfo<'a>,
+    ) -> Option<Conflict<'a>> {
+        // The fields can only be selected on different objects if their parent types are
+        // different object types, so they can be different fields with different arguments.
+        let mutually_exclusive = parent_fields_mutually_exclusive
+            || match (field1.parent_type, field2.parent_type) {
+                (Some(type1 @ MetaType::Object { .. }), Some(type2 @ MetaType::Object { .. })) => {
+                    type1.name() != type2.name()
+                }
+                _ => false,
+            };
+
+        let conflict = |reason: String| Conflict {
+            response_name,
+            reason: ConflictReason::Message(reason),
+            fields1: vec![field1.field],
+            fields2: vec![field2.field],
+        };
+
+        if !mutually_exclusive {
+            if field1.field.name.node != field2.field.name.node {
+                return Some(conflict(format!(
+                    "\"{}\" and \"{}\" are different fields",
+                    field1.field.name, field2.field.name
+                )));
+            }
+
+            if !same_arguments(&field1.field.arguments, &field2.field.arguments) {
+                return Some(conflict("they have differing arguments".to_string()));
+            }
+        }
+
+        let type1 = field1.definition.map(|definition| definition.ty.as_str());
+        let type2 = field2.definition.map(|definition| definition.ty.as_str());
+        if let (Some(type1), Some(type2)) = (type1, type2) {
+            if self.do_types_conflict(type1, type2) {
+                return Some(conflict(format!(
+                    "they return conflicting types \"{}\" and \"{}\"",
+                    type1, type2
+                )));
+            }
+        }
+
+        if field1.field.selection_set.items.is_empty()
+            || field2.field.selection_set.items.is_empty()
+        {
+            return None;
+        }
+
+        let registry = self.ctx.registry;
+        let subfield_conflicts = self.find_conflicts_between_sub_selection_sets(
+            mutually_exclusive,
+            type1.and_then(|ty| registry.concrete_type_by_name(ty)),
+            &field1.field.selection_set,
+            type2.and_then(|ty| registry.concrete_type_by_name(ty)),
+            &field2.field.selection_set,
+        );
+        if subfield_conflicts.is_empty() {
+            return None;
+        }
+
+        let mut reasons = Vec::new();
+        let mut fields1 = vec![field1.field];
+        let mut fields2 = vec![field2.field];
+        for subfield_conflict in subfield_conflicts {
+            reasons.push((subfield_conflict.response_name, subfield_conflict.reason));
+            fields1.extend(subfield_conflict.fields1);
+            fields2.extend(subfield_conflict.fields2);
+        }
+        Some(Conflict {
+            response_name,
+            reason: ConflictReason::Subfields(reasons),
+            fields1,
+            fields2,
+        })
+    }
+
+    /// Find the conflicts between the sub-selections of two fields with the same response name.
+    fn find_conflicts_between_sub_selection_sets(
+        &mut self,
+        mutually_exclusive: bool,
+        parent_type1: Option<&'a MetaType>,
+        selection_set1: &'a Positioned<SelectionSet>,
+        parent_type2: Option<&'a MetaType>,
+        selection_set2: &'a Positioned<SelectionSet>,
+    ) -> Vec<Conflict<'a>> {
+        let mut conflicts = Vec::new();
+        let fields1 = self.fields_and_fragment_names(parent_type1, selection_set1);
+        let fields2 = self.fields_and_fragment_names(parent_type2, selection_set2);
+
+        self.collect_conflicts_between(
+            &mut conflicts,
+            mutually_exclusive,
+            &fields1.fields,
+            &fields2.fields,
+        );
+        for fragment_name in &fields2.fragment_names {
+            self.collect_conflicts_between_fields_and_fragment(
+                &mut conflicts,
+                mutually_exclusive,
+                &fields1,
+                fragment_name,
+            );
+        }
+        for fragment_name in &fields1.fragment_names {
+            self.collect_conflicts_between_fields_and_fragment(
+                &mut conflicts,
+                mutually_exclusive,
+                &fields2,
+                fragment_name,
+            );
+        }
+        for fragment_name1 in &fields1.fragment_names {
+            for fragment_name2 in &fields2.fragment_names {
+                self.collect_conflicts_between_fragments(
+                    &mut conflicts,
+                    mutually_exclusive,
+                    fragment_name1,
+                    fragment_name2,
+                );
+            }
+        }
+
+        conflicts
+    }
+
+    /// Two types conflict if the shapes of their values are different, or if they are
+    /// different leaf types.
+    fn do_types_conflict(&self, type1: &str, type2: &str) -> bool {
+        match (MetaTypeName::create(type1), MetaTypeName::create(type2)) {
+            (MetaTypeName::List(type1), MetaTypeName::List(type2))
+            | (MetaTypeName::NonNull(type1), MetaTypeName::NonNull(type2)) => {
+                self.do_types_conflict(type1, type2)
+            }
+            (MetaTypeName::Named(type1), MetaTypeName::Named(type2)) => {
+                let is_leaf = |name: &str| {
+                    self.ctx
+                        .registry
+                        .types
+                        .get(name)
+                        .map(|ty| ty.is_leaf())
+                        .unwrap_or_default()
+                };
+                (is_leaf(type1) || is_leaf(type2)) && type1 != type2
+            }
+            _ => true,
+        }
+    }
+}
+
+fn same_arguments(
+    arguments1: &[(Positioned<String>, Positioned<Value>)],
+    arguments2: &[(Positioned<String>, Positioned<Value>)],
+) -> bool {
+    arguments1.len() == arguments2.len()
+        && arguments1.iter().all(|(name1, value1)| {
+            arguments2
+                .iter()
+                .any(|(name2, value2)| name1.node == name2.node && value1.node == value2.node)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::test_harness::validate;
+    use crate::{expect_fails_rule, expect_passes_rule, Error};
+
+    pub fn factory<'a>() -> OverlappingFieldsCanBeMerged<'a> {
+        OverlappingFieldsCanBeMerged::default()
+    }
+
+    #[test]
+    fn unique_fields() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          fragment uniqueFields on Dog {
+            name
+            nickname
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn identical_fields() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          fragment mergeIdenticalFields on Dog {
+            name
+            name
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn identical_fields_with_identical_args() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          fragment mergeIdenticalFieldsWithIdenticalArgs on Dog {
+            doesKnowCommand(dogCommand: SIT)
+            doesKnowCommand(dogCommand: SIT)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn identical_fields_with_identical_directives() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          fragment mergeSameFieldsWithSameDirectives on Dog {
+            name @include(if: true)
+            name @include(if: true)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn different_args_with_different_aliases() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          fragment differentArgsWithDifferentAliases on Dog {
+            knowsSit: doesKnowCommand(dogCommand: SIT)
+            knowsDown: doesKnowCommand(dogCommand: DOWN)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn different_directives_with_different_aliases() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          fragment differentDirectivesWithDifferentAliases on Dog {
+            nameIfTrue: name @include(if: true)
+            nameIfFalse: name @include(if: false)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn different_skip_include_directives_accepted() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          fragment differentDirectivesWithDifferentAliases on Dog {
+            name @include(if: true)
+            name @include(if: false)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn same_aliases_with_different_field_targets() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          fragment sameAliasesWithDifferentFieldTargets on Dog {
+            fido: name
+            fido: nickname
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn same_aliases_allowed_on_non_overlapping_fields() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          fragment sameAliasesWithDifferentFieldTargets on Pet {
+            ... on Dog {
+              name
+            }
+            ... on Cat {
+              name: nickname
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn alias_masking_direct_field_access() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          fragment aliasMaskingDirectFieldAccess on Dog {
+            name: nickname
+            name
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn different_args_second_adds_an_argument() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          fragment conflictingArgs on Dog {
+            doesKnowCommand
+            doesKnowCommand(dogCommand: HEEL)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn different_args_second_missing_an_argument() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          fragment conflictingArgs on Dog {
+            doesKnowCommand(dogCommand: SIT)
+            doesKnowCommand
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn conflicting_arg_values() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          fragment conflictingArgs on Dog {
+            doesKnowCommand(dogCommand: SIT)
+            doesKnowCommand(dogCommand: HEEL)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn conflicting_arg_names() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          fragment conflictingArgs on Dog {
+            isAtLocation(x: 0)
+            isAtLocation(y: 0)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn allows_different_args_where_no_conflict_is_possible() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          fragment conflictingArgs on Pet {
+            ... on Dog {
+              name(surname: true)
+            }
+            ... on Cat {
+              name
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn encounters_conflict_in_fragments() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          {
+            dog {
+              ...A
+              ...B
+            }
+          }
+          fragment A on Dog {
+            x: name
+          }
+          fragment B on Dog {
+            x: nickname
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn encounters_conflict_between_fields_and_nested_fragments() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          {
+            dog {
+              x: name
+              ...A
+            }
+          }
+          fragment A on Dog {
+            ...B
+          }
+          fragment B on Dog {
+            x: nickname
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn deep_conflict() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          {
+            dog {
+              x: name
+            }
+            dog {
+              x: nickname
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn deep_conflict_in_fragments() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          {
+            dog {
+              ...A
             }
+            dog {
+              ...B
+            }
+          }
+          fragment A on Dog {
+            x: name
+          }
+          fragment B on Dog {
+            x: nickname
+          }
+        "#,
+        );
+    }
 
-            // check arguments
-            if prev_field.arguments.len() != field.arguments.len() {
-                self.ctx.report_error(
-                    vec![prev_field.position(), field.position()],
-                    format!("Fields \"{}\" conflict because they have differing arguments. Use different aliases on the fields to fetch both if this was intentional.", name));
+    #[test]
+    fn ignores_unknown_fragments() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          {
+            dog {
+              name
+              ...Unknown
+              ...Known
             }
+          }
+          fragment Known on Dog {
+            name
+            ...OtherUnknown
+          }
+        "#,
+        );
+    }
 
-            for (name, value) in &prev_field.arguments {
-                match field.get_argument(name.as_str())
-                {
-                    Some(other_value) if value == other_value => {}
-                    _=> self.ctx.report_error(
-                        vec![prev_field.position(), field.position()],
-                        format!("Fields \"{}\" conflict because they have differing arguments. Use different aliases on the fields to fetch both if this was intentional.", name)),
+    #[test]
+    fn does_not_loop_on_fragment_cycles() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          {
+            dog {
+              ...A
+            }
+          }
+          fragment A on Dog {
+            name
+            ...B
+          }
+          fragment B on Dog {
+            name: nickname
+            ...A
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn conflicting_return_types_which_potentially_overlap() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          {
+            pet {
+              ... on Dog {
+                x: barkVolume
+              }
+              ... on Cat {
+                x: furColor
+              }
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn compatible_return_shapes_on_different_return_types() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          {
+            pet {
+              ... on Dog {
+                x: barkVolume
+              }
+              ... on Cat {
+                x: meowVolume
+              }
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn disallows_differing_return_type_list_despite_no_overlap() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          {
+            human {
+              ... on Human {
+                x: relatives {
+                  name
                 }
+              }
             }
-        } else {
-            self.outputs.insert(name, field);
+            human {
+              ... on Human {
+                x: pets {
+                  name
+                }
+              }
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn disallows_different_fields_on_interface() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          {
+            pet {
+              ... on Pet {
+                name
+              }
+              ... on Dog {
+                name: nickname
+              }
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn reports_deep_conflict_to_nearest_common_ancestor() {
+        let doc = crate::parser::parse_query(
+            r#"
+          {
+            dog {
+              x: name
+              y: barkVolume
+            }
+            dog {
+              x: nickname
+              y: barks
+            }
+          }
+        "#,
+        )
+        .unwrap();
+        match validate(&doc, factory) {
+            Err(Error::Rule { errors }) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(
+                    errors[0].message,
+                    "Fields \"dog\" conflict because subfields \"x\" conflict because \"name\" and \"nickname\" are different fields and subfields \"y\" conflict because \"barkVolume\" and \"barks\" are different fields. Use different aliases on the fields to fetch both if this was intentional."
+                );
+                assert_eq!(
+                    errors[0]
+                        .locations
+                        .iter()
+                        .map(|pos| (pos.line, pos.column))
+                        .collect::<Vec<_>>(),
+                    vec![(3, 13), (4, 15), (5, 15), (7, 13), (8, 15), (9, 15)]
+                );
+            }
+            _ => panic!("expected a rule error"),
         }
     }
 }
@@ -32,7 +32,7 @@ impl<'a> Visitor<'a> for PossibleFragmentSpreads<'a> {
                 if let Some(on_type) = ctx.registry.types.get(*fragment_type) {
                     if !current_type.type_overlap(on_type) {
                         ctx.report_error(
-                            vec![fragment_spread.position()],
+                            vec![fragment_spread.span()],
                             format!(
                                 "Fragment \"{}\" cannot be spread here as objects of type \"{}\" can never be of type \"{}\"",
                                 &fragment_spread.fragment_name, current_type.name(), fragment_type
@@ -56,7 +56,7 @@ impl<'a> Visitor<'a> for PossibleFragmentSpreads<'a> {
                 if let Some(on_type) = ctx.registry.types.get(fragment_type.as_str()) {
                     if !parent_type.type_overlap(&on_type) {
                         ctx.report_error(
-                            vec![inline_fragment.position()],
+                            vec![inline_fragment.span()],
                             format!(
                                 "Fragment cannot be spread here as objects of type \"{}\" \
              can never be of type \"{}\"",
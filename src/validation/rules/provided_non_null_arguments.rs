@@ -22,7 +22,7 @@ impl<'a> Visitor<'a> for ProvidedNonNullArguments {
                         .find(|(name, _)| name.node == arg.name)
                         .is_none()
                 {
-                    ctx.report_error(vec![directive.position()],
+                    ctx.report_error(vec![directive.span()],
                             format!(
                                 "Directive \"@{}\" argument \"{}\" of type \"{}\" is required but not provided",
                                 directive.name, arg.name, arg.ty
@@ -44,7 +44,7 @@ impl<'a> Visitor<'a> for ProvidedNonNullArguments {
                             .find(|(name, _)| name.node == arg.name)
                             .is_none()
                     {
-                        ctx.report_error(vec![field.position()],
+                        ctx.report_error(vec![field.span()],
                              format!(
                                  r#"Field "{}" argument "{}" of type "{}" is required but not provided"#,
                                  field.name, arg.name, parent_type.name()
@@ -11,13 +11,13 @@ impl<'a> Visitor<'a> for ScalarLeafs {
             if let Some(schema_field) = ty.field_by_name(&field.name) {
                 if let Some(ty) = ctx.registry.concrete_type_by_name(&schema_field.ty) {
                     if ty.is_leaf() && !field.selection_set.items.is_empty() {
-                        ctx.report_error(vec![field.position()], format!(
+                        ctx.report_error(vec![field.span()], format!(
                             "Field \"{}\" must not have a selection since type \"{}\" has no subfields",
                             field.name, ty.name()
                         ))
                     } else if !ty.is_leaf() && field.selection_set.items.is_empty() {
                         ctx.report_error(
-                            vec![field.position()],
+                            vec![field.span()],
                             format!(
                                 "Field \"{}\" of type \"{}\" must have a selection of subfields",
                                 field.name,
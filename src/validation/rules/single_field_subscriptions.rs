@@ -0,0 +1,263 @@
+use crate::parser::query::{OperationDefinition, Selection, SelectionSet};
+use crate::validation::visitor::{Visitor, VisitorContext};
+use crate::{Positioned, Span};
+use indexmap::IndexMap;
+use std::collections::HashSet;
+
+#[derive(Default)]
+pub struct SingleFieldSubscriptions;
+
+impl<'a> Visitor<'a> for SingleFieldSubscriptions {
+    fn enter_operation_definition(
+        &mut self,
+        ctx: &mut VisitorContext<'a>,
+        operation_definition: &'a Positioned<OperationDefinition>,
+    ) {
+        let subscription = match &operation_definition.node {
+            OperationDefinition::Subscription(subscription) => subscription,
+            _ => return,
+        };
+        let operation_name = match &subscription.name {
+            Some(name) => format!("Subscription \"{}\"", name),
+            None => "Anonymous Subscription".to_string(),
+        };
+
+        let mut fields = IndexMap::new();
+        collect_fields(
+            ctx,
+            &subscription.selection_set,
+            &mut fields,
+            &mut HashSet::new(),
+        );
+
+        if fields.len() > 1 {
+            ctx.report_error(
+                fields
+                    .values()
+                    .skip(1)
+                    .flat_map(|(_, spans)| spans.iter().copied())
+                    .collect(),
+                format!("{} must select only one top level field.", operation_name),
+            );
+        }
+
+        let introspection_spans = fields
+            .iter()
+            .filter(|(_, (name, _))| name.starts_with("__"))
+            .flat_map(|(_, (_, spans))| spans.iter().copied())
+            .collect::<Vec<_>>();
+        if !introspection_spans.is_empty() {
+            ctx.report_error(
+                introspection_spans,
+                format!(
+                    "{} must not select an introspection top level field.",
+                    operation_name
+                ),
+            );
+        }
+    }
+}
+
+/// Collects the top level fields grouped by response key, with the field name and the spans of
+/// the fields, following the fragment spreads and the inline fragments.
+fn collect_fields<'a>(
+    ctx: &VisitorContext<'a>,
+    selection_set: &'a Positioned<SelectionSet>,
+    fields: &mut IndexMap<&'a str, (&'a str, Vec<Span>)>,
+    visited_fragments: &mut HashSet<&'a str>,
+) {
+    for selection in &selection_set.items {
+        match &selection.node {
+            Selection::Field(field) => {
+                let response_key = field.alias.as_ref().unwrap_or(&field.name);
+                fields
+                    .entry(response_key.as_str())
+                    .or_insert_with(|| (field.name.as_str(), Vec::new()))
+                    .1
+                    .push(field.span());
+            }
+            Selection::InlineFragment(inline_fragment) => {
+                collect_fields(
+                    ctx,
+                    &inline_fragment.selection_set,
+                    fields,
+                    visited_fragments,
+                );
+            }
+            Selection::FragmentSpread(fragment_spread) => {
+                if !visited_fragments.insert(fragment_spread.fragment_name.as_str()) {
+                    continue;
+                }
+                if let Some(fragment) = ctx.fragment(&fragment_spread.fragment_name) {
+                    collect_fields(ctx, &fragment.selection_set, fields, visited_fragments);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::test_harness::validate;
+    use crate::{expect_fails_rule, expect_passes_rule, Error};
+
+    pub fn factory() -> SingleFieldSubscriptions {
+        SingleFieldSubscriptions
+    }
+
+    #[test]
+    fn valid_subscription() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          subscription ImportantEmails {
+            values
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn valid_subscription_with_fragment() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          subscription sub {
+            ...newMessageFields
+          }
+          fragment newMessageFields on SubscriptionRoot {
+            values
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn valid_subscription_with_repeated_field() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          subscription sub {
+            values
+            ... on SubscriptionRoot {
+              values
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn ignores_queries_and_mutations() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          query {
+            dog { name }
+            cat { name }
+          }
+          mutation {
+            a: testInput
+            b: testInput
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn fails_with_more_than_one_root_field() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          subscription ImportantEmails {
+            values
+            notImportantEmails
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn fails_with_more_than_one_root_field_including_introspection() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          subscription ImportantEmails {
+            values
+            __typename
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn fails_with_introspection_field() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          subscription ImportantEmails {
+            __typename
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn fails_with_more_than_one_root_field_in_fragments() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          subscription sub {
+            ...otherFields
+            ... on SubscriptionRoot {
+              values
+            }
+          }
+          fragment otherFields on SubscriptionRoot {
+            events
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn fails_with_many_more_than_one_root_field() {
+        let doc = crate::parser::parse_query(
+            r#"subscription ImportantEmails {
+  values
+  events
+  alias: values
+}"#,
+        )
+        .unwrap();
+        match validate(&doc, factory) {
+            Err(Error::Rule { errors }) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(
+                    errors[0].message,
+                    "Subscription \"ImportantEmails\" must select only one top level field."
+                );
+                let locations = errors[0]
+                    .locations
+                    .iter()
+                    .map(|pos| (pos.line, pos.column))
+                    .collect::<Vec<_>>();
+                assert_eq!(locations, vec![(3, 3), (4, 3)]);
+            }
+            _ => panic!("Expected rule to fail"),
+        }
+    }
+
+    #[test]
+    fn fails_with_more_than_one_root_field_in_anonymous_subscriptions() {
+        let doc = crate::parser::parse_query("subscription { values events }").unwrap();
+        match validate(&doc, factory) {
+            Err(Error::Rule { errors }) => assert_eq!(
+                errors[0].message,
+                "Anonymous Subscription must select only one top level field."
+            ),
+            _ => panic!("Expected rule to fail"),
+        }
+    }
+}
@@ -25,7 +25,7 @@ impl<'a> Visitor<'a> for UniqueArgumentNames<'a> {
     ) {
         if !self.names.insert(name) {
             ctx.report_error(
-                vec![name.position()],
+                vec![name.span()],
                 format!("There can only be one argument named \"{}\"", name),
             )
         }
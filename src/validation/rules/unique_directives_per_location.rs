@@ -0,0 +1,206 @@
+use crate::parser::query::{
+    Directive, Field, FragmentDefinition, FragmentSpread, InlineFragment, OperationDefinition,
+};
+use crate::validation::visitor::{Visitor, VisitorContext};
+use crate::Positioned;
+use std::collections::HashSet;
+
+#[derive(Default)]
+pub struct UniqueDirectivesPerLocation;
+
+impl UniqueDirectivesPerLocation {
+    fn check_directives(&self, ctx: &mut VisitorContext<'_>, directives: &[Positioned<Directive>]) {
+        let mut names = HashSet::new();
+        for directive in directives {
+            if !names.insert(directive.name.as_str()) {
+                ctx.report_error(
+                    vec![directive.span()],
+                    format!(
+                        "The directive \"@{}\" can only be used once at this location",
+                        directive.name
+                    ),
+                );
+            }
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for UniqueDirectivesPerLocation {
+    fn enter_operation_definition(
+        &mut self,
+        ctx: &mut VisitorContext<'a>,
+        operation_definition: &'a Positioned<OperationDefinition>,
+    ) {
+        let directives = match &operation_definition.node {
+            OperationDefinition::SelectionSet(_) => return,
+            OperationDefinition::Query(query) => &query.directives,
+            OperationDefinition::Mutation(mutation) => &mutation.directives,
+            OperationDefinition::Subscription(subscription) => &subscription.directives,
+        };
+        self.check_directives(ctx, directives);
+    }
+
+    fn enter_fragment_definition(
+        &mut self,
+        ctx: &mut VisitorContext<'a>,
+        fragment_definition: &'a Positioned<FragmentDefinition>,
+    ) {
+        self.check_directives(ctx, &fragment_definition.directives);
+    }
+
+    fn enter_field(&mut self, ctx: &mut VisitorContext<'a>, field: &'a Positioned<Field>) {
+        self.check_directives(ctx, &field.directives);
+    }
+
+    fn enter_fragment_spread(
+        &mut self,
+        ctx: &mut VisitorContext<'a>,
+        fragment_spread: &'a Positioned<FragmentSpread>,
+    ) {
+        self.check_directives(ctx, &fragment_spread.directives);
+    }
+
+    fn enter_inline_fragment(
+        &mut self,
+        ctx: &mut VisitorContext<'a>,
+        inline_fragment: &'a Positioned<InlineFragment>,
+    ) {
+        self.check_directives(ctx, &inline_fragment.directives);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::test_harness::validate;
+    use crate::{expect_fails_rule, expect_passes_rule, Error};
+
+    pub fn factory() -> UniqueDirectivesPerLocation {
+        UniqueDirectivesPerLocation
+    }
+
+    #[test]
+    fn no_directives() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          fragment Test on Dog {
+            name
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn unique_directives_in_different_locations() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          fragment Test on Dog @skip(if: true) {
+            name @include(if: true)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn unique_directives_in_same_locations() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          fragment Test on Dog @skip(if: true) @include(if: true) {
+            name @include(if: true) @skip(if: false)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn same_directives_in_different_locations() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          fragment Test on Dog @skip(if: true) {
+            name @skip(if: true)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn same_directives_in_similar_locations() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          fragment Test on Dog {
+            name @skip(if: true)
+            name @skip(if: true)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn duplicate_directives_in_one_location() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          fragment Test on Dog {
+            name @skip(if: true) @skip(if: false)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn many_duplicate_directives_in_one_location() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          fragment Test on Dog {
+            name @skip(if: true) @skip(if: false) @skip(if: true)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn different_duplicate_directives_in_one_location() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          fragment Test on Dog {
+            name @skip(if: true) @include(if: true) @skip(if: false) @include(if: false)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn duplicate_directives_in_many_locations() {
+        let doc = crate::parser::parse_query(
+            r#"query Q @skip(if: true) @skip(if: true) {
+  dog {
+    ...DogFields @include(if: true) @include(if: false)
+    ... on Dog @skip(if: true) @skip(if: false) {
+      name
+    }
+  }
+}
+fragment DogFields on Dog @skip(if: true) @skip(if: true) {
+  name
+}"#,
+        )
+        .unwrap();
+        match validate(&doc, factory) {
+            Err(Error::Rule { errors }) => {
+                let errors = errors
+                    .iter()
+                    .map(|err| (err.locations[0].line, err.locations[0].column))
+                    .collect::<Vec<_>>();
+                assert_eq!(errors, vec![(1, 25), (3, 37), (4, 32), (9, 43)]);
+            }
+            _ => panic!("Expected rule to fail"),
+        }
+    }
+}
@@ -16,7 +16,7 @@ impl<'a> Visitor<'a> for UniqueFragmentNames<'a> {
     ) {
         if !self.names.insert(&fragment_definition.name) {
             ctx.report_error(
-                vec![fragment_definition.position()],
+                vec![fragment_definition.span()],
                 format!(
                     "There can only be one fragment named \"{}\"",
                     fragment_definition.name
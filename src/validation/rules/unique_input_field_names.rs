@@ -0,0 +1,172 @@
+use crate::parser::query::Document;
+use crate::validation::visitor::{Visitor, VisitorContext};
+
+pub struct UniqueInputFieldNames;
+
+impl<'a> Visitor<'a> for UniqueInputFieldNames {
+    fn enter_document(&mut self, ctx: &mut VisitorContext<'a>, doc: &'a Document) {
+        for field in doc.duplicate_input_fields() {
+            ctx.report_error(
+                vec![field.first.span(), field.duplicate],
+                format!(
+                    "There can only be one input field named \"{}\".",
+                    field.first
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::test_harness::validate;
+    use crate::{expect_fails_rule, expect_passes_rule, Error};
+
+    pub fn factory() -> UniqueInputFieldNames {
+        UniqueInputFieldNames
+    }
+
+    fn expect_errors(query: &str, expected: Vec<(&str, Vec<(usize, usize)>)>) {
+        let doc = crate::parser::parse_query(query).unwrap();
+        match validate(&doc, factory) {
+            Err(Error::Rule { errors }) => {
+                let errors = errors
+                    .iter()
+                    .map(|err| {
+                        (
+                            err.message.as_str(),
+                            err.locations
+                                .iter()
+                                .map(|pos| (pos.line, pos.column))
+                                .collect::<Vec<_>>(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(errors, expected);
+            }
+            _ => panic!("Expected rule to fail"),
+        }
+    }
+
+    #[test]
+    fn input_object_with_fields() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          {
+            field(arg: { f: true })
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn same_input_object_within_two_args() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          {
+            field(arg1: { f: true }, arg2: { f: true })
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn multiple_input_object_fields() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          {
+            field(arg: { f1: "value", f2: "value", f3: "value" })
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn allows_for_nested_input_objects_with_similar_fields() {
+        expect_passes_rule!(
+            factory,
+            r#"
+          {
+            field(arg: {
+              deep: {
+                deep: {
+                  id: 1
+                }
+                id: 1
+              }
+              id: 1
+            })
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn duplicate_input_object_fields() {
+        expect_errors(
+            r#"
+          {
+            field(arg: { f1: "value", f1: "value" })
+          }
+        "#,
+            vec![(
+                "There can only be one input field named \"f1\".",
+                vec![(3, 26), (3, 39)],
+            )],
+        );
+    }
+
+    #[test]
+    fn many_duplicate_input_object_fields() {
+        expect_errors(
+            r#"
+          {
+            field(arg: { f1: "value", f1: "value", f1: "value" })
+          }
+        "#,
+            vec![
+                (
+                    "There can only be one input field named \"f1\".",
+                    vec![(3, 26), (3, 39)],
+                ),
+                (
+                    "There can only be one input field named \"f1\".",
+                    vec![(3, 26), (3, 52)],
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn nested_duplicate_input_object_fields() {
+        expect_errors(
+            r#"
+          {
+            field(arg: { f1: {f2: "value", f2: "value" }})
+          }
+        "#,
+            vec![(
+                "There can only be one input field named \"f2\".",
+                vec![(3, 31), (3, 44)],
+            )],
+        );
+    }
+
+    #[test]
+    fn duplicate_fields_in_variable_defaults_and_directives() {
+        expect_fails_rule!(
+            factory,
+            r#"
+          query Q($arg: TestInput = { id: 1, id: 2 }) {
+            dog @include(if: { value: true, value: true }) {
+              name
+            }
+          }
+        "#,
+        );
+    }
+}
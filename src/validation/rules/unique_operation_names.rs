@@ -33,7 +33,7 @@ impl<'a> Visitor<'a> for UniqueOperationNames<'a> {
         if let Some(name) = name {
             if !self.names.insert(name.as_str()) {
                 ctx.report_error(
-                    vec![name.position()],
+                    vec![name.span()],
                     format!("There can only be one operation named \"{}\"", name),
                 )
             }
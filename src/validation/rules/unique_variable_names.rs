@@ -24,7 +24,7 @@ impl<'a> Visitor<'a> for UniqueVariableNames<'a> {
     ) {
         if !self.names.insert(variable_definition.name.as_str()) {
             ctx.report_error(
-                vec![variable_definition.position()],
+                vec![variable_definition.span()],
                 format!(
                     "There can only be one variable named \"${}\"",
                     variable_definition.name
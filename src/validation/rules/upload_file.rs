@@ -16,7 +16,7 @@ impl<'a> Visitor<'a> for UploadFile {
                 if let Some(ty) = ctx.registry.concrete_type_by_parsed_type(&var.var_type) {
                     if ty.name() == "Upload" {
                         ctx.report_error(
-                            vec![var.position()],
+                            vec![var.span()],
                             "The Upload type is only allowed to be defined on a mutation",
                         );
                     }
@@ -27,7 +27,7 @@ impl<'a> Visitor<'a> for UploadFile {
                 if let Some(ty) = ctx.registry.concrete_type_by_parsed_type(&var.var_type) {
                     if ty.name() == "Upload" {
                         ctx.report_error(
-                            vec![var.position()],
+                            vec![var.span()],
                             "The Upload type is only allowed to be defined on a mutation",
                         );
                     }
@@ -17,7 +17,7 @@ impl<'a> Visitor<'a> for VariablesAreInputTypes {
         {
             if !ty.is_input() {
                 ctx.report_error(
-                    vec![variable_definition.position()],
+                    vec![variable_definition.span()],
                     format!(
                         "Variable \"{}\" cannot be of non-input type \"{}\"",
                         &variable_definition.name,
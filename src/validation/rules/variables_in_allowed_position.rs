@@ -4,13 +4,13 @@ use crate::parser::query::{
 use crate::registry::MetaTypeName;
 use crate::validation::utils::{operation_name, Scope};
 use crate::validation::visitor::{Visitor, VisitorContext};
-use crate::{Pos, Positioned, Value};
+use crate::{Positioned, Span, Value};
 use std::collections::{HashMap, HashSet};
 
 #[derive(Default)]
 pub struct VariableInAllowedPosition<'a> {
     spreads: HashMap<Scope<'a>, HashSet<&'a str>>,
-    variable_usages: HashMap<Scope<'a>, Vec<(&'a str, Pos, MetaTypeName<'a>)>>,
+    variable_usages: HashMap<Scope<'a>, Vec<(&'a str, Span, MetaTypeName<'a>)>>,
     variable_defs: HashMap<Scope<'a>, Vec<&'a Positioned<VariableDefinition>>>,
     current_scope: Option<Scope<'a>>,
 }
@@ -30,7 +30,7 @@ impl<'a> VariableInAllowedPosition<'a> {
         visited.insert(from.clone());
 
         if let Some(usages) = self.variable_usages.get(from) {
-            for (var_name, usage_pos, var_type) in usages {
+            for (var_name, usage_span, var_type) in usages {
                 if let Some(def) = var_defs.iter().find(|def| def.name.node == *var_name) {
                     let expected_type = match (&def.default_value, &def.var_type.node) {
                         (Some(_), Type::List(_)) => def.var_type.to_string() + "!",
@@ -40,7 +40,7 @@ impl<'a> VariableInAllowedPosition<'a> {
 
                     if !var_type.is_subtype(&MetaTypeName::create(&expected_type)) {
                         ctx.report_error(
-                            vec![def.position(), *usage_pos],
+                            vec![def.span(), *usage_span],
                             format!(
                             "Variable \"{}\" of type \"{}\" used in position expecting type \"{}\"",
                             var_name, var_type, expected_type
@@ -112,7 +112,7 @@ impl<'a> Visitor<'a> for VariableInAllowedPosition<'a> {
     fn enter_input_value(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
-        pos: Pos,
+        span: Span,
         expected_type: &Option<MetaTypeName<'a>>,
         value: &'a Value,
     ) {
@@ -122,7 +122,7 @@ impl<'a> Visitor<'a> for VariableInAllowedPosition<'a> {
                     self.variable_usages
                         .entry(scope.clone())
                         .or_insert_with(Vec::new)
-                        .push((name, pos, *expected_type));
+                        .push((name, span, *expected_type));
                 }
             }
         }
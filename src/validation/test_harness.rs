@@ -330,8 +330,21 @@ impl MutationRoot {
     }
 }
 
-static TEST_HARNESS: Lazy<Schema<QueryRoot, MutationRoot, EmptySubscription>> =
-    Lazy::new(|| Schema::new(QueryRoot, MutationRoot, EmptySubscription));
+pub struct SubscriptionRoot;
+
+#[Subscription(internal)]
+impl SubscriptionRoot {
+    async fn values(&self) -> impl futures::Stream<Item = i32> {
+        futures::stream::empty()
+    }
+
+    async fn events(&self) -> impl futures::Stream<Item = i32> {
+        futures::stream::empty()
+    }
+}
+
+static TEST_HARNESS: Lazy<Schema<QueryRoot, MutationRoot, SubscriptionRoot>> =
+    Lazy::new(|| Schema::new(QueryRoot, MutationRoot, SubscriptionRoot));
 
 pub fn validate<'a, V, F>(doc: &'a Document, factory: F) -> Result<()>
 where
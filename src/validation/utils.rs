@@ -1,6 +1,6 @@
 use crate::context::QueryPathNode;
 use crate::parser::query::OperationDefinition;
-use crate::{registry, Pos, QueryPathSegment, Value};
+use crate::{registry, QueryPathSegment, Span, Value};
 use std::collections::HashSet;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -34,19 +34,18 @@ fn referenced_variables_to_vec<'a>(value: &'a Value, vars: &mut Vec<&'a str>) {
     }
 }
 
-pub fn operation_name(operation_definition: &OperationDefinition) -> (Option<&str>, Pos) {
+pub fn operation_name(operation_definition: &OperationDefinition) -> (Option<&str>, Span) {
     match operation_definition {
-        OperationDefinition::SelectionSet(selection_set) => (None, selection_set.position()),
+        OperationDefinition::SelectionSet(selection_set) => (None, selection_set.span()),
         OperationDefinition::Query(query) => {
-            (query.name.as_ref().map(|n| n.as_str()), query.position())
+            (query.name.as_ref().map(|n| n.as_str()), query.span())
+        }
+        OperationDefinition::Mutation(mutation) => {
+            (mutation.name.as_ref().map(|n| n.as_str()), mutation.span())
         }
-        OperationDefinition::Mutation(mutation) => (
-            mutation.name.as_ref().map(|n| n.as_str()),
-            mutation.position(),
-        ),
         OperationDefinition::Subscription(subscription) => (
             subscription.name.as_ref().map(|n| n.as_str()),
-            subscription.position(),
+            subscription.span(),
         ),
     }
 }
@@ -4,19 +4,23 @@ use crate::parser::query::{
     OperationDefinition, Selection, SelectionSet, TypeCondition, VariableDefinition,
 };
 use crate::registry::{self, MetaType, MetaTypeName};
-use crate::{Pos, Positioned, Value};
+use crate::{Positioned, Span, Value};
 use std::collections::HashMap;
 
+/// The state of a document traversal, passed to each method of a `Visitor`.
+///
+/// It gives access to the type system and the fragments of the document, and collects the
+/// errors reported by the validation rules.
 pub struct VisitorContext<'a> {
-    pub registry: &'a registry::Registry,
-    pub errors: Vec<RuleError>,
+    pub(crate) registry: &'a registry::Registry,
+    pub(crate) errors: Vec<RuleError>,
     type_stack: Vec<Option<&'a registry::MetaType>>,
     input_type: Vec<Option<MetaTypeName<'a>>>,
     fragments: HashMap<&'a str, &'a Positioned<FragmentDefinition>>,
 }
 
 impl<'a> VisitorContext<'a> {
-    pub fn new(registry: &'a registry::Registry, doc: &'a Document) -> Self {
+    pub(crate) fn new(registry: &'a registry::Registry, doc: &'a Document) -> Self {
         Self {
             registry,
             errors: Default::default(),
@@ -33,18 +37,21 @@ impl<'a> VisitorContext<'a> {
         }
     }
 
-    pub fn report_error<T: Into<String>>(&mut self, locations: Vec<Pos>, msg: T) {
+    /// Report a validation error at the spans of the nodes which caused it, the document is
+    /// rejected once the traversal is finished.
+    pub fn report_error<T: Into<String>>(&mut self, spans: Vec<Span>, msg: T) {
         self.errors.push(RuleError {
-            locations,
+            locations: spans.iter().map(|span| span.start).collect(),
+            spans,
             message: msg.into(),
         })
     }
 
-    pub fn append_errors(&mut self, errors: Vec<RuleError>) {
+    pub(crate) fn append_errors(&mut self, errors: Vec<RuleError>) {
         self.errors.extend(errors);
     }
 
-    pub fn with_type<F: FnMut(&mut VisitorContext<'a>)>(
+    pub(crate) fn with_type<F: FnMut(&mut VisitorContext<'a>)>(
         &mut self,
         ty: Option<&'a registry::MetaType>,
         mut f: F,
@@ -54,7 +61,7 @@ impl<'a> VisitorContext<'a> {
         self.type_stack.pop();
     }
 
-    pub fn with_input_type<F: FnMut(&mut VisitorContext<'a>)>(
+    pub(crate) fn with_input_type<F: FnMut(&mut VisitorContext<'a>)>(
         &mut self,
         ty: Option<MetaTypeName<'a>>,
         mut f: F,
@@ -64,7 +71,7 @@ impl<'a> VisitorContext<'a> {
         self.input_type.pop();
     }
 
-    pub fn parent_type(&self) -> Option<&'a registry::MetaType> {
+    pub(crate) fn parent_type(&self) -> Option<&'a registry::MetaType> {
         if self.type_stack.len() >= 2 {
             self.type_stack
                 .get(self.type_stack.len() - 2)
@@ -75,29 +82,65 @@ impl<'a> VisitorContext<'a> {
         }
     }
 
-    pub fn current_type(&self) -> Option<&'a registry::MetaType> {
+    pub(crate) fn current_type(&self) -> Option<&'a registry::MetaType> {
         self.type_stack.last().copied().flatten()
     }
 
+    /// Returns the name of the type of the parent of the current node, that is the type on which
+    /// the current field is defined, `None` if it is unknown.
+    pub fn parent_type_name(&self) -> Option<&'a str> {
+        self.parent_type().map(MetaType::name)
+    }
+
+    /// Returns the name of the type of the current node, that is the type of the current field or
+    /// the type condition of the current fragment, `None` if it is unknown.
+    pub fn current_type_name(&self) -> Option<&'a str> {
+        self.current_type().map(MetaType::name)
+    }
+
+    /// Returns the deprecation reason of a field of an object or interface type, `None` if the
+    /// field is not deprecated or unknown.
+    pub fn field_deprecation(&self, type_name: &str, field_name: &str) -> Option<&'a str> {
+        self.registry
+            .types
+            .get(type_name)
+            .and_then(|ty| ty.field_by_name(field_name))
+            .and_then(|field| field.deprecation)
+    }
+
+    /// Returns `true` if the document defines a fragment with this name.
     pub fn is_known_fragment(&self, name: &str) -> bool {
         self.fragments.contains_key(name)
     }
 
+    /// Returns the definition of a fragment of the document.
     pub fn fragment(&self, name: &str) -> Option<&'a Positioned<FragmentDefinition>> {
         self.fragments.get(name).copied()
     }
 }
 
+/// A visitor of the nodes of a query document, used to implement the validation rules.
+///
+/// The document is traversed depth-first in source order, the `enter_*` method of each node is
+/// called before its children and the matching `exit_*` method after them. All the methods do
+/// nothing by default, so a rule only implements the ones for the nodes it checks.
+///
+/// The fragment spreads are not followed, the fragment definitions are visited where they appear
+/// in the document, use `VisitorContext::fragment` to look them up.
 pub trait Visitor<'a> {
+    /// Called at the start of the document.
     fn enter_document(&mut self, _ctx: &mut VisitorContext<'a>, _doc: &'a Document) {}
+    /// Called at the end of the document.
     fn exit_document(&mut self, _ctx: &mut VisitorContext<'a>, _doc: &'a Document) {}
 
+    /// Called for each query, mutation or subscription.
     fn enter_operation_definition(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
         _operation_definition: &'a Positioned<OperationDefinition>,
     ) {
     }
+    /// Called after the children of the node, see `enter_operation_definition`.
     fn exit_operation_definition(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
@@ -105,12 +148,14 @@ pub trait Visitor<'a> {
     ) {
     }
 
+    /// Called for each fragment definition.
     fn enter_fragment_definition(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
         _fragment_definition: &'a Positioned<FragmentDefinition>,
     ) {
     }
+    /// Called after the children of the node, see `enter_fragment_definition`.
     fn exit_fragment_definition(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
@@ -118,12 +163,14 @@ pub trait Visitor<'a> {
     ) {
     }
 
+    /// Called for each variable definition of an operation.
     fn enter_variable_definition(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
         _variable_definition: &'a Positioned<VariableDefinition>,
     ) {
     }
+    /// Called after the children of the node, see `enter_variable_definition`.
     fn exit_variable_definition(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
@@ -131,12 +178,14 @@ pub trait Visitor<'a> {
     ) {
     }
 
+    /// Called for each directive, after the arguments of the node it is applied to.
     fn enter_directive(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
         _directive: &'a Positioned<Directive>,
     ) {
     }
+    /// Called after the children of the node, see `enter_directive`.
     fn exit_directive(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
@@ -144,6 +193,7 @@ pub trait Visitor<'a> {
     ) {
     }
 
+    /// Called for each argument of a field or a directive.
     fn enter_argument(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
@@ -151,6 +201,7 @@ pub trait Visitor<'a> {
         _value: &'a Positioned<Value>,
     ) {
     }
+    /// Called after the children of the node, see `enter_argument`.
     fn exit_argument(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
@@ -159,12 +210,15 @@ pub trait Visitor<'a> {
     ) {
     }
 
+    /// Called for each selection set, `VisitorContext::current_type_name` is the name of the type
+    /// the selections apply to.
     fn enter_selection_set(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
         _selection_set: &'a Positioned<SelectionSet>,
     ) {
     }
+    /// Called after the children of the node, see `enter_selection_set`.
     fn exit_selection_set(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
@@ -172,12 +226,14 @@ pub trait Visitor<'a> {
     ) {
     }
 
+    /// Called for each item of a selection set, before the field or fragment it contains.
     fn enter_selection(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
         _selection: &'a Positioned<Selection>,
     ) {
     }
+    /// Called after the children of the node, see `enter_selection`.
     fn exit_selection(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
@@ -185,15 +241,20 @@ pub trait Visitor<'a> {
     ) {
     }
 
+    /// Called for each field except `__typename`, `VisitorContext::parent_type_name` is the name
+    /// of the type which defines it.
     fn enter_field(&mut self, _ctx: &mut VisitorContext<'a>, _field: &'a Positioned<Field>) {}
+    /// Called after the children of the node, see `enter_field`.
     fn exit_field(&mut self, _ctx: &mut VisitorContext<'a>, _field: &'a Positioned<Field>) {}
 
+    /// Called for each fragment spread.
     fn enter_fragment_spread(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
         _fragment_spread: &'a Positioned<FragmentSpread>,
     ) {
     }
+    /// Called after the children of the node, see `enter_fragment_spread`.
     fn exit_fragment_spread(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
@@ -201,12 +262,14 @@ pub trait Visitor<'a> {
     ) {
     }
 
+    /// Called for each inline fragment.
     fn enter_inline_fragment(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
         _inline_fragment: &'a Positioned<InlineFragment>,
     ) {
     }
+    /// Called after the children of the node, see `enter_inline_fragment`.
     fn exit_inline_fragment(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
@@ -214,18 +277,21 @@ pub trait Visitor<'a> {
     ) {
     }
 
+    /// Called for the value of each field argument, and recursively for the items of the lists
+    /// and the fields of the objects, with the input type expected there.
     fn enter_input_value(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
-        _pos: Pos,
+        _span: Span,
         _expected_type: &Option<MetaTypeName<'a>>,
         _value: &'a Value,
     ) {
     }
+    /// Called after the children of the node, see `enter_input_value`.
     fn exit_input_value(
         &mut self,
         _ctx: &mut VisitorContext<'a>,
-        _pos: Pos,
+        _span: Span,
         _expected_type: &Option<MetaTypeName<'a>>,
         _value: &Value,
     ) {
@@ -440,13 +506,17 @@ where
     }
 }
 
-pub fn visit<'a, V: Visitor<'a>>(v: &mut V, ctx: &mut VisitorContext<'a>, doc: &'a Document) {
+pub fn visit<'a, V: Visitor<'a> + ?Sized>(
+    v: &mut V,
+    ctx: &mut VisitorContext<'a>,
+    doc: &'a Document,
+) {
     v.enter_document(ctx, doc);
     visit_definitions(v, ctx, doc);
     v.exit_document(ctx, doc);
 }
 
-fn visit_definitions<'a, V: Visitor<'a>>(
+fn visit_definitions<'a, V: Visitor<'a> + ?Sized>(
     v: &mut V,
     ctx: &mut VisitorContext<'a>,
     doc: &'a Document,
@@ -466,7 +536,7 @@ fn visit_definitions<'a, V: Visitor<'a>>(
     }
 }
 
-fn visit_operation_definition<'a, V: Visitor<'a>>(
+fn visit_operation_definition<'a, V: Visitor<'a> + ?Sized>(
     v: &mut V,
     ctx: &mut VisitorContext<'a>,
     operation: &'a Positioned<OperationDefinition>,
@@ -494,7 +564,7 @@ fn visit_operation_definition<'a, V: Visitor<'a>>(
                 });
             } else {
                 ctx.report_error(
-                    vec![mutation.position()],
+                    vec![mutation.span()],
                     "Schema is not configured for mutations.",
                 );
             }
@@ -508,7 +578,7 @@ fn visit_operation_definition<'a, V: Visitor<'a>>(
                 });
             } else {
                 ctx.report_error(
-                    vec![subscription.position()],
+                    vec![subscription.span()],
                     "Schema is not configured for subscriptions.",
                 );
             }
@@ -517,7 +587,7 @@ fn visit_operation_definition<'a, V: Visitor<'a>>(
     v.exit_operation_definition(ctx, operation);
 }
 
-fn visit_selection_set<'a, V: Visitor<'a>>(
+fn visit_selection_set<'a, V: Visitor<'a> + ?Sized>(
     v: &mut V,
     ctx: &mut VisitorContext<'a>,
     selection_set: &'a Positioned<SelectionSet>,
@@ -531,7 +601,7 @@ fn visit_selection_set<'a, V: Visitor<'a>>(
     }
 }
 
-fn visit_selection<'a, V: Visitor<'a>>(
+fn visit_selection<'a, V: Visitor<'a> + ?Sized>(
     v: &mut V,
     ctx: &mut VisitorContext<'a>,
     selection: &'a Positioned<Selection>,
@@ -568,7 +638,7 @@ fn visit_selection<'a, V: Visitor<'a>>(
     v.exit_selection(ctx, selection);
 }
 
-fn visit_field<'a, V: Visitor<'a>>(
+fn visit_field<'a, V: Visitor<'a> + ?Sized>(
     v: &mut V,
     ctx: &mut VisitorContext<'a>,
     field: &'a Positioned<Field>,
@@ -583,7 +653,7 @@ fn visit_field<'a, V: Visitor<'a>>(
             .and_then(|schema_field| schema_field.args.get(name.as_str()))
             .map(|input_ty| MetaTypeName::create(&input_ty.ty));
         ctx.with_input_type(expected_ty, |ctx| {
-            visit_input_value(v, ctx, field.position(), expected_ty, value)
+            visit_input_value(v, ctx, field.span(), expected_ty, value)
         });
         v.exit_argument(ctx, name, value);
     }
@@ -593,14 +663,14 @@ fn visit_field<'a, V: Visitor<'a>>(
     v.exit_field(ctx, field);
 }
 
-fn visit_input_value<'a, V: Visitor<'a>>(
+fn visit_input_value<'a, V: Visitor<'a> + ?Sized>(
     v: &mut V,
     ctx: &mut VisitorContext<'a>,
-    pos: Pos,
+    span: Span,
     expected_ty: Option<MetaTypeName<'a>>,
     value: &'a Value,
 ) {
-    v.enter_input_value(ctx, pos, &expected_ty, value);
+    v.enter_input_value(ctx, span, &expected_ty, value);
 
     match value {
         Value::List(values) => {
@@ -611,7 +681,7 @@ fn visit_input_value<'a, V: Visitor<'a>>(
                         visit_input_value(
                             v,
                             ctx,
-                            pos,
+                            span,
                             Some(MetaTypeName::create(expected_ty)),
                             value,
                         )
@@ -634,7 +704,7 @@ fn visit_input_value<'a, V: Visitor<'a>>(
                                     visit_input_value(
                                         v,
                                         ctx,
-                                        pos,
+                                        span,
                                         Some(MetaTypeName::create(&input_value.ty)),
                                         item_value,
                                     );
@@ -648,10 +718,10 @@ fn visit_input_value<'a, V: Visitor<'a>>(
         _ => {}
     }
 
-    v.exit_input_value(ctx, pos, &expected_ty, value);
+    v.exit_input_value(ctx, span, &expected_ty, value);
 }
 
-fn visit_variable_definitions<'a, V: Visitor<'a>>(
+fn visit_variable_definitions<'a, V: Visitor<'a> + ?Sized>(
     v: &mut V,
     ctx: &mut VisitorContext<'a>,
     variable_definitions: &'a [Positioned<VariableDefinition>],
@@ -662,7 +732,7 @@ fn visit_variable_definitions<'a, V: Visitor<'a>>(
     }
 }
 
-fn visit_directives<'a, V: Visitor<'a>>(
+fn visit_directives<'a, V: Visitor<'a> + ?Sized>(
     v: &mut V,
     ctx: &mut VisitorContext<'a>,
     directives: &'a [Positioned<Directive>],
@@ -678,7 +748,7 @@ fn visit_directives<'a, V: Visitor<'a>>(
                 .and_then(|schema_directive| schema_directive.args.get(name.as_str()))
                 .map(|input_ty| MetaTypeName::create(&input_ty.ty));
             ctx.with_input_type(expected_ty, |ctx| {
-                visit_input_value(v, ctx, d.position(), expected_ty, value)
+                visit_input_value(v, ctx, d.span(), expected_ty, value)
             });
             v.exit_argument(ctx, name, value);
         }
@@ -687,7 +757,7 @@ fn visit_directives<'a, V: Visitor<'a>>(
     }
 }
 
-fn visit_fragment_definition<'a, V: Visitor<'a>>(
+fn visit_fragment_definition<'a, V: Visitor<'a> + ?Sized>(
     v: &mut V,
     ctx: &mut VisitorContext<'a>,
     fragment: &'a Positioned<FragmentDefinition>,
@@ -698,7 +768,7 @@ fn visit_fragment_definition<'a, V: Visitor<'a>>(
     v.exit_fragment_definition(ctx, fragment);
 }
 
-fn visit_fragment_spread<'a, V: Visitor<'a>>(
+fn visit_fragment_spread<'a, V: Visitor<'a> + ?Sized>(
     v: &mut V,
     ctx: &mut VisitorContext<'a>,
     fragment_spread: &'a Positioned<FragmentSpread>,
@@ -708,7 +778,7 @@ fn visit_fragment_spread<'a, V: Visitor<'a>>(
     v.exit_fragment_spread(ctx, fragment_spread);
 }
 
-fn visit_inline_fragment<'a, V: Visitor<'a>>(
+fn visit_inline_fragment<'a, V: Visitor<'a> + ?Sized>(
     v: &mut V,
     ctx: &mut VisitorContext<'a>,
     inline_fragment: &'a Positioned<InlineFragment>,
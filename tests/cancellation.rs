@@ -0,0 +1,127 @@
+use async_graphql::*;
+use async_std::task;
+use futures::StreamExt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+struct Stopped(Arc<AtomicBool>);
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn fast(&self) -> i32 {
+        1
+    }
+
+    async fn slow(&self, ctx: &Context<'_>) -> i32 {
+        let token = ctx.cancellation_token().clone();
+        let stopped = ctx.data::<Stopped>().0.clone();
+        // A background task that stops when the query is cancelled.
+        task::spawn(async move {
+            token.cancelled().await;
+            stopped.store(true, Ordering::SeqCst);
+        });
+        task::sleep(Duration::from_millis(300)).await;
+        2
+    }
+
+    async fn check(&self, ctx: &Context<'_>) -> bool {
+        ctx.is_cancelled()
+    }
+
+    async fn deferred(&self) -> Deferred<SlowObj> {
+        SlowObj.into()
+    }
+}
+
+struct SlowObj;
+
+#[Object]
+impl SlowObj {
+    async fn value(&self) -> i32 {
+        task::sleep(Duration::from_millis(300)).await;
+        4
+    }
+}
+
+fn schema() -> Schema<QueryRoot, EmptyMutation, EmptySubscription> {
+    Schema::new(QueryRoot, EmptyMutation, EmptySubscription)
+}
+
+#[async_std::test]
+pub async fn test_cancel_query() {
+    let token = CancellationToken::new();
+    let stopped = Arc::new(AtomicBool::new(false));
+    let query = task::spawn({
+        let builder = QueryBuilder::new("{ fast slow }")
+            .data(Stopped(stopped.clone()))
+            .cancellation_token(token.clone());
+        async move { builder.execute(&schema()).await }
+    });
+    task::sleep(Duration::from_millis(50)).await;
+    token.cancel();
+    assert_eq!(
+        query.await.unwrap_err().to_string(),
+        "Query error: Query cancelled"
+    );
+    task::sleep(Duration::from_millis(50)).await;
+    assert!(stopped.load(Ordering::SeqCst));
+
+    // Cancelled before execution.
+    assert_eq!(
+        QueryBuilder::new("{ fast }")
+            .cancellation_token(token)
+            .execute(&schema())
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Query error: Query cancelled"
+    );
+}
+
+#[async_std::test]
+pub async fn test_drop_query() {
+    let token = CancellationToken::new();
+    let stopped = Arc::new(AtomicBool::new(false));
+    let schema = schema();
+    let query = QueryBuilder::new("{ fast slow }")
+        .data(Stopped(stopped.clone()))
+        .cancellation_token(token.clone())
+        .execute(&schema);
+    assert!(async_std::future::timeout(Duration::from_millis(50), query)
+        .await
+        .is_err());
+    task::sleep(Duration::from_millis(50)).await;
+    assert!(stopped.load(Ordering::SeqCst));
+
+    // The shared token is not cancelled.
+    assert!(!token.is_cancelled());
+    let resp = QueryBuilder::new("{ fast check }")
+        .cancellation_token(token)
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(resp.data, serde_json::json!({ "fast": 1, "check": false }));
+}
+
+#[async_std::test]
+pub async fn test_cancel_deferred() {
+    let token = CancellationToken::new();
+    let mut stream = QueryBuilder::new("{ fast deferred @defer { value } }")
+        .cancellation_token(token.clone())
+        .execute_stream(&schema())
+        .await
+        .into_stream();
+    assert_eq!(
+        stream.next().await.unwrap().unwrap().data,
+        serde_json::json!({ "fast": 1, "deferred": null })
+    );
+    token.cancel();
+    assert_eq!(
+        stream.next().await.unwrap().unwrap_err().to_string(),
+        "Query error: Query cancelled"
+    );
+    assert!(stream.next().await.is_none());
+}
@@ -0,0 +1,113 @@
+use async_graphql::extensions::Extension;
+use async_graphql::*;
+use std::sync::{Arc, Mutex};
+
+struct Obj;
+
+#[Object]
+impl Obj {
+    async fn ok(&self) -> i32 {
+        1
+    }
+
+    async fn fail(&self) -> i32 {
+        panic!("oops {}", 1)
+    }
+}
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn value(&self) -> i32 {
+        10
+    }
+
+    async fn obj(&self) -> Obj {
+        Obj
+    }
+
+    async fn fail(&self) -> i32 {
+        panic!("query failed")
+    }
+}
+
+struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn action(&self) -> bool {
+        panic!("mutation failed")
+    }
+}
+
+struct ErrorRecorder(Arc<Mutex<Vec<String>>>);
+
+impl Extension for ErrorRecorder {
+    fn error(&mut self, err: &Error) {
+        self.0.lock().unwrap().push(err.to_string());
+    }
+}
+
+#[async_std::test]
+pub async fn test_catch_panics() {
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .catch_panics()
+        .extension({
+            let errors = errors.clone();
+            move || ErrorRecorder(errors.clone())
+        })
+        .finish();
+
+    assert_eq!(
+        schema.execute("{ value obj { ok } }").await.unwrap().data,
+        serde_json::json!({ "value": 10, "obj": { "ok": 1 } })
+    );
+
+    let err = schema
+        .execute("{ value obj { ok fail } }")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Error::Query {
+            pos: Pos {
+                line: 1,
+                column: 18
+            },
+            path: Some(serde_json::json!(["obj", "fail"])),
+            err: QueryError::ResolverPanicked {
+                message: "oops 1".to_string()
+            },
+        }
+    );
+
+    errors.lock().unwrap().clear();
+    let err = schema.execute("{ fail }").await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Query error: Field resolver panicked: query failed"
+    );
+
+    let err = schema.execute("mutation { action }").await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Query error: Field resolver panicked: mutation failed"
+    );
+
+    assert_eq!(
+        errors.lock().unwrap().as_slice(),
+        &[
+            "Query error: Field resolver panicked: query failed",
+            "Query error: Field resolver panicked: mutation failed",
+        ]
+    );
+}
+
+#[async_std::test]
+#[should_panic(expected = "query failed")]
+pub async fn test_panics_not_caught() {
+    let schema = Schema::new(QueryRoot, MutationRoot, EmptySubscription);
+    let _ = schema.execute("{ fail }").await;
+}
@@ -0,0 +1,155 @@
+use async_graphql::*;
+use async_std::task;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Default)]
+struct Counter {
+    active: AtomicUsize,
+    max_active: AtomicUsize,
+}
+
+struct Item(i32);
+
+#[Object]
+impl Item {
+    async fn value(&self, ctx: &Context<'_>) -> i32 {
+        let counter = ctx.data::<Arc<Counter>>();
+        let active = counter.active.fetch_add(1, Ordering::SeqCst) + 1;
+        counter.max_active.fetch_max(active, Ordering::SeqCst);
+        // Finish in the reverse order of starting.
+        task::sleep(Duration::from_millis(50 - self.0 as u64 * 5)).await;
+        counter.active.fetch_sub(1, Ordering::SeqCst);
+        self.0
+    }
+}
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn items(&self) -> Vec<Item> {
+        (0..8).map(Item).collect()
+    }
+}
+
+async fn run(
+    schema: &Schema<QueryRoot, EmptyMutation, EmptySubscription>,
+    limit: Option<usize>,
+) -> usize {
+    let counter = Arc::new(Counter::default());
+    let mut builder = QueryBuilder::new("{ items { value } }").data(counter.clone());
+    if let Some(limit) = limit {
+        builder = builder.limit_concurrency(limit);
+    }
+    let data = builder.execute(schema).await.unwrap().data;
+    assert_eq!(
+        data,
+        serde_json::json!({
+            "items": (0..8).map(|n| serde_json::json!({ "value": n })).collect::<Vec<_>>()
+        })
+    );
+    counter.max_active.load(Ordering::SeqCst)
+}
+
+#[async_std::test]
+pub async fn test_limit_concurrency() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    assert_eq!(run(&schema, None).await, 8);
+    assert_eq!(run(&schema, Some(3)).await, 3);
+
+    let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_concurrency(2)
+        .finish();
+    assert_eq!(run(&schema, None).await, 2);
+    assert_eq!(run(&schema, Some(1)).await, 1);
+}
+
+impl Counter {
+    async fn track<T>(&self, value: T) -> T {
+        let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_active.fetch_max(active, Ordering::SeqCst);
+        task::sleep(Duration::from_millis(10)).await;
+        self.active.fetch_sub(1, Ordering::SeqCst);
+        value
+    }
+}
+
+struct Leaf(i32);
+
+#[Object]
+impl Leaf {
+    async fn a(&self, ctx: &Context<'_>) -> i32 {
+        ctx.data::<Arc<Counter>>().track(self.0).await
+    }
+
+    async fn b(&self, ctx: &Context<'_>) -> i32 {
+        ctx.data::<Arc<Counter>>().track(self.0 * 10).await
+    }
+
+    async fn c(&self, ctx: &Context<'_>) -> i32 {
+        ctx.data::<Arc<Counter>>().track(self.0 * 100).await
+    }
+}
+
+struct Group(i32);
+
+#[Object]
+impl Group {
+    async fn id(&self, ctx: &Context<'_>) -> i32 {
+        ctx.data::<Arc<Counter>>().track(self.0).await
+    }
+
+    async fn leaves(&self, ctx: &Context<'_>) -> Vec<Leaf> {
+        ctx.data::<Arc<Counter>>()
+            .track((0..4).map(|n| Leaf(self.0 * 4 + n)).collect())
+            .await
+    }
+}
+
+struct NestedRoot;
+
+#[Object]
+impl NestedRoot {
+    async fn groups(&self, ctx: &Context<'_>) -> Vec<Group> {
+        ctx.data::<Arc<Counter>>()
+            .track((0..4).map(Group).collect())
+            .await
+    }
+}
+
+#[async_std::test]
+pub async fn test_limit_concurrency_nested() {
+    let expected = serde_json::json!({
+        "groups": (0..4).map(|g| serde_json::json!({
+            "id": g,
+            "leaves": (0..4).map(|n| {
+                let n = g * 4 + n;
+                serde_json::json!({ "a": n, "b": n * 10, "c": n * 100 })
+            }).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>()
+    });
+
+    for limit in &[None, Some(3), Some(1)] {
+        let mut builder = Schema::build(NestedRoot, EmptyMutation, EmptySubscription);
+        if let Some(limit) = limit {
+            builder = builder.limit_concurrency(*limit);
+        }
+        let schema = builder.finish();
+        let counter = Arc::new(Counter::default());
+        let data = QueryBuilder::new("{ groups { id leaves { a b c } } }")
+            .data(counter.clone())
+            .execute(&schema)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(data, expected);
+        let max_active = counter.max_active.load(Ordering::SeqCst);
+        match limit {
+            // All the leaf fields run at the same time.
+            None => assert!(max_active >= 48, "max_active: {}", max_active),
+            Some(limit) => assert_eq!(max_active, *limit),
+        }
+    }
+}
@@ -150,3 +150,115 @@ pub async fn test_stream() {
 
     assert!(stream.next().await.is_none());
 }
+
+#[async_std::test]
+pub async fn test_defer_error_path() {
+    struct MyObj;
+
+    #[Object]
+    impl MyObj {
+        async fn value(&self) -> FieldResult<i32> {
+            Err("failed".into())
+        }
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn obj(&self) -> Deferred<MyObj> {
+            MyObj.into()
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let mut stream = schema
+        .execute_stream("{ obj @defer { value } }")
+        .await
+        .into_stream();
+    assert_eq!(
+        stream.next().await.unwrap().unwrap().data,
+        serde_json::json!({ "obj": null })
+    );
+    match stream.next().await.unwrap() {
+        Err(Error::Incremental { label, path, err }) => {
+            assert_eq!(label.as_deref(), Some("obj"));
+            assert_eq!(path, vec![serde_json::json!("obj")]);
+            match *err {
+                Error::Query { path, .. } => {
+                    assert_eq!(path, Some(serde_json::json!(["obj", "value"])))
+                }
+                _ => panic!("expected a query error"),
+            }
+        }
+        _ => panic!("expected an incremental error"),
+    }
+}
+
+#[async_std::test]
+pub async fn test_defer_nested_error_path() {
+    struct Inner;
+
+    #[Object]
+    impl Inner {
+        async fn value(&self) -> FieldResult<i32> {
+            Err("failed".into())
+        }
+    }
+
+    struct MyObj;
+
+    #[Object]
+    impl MyObj {
+        async fn inner(&self) -> Inner {
+            Inner
+        }
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn obj(&self) -> Deferred<MyObj> {
+            MyObj.into()
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let stream = schema
+        .execute_stream("{ obj @defer { inner { value } } }")
+        .await
+        .into_stream();
+    let data = String::from_utf8(
+        async_graphql::http::multipart_stream(stream)
+            .collect::<Vec<_>>()
+            .await
+            .concat(),
+    )
+    .unwrap();
+    let payloads = data
+        .split("\r\n---\r\n")
+        .skip(1)
+        .map(|part| {
+            let body = part.split("\r\n\r\n").nth(1).unwrap();
+            serde_json::from_str::<serde_json::Value>(body.trim_end_matches("\r\n-----\r\n"))
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        payloads[1],
+        serde_json::json!({
+            "incremental": [{
+                "data": null,
+                "path": [],
+                "label": "obj",
+                "errors": [{
+                    "message": "failed",
+                    "locations": [{"line": 1, "column": 24}],
+                    "path": ["obj", "inner", "value"]
+                }]
+            }],
+            "hasNext": true
+        })
+    );
+}
@@ -0,0 +1,241 @@
+use async_graphql::extensions::{Extension, ResolveInfo};
+use async_graphql::parser::query::{Document, OperationType};
+use async_graphql::*;
+use itertools::Itertools;
+use std::sync::{Arc, Mutex};
+
+struct Token(String);
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn value(&self, n: i32) -> i32 {
+        n
+    }
+
+    async fn token(&self, ctx: &Context<'_>) -> String {
+        ctx.data::<Token>().0.clone()
+    }
+}
+
+struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn action(&self) -> bool {
+        true
+    }
+}
+
+fn forbidden(message: &str) -> Error {
+    Error::Query {
+        pos: Pos::default(),
+        path: None,
+        err: QueryError::FieldError {
+            err: message.to_string(),
+            extended_error: None,
+        },
+    }
+}
+
+struct Auth;
+
+#[async_trait::async_trait]
+impl Extension for Auth {
+    async fn prepare_request(&mut self, mut request: QueryBuilder) -> Result<QueryBuilder> {
+        let token = match request.data_mut().get::<String>() {
+            Some(token) => token.clone(),
+            None => return Err(forbidden("Unauthorized")),
+        };
+        Ok(request.data(Token(token)))
+    }
+}
+
+struct ReadOnly;
+
+#[async_trait::async_trait]
+impl Extension for ReadOnly {
+    async fn before_execute(
+        &mut self,
+        document: &Document,
+        variables: &mut Variables,
+        _data: &mut Data,
+    ) -> Result<()> {
+        if document.current_operation().ty == OperationType::Mutation {
+            return Err(forbidden("Read only"));
+        }
+        variables.insert("n".to_string(), Value::Int(100));
+        Ok(())
+    }
+}
+
+struct Rewrite;
+
+#[async_trait::async_trait]
+impl Extension for Rewrite {
+    async fn after_execute(&mut self, result: Result<QueryResponse>) -> Result<QueryResponse> {
+        result.map(|mut resp| {
+            resp.data = serde_json::json!({ "wrapped": resp.data });
+            resp
+        })
+    }
+}
+
+#[async_std::test]
+pub async fn test_prepare_request() {
+    let schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .extension(|| Auth)
+        .finish();
+
+    let resp = QueryBuilder::new("{ token }")
+        .data("abc".to_string())
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(resp.data, serde_json::json!({ "token": "abc" }));
+
+    let err = QueryBuilder::new("{ token }")
+        .execute(&schema)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Query error: Failed to resolve field: Unauthorized"
+    );
+}
+
+#[async_std::test]
+pub async fn test_prepare_request_settings() {
+    struct Configure(CancellationToken);
+
+    #[async_trait::async_trait]
+    impl Extension for Configure {
+        async fn prepare_request(&mut self, request: QueryBuilder) -> Result<QueryBuilder> {
+            if request.query_source().contains("cancel") {
+                Ok(request.cancellation_token(self.0.clone()))
+            } else {
+                Ok(request.extension(|| Rewrite))
+            }
+        }
+    }
+
+    let token = CancellationToken::new();
+    token.cancel();
+    let schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .extension(move || Configure(token.clone()))
+        .finish();
+
+    let resp = schema.execute("{ value(n: 1) }").await.unwrap();
+    assert_eq!(resp.data, serde_json::json!({ "wrapped": { "value": 1 } }));
+
+    let err = schema.execute("{ cancel: value(n: 1) }").await.unwrap_err();
+    assert_eq!(err.to_string(), "Query error: Query cancelled");
+}
+
+#[async_std::test]
+pub async fn test_before_execute() {
+    let schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .extension(|| ReadOnly)
+        .finish();
+
+    let resp = QueryBuilder::new("query ($n: Int!) { value(n: $n) }")
+        .variables(Variables::parse_from_json(serde_json::json!({ "n": 1 })).unwrap())
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(resp.data, serde_json::json!({ "value": 100 }));
+
+    let err = schema.execute("mutation { action }").await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Query error: Failed to resolve field: Read only"
+    );
+}
+
+#[async_std::test]
+pub async fn test_after_execute() {
+    let schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .extension(|| Rewrite)
+        .finish();
+
+    let resp = schema.execute("{ value(n: 1) }").await.unwrap();
+    assert_eq!(resp.data, serde_json::json!({ "wrapped": { "value": 1 } }));
+
+    assert!(schema.execute("{ value }").await.is_err());
+}
+
+#[async_std::test]
+pub async fn test_resolve_info() {
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    struct Audit(Log);
+
+    impl Extension for Audit {
+        fn resolve_end(
+            &mut self,
+            info: &ResolveInfo<'_>,
+            result: std::result::Result<&serde_json::Value, &Error>,
+        ) {
+            let arguments = info
+                .arguments()
+                .unwrap()
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .join(",");
+            let directives = info
+                .directives
+                .iter()
+                .map(|directive| directive.name.as_str())
+                .join(",");
+            self.0.lock().unwrap().push(format!(
+                "{} alias={:?} args=[{}] directives=[{}] {}",
+                info.path_node,
+                info.alias,
+                arguments,
+                directives,
+                match result {
+                    Ok(value) => format!("ok={}", value),
+                    Err(err) => format!("err={}", err),
+                }
+            ));
+        }
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self, n: i32) -> FieldResult<i32> {
+            if n < 0 {
+                Err("negative".into())
+            } else {
+                Ok(n)
+            }
+        }
+    }
+
+    let log = Log::default();
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .extension({
+            let log = log.clone();
+            move || Audit(log.clone())
+        })
+        .finish();
+
+    let resp = QueryBuilder::new("query ($n: Int!) { a: value(n: $n) @include(if: true) }")
+        .variables(Variables::parse_from_json(serde_json::json!({ "n": 1 })).unwrap())
+        .execute(&schema)
+        .await
+        .unwrap();
+    assert_eq!(resp.data, serde_json::json!({ "a": 1 }));
+    assert!(schema.execute("{ value(n: -1) }").await.is_err());
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            r#"a alias=Some("a") args=[n=1] directives=[include] ok=1"#.to_string(),
+            "value alias=None args=[n=-1] directives=[] err=Query error: Failed to resolve field: negative".to_string(),
+        ]
+    );
+}
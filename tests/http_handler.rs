@@ -0,0 +1,306 @@
+use async_graphql::http::{handle_request, ResponseBody};
+use async_graphql::*;
+use futures::io::Cursor;
+use futures::StreamExt;
+
+struct QueryRoot;
+
+#[Object(cache_control(max_age = 60))]
+impl QueryRoot {
+    async fn add(&self, a: i32, b: i32) -> i32 {
+        a + b
+    }
+
+    async fn value(&self) -> Deferred<i32> {
+        10.into()
+    }
+
+    async fn token(&self, ctx: &Context<'_>) -> Option<String> {
+        ctx.data_opt::<String>().cloned()
+    }
+}
+
+struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn action(&self) -> bool {
+        true
+    }
+}
+
+fn request(
+    method: &str,
+    uri: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> ::http::Request<Cursor<Vec<u8>>> {
+    let mut builder = ::http::Request::builder().method(method).uri(uri);
+    for (name, value) in headers {
+        builder = builder.header(*name, *value);
+    }
+    builder.body(Cursor::new(body.as_bytes().to_vec())).unwrap()
+}
+
+async fn body_string(body: ResponseBody) -> String {
+    let data = body.collect::<Vec<_>>().await.concat();
+    String::from_utf8(data).unwrap()
+}
+
+async fn body_json(body: ResponseBody) -> serde_json::Value {
+    serde_json::from_str(&body_string(body).await).unwrap()
+}
+
+#[async_std::test]
+pub async fn test_handle_request() {
+    let schema = Schema::new(QueryRoot, MutationRoot, EmptySubscription);
+
+    let resp = handle_request(
+        &schema,
+        request(
+            "POST",
+            "/",
+            &[("content-type", "application/json")],
+            r#"{"query": "{ add(a: 10, b: 20) token }"}"#,
+        ),
+        |builder| builder.data("abc".to_string()),
+    )
+    .await;
+    assert_eq!(resp.status(), ::http::StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "application/json");
+    assert_eq!(resp.headers()["cache-control"], "max-age=60");
+    assert_eq!(
+        body_json(resp.into_body()).await,
+        serde_json::json!({"data": {"add": 30, "token": "abc"}})
+    );
+
+    let resp = handle_request(
+        &schema,
+        request(
+            "POST",
+            "/",
+            &[("content-type", "application/graphql")],
+            "{ add(a: 1, b: 2) }",
+        ),
+        |builder| builder,
+    )
+    .await;
+    assert_eq!(
+        body_json(resp.into_body()).await,
+        serde_json::json!({"data": {"add": 3}})
+    );
+
+    let resp = handle_request(
+        &schema,
+        request(
+            "POST",
+            "/",
+            &[("accept", "application/graphql-response+json")],
+            r#"{"query": "{ add }"}"#,
+        ),
+        |builder| builder,
+    )
+    .await;
+    assert_eq!(resp.status(), ::http::StatusCode::BAD_REQUEST);
+    assert_eq!(
+        resp.headers()["content-type"],
+        "application/graphql-response+json"
+    );
+
+    let resp = handle_request(&schema, request("POST", "/", &[], "{"), |builder| builder).await;
+    assert_eq!(resp.status(), ::http::StatusCode::BAD_REQUEST);
+
+    let resp = handle_request(&schema, request("PUT", "/", &[], ""), |builder| builder).await;
+    assert_eq!(resp.status(), ::http::StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(resp.headers()["allow"], "GET, POST");
+}
+
+#[async_std::test]
+pub async fn test_handle_get_request() {
+    let schema = Schema::new(QueryRoot, MutationRoot, EmptySubscription);
+
+    let resp = handle_request(
+        &schema,
+        request(
+            "GET",
+            "/?query=query%28%24a%3A%20Int%21%29%20%7B%20add%28a%3A%20%24a%2C%20b%3A%202%29%20%7D&variables=%7B%22a%22%3A%2010%7D",
+            &[],
+            "",
+        ),
+        |builder| builder,
+    )
+    .await;
+    assert_eq!(resp.status(), ::http::StatusCode::OK);
+    assert_eq!(
+        body_json(resp.into_body()).await,
+        serde_json::json!({"data": {"add": 12}})
+    );
+
+    let resp = handle_request(
+        &schema,
+        request("GET", "/?query=mutation%20%7B%20action%20%7D", &[], ""),
+        |builder| builder,
+    )
+    .await;
+    assert_eq!(resp.status(), ::http::StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(resp.headers()["allow"], "POST");
+
+    for variables in &["%7B%22a%22%3A", "%5B10%5D", "10"] {
+        let resp = handle_request(
+            &schema,
+            request(
+                "GET",
+                &format!(
+                    "/?query=query%28%24a%3A%20Int%21%29%20%7B%20add%28a%3A%20%24a%2C%20b%3A%202%29%20%7D&variables={}",
+                    variables
+                ),
+                &[],
+                "",
+            ),
+            |builder| builder,
+        )
+        .await;
+        assert_eq!(resp.status(), ::http::StatusCode::BAD_REQUEST);
+    }
+}
+
+#[async_std::test]
+pub async fn test_handle_batch_request() {
+    let schema = Schema::new(QueryRoot, MutationRoot, EmptySubscription);
+
+    let resp = handle_request(
+        &schema,
+        request(
+            "POST",
+            "/",
+            &[("content-type", "application/json")],
+            r#"[{"query": "{ add(a: 1, b: 2) }"}, {"query": "mutation { action }"}]"#,
+        ),
+        |builder| builder,
+    )
+    .await;
+    assert_eq!(resp.status(), ::http::StatusCode::OK);
+    // The mutation is not cacheable, so neither is the batch.
+    assert!(resp.headers().get("cache-control").is_none());
+    assert_eq!(
+        body_json(resp.into_body()).await,
+        serde_json::json!([
+            {"data": {"add": 3}},
+            {"data": {"action": true}},
+        ])
+    );
+
+    let resp = handle_request(
+        &schema,
+        request(
+            "POST",
+            "/",
+            &[("content-type", "application/json")],
+            r#" [{"query": "{ add(a: 1, b: 2) }"}]"#,
+        ),
+        |builder| builder,
+    )
+    .await;
+    assert_eq!(resp.status(), ::http::StatusCode::OK);
+    assert_eq!(
+        body_json(resp.into_body()).await,
+        serde_json::json!([{"data": {"add": 3}}])
+    );
+
+    let resp = handle_request(
+        &schema,
+        request(
+            "POST",
+            "/",
+            &[("content-type", "application/json")],
+            r#"{"query": "{ add(a: 1, b: 2) }", "variables": [1]}"#,
+        ),
+        |builder| builder,
+    )
+    .await;
+    assert_eq!(resp.status(), ::http::StatusCode::BAD_REQUEST);
+
+    let resp = handle_request(
+        &schema,
+        request("POST", "/", &[("content-type", "application/json")], "[]"),
+        |builder| builder,
+    )
+    .await;
+    assert_eq!(resp.status(), ::http::StatusCode::BAD_REQUEST);
+}
+
+#[async_std::test]
+pub async fn test_handle_stream_request() {
+    let schema = Schema::new(QueryRoot, MutationRoot, EmptySubscription);
+
+    let resp = handle_request(
+        &schema,
+        request(
+            "POST",
+            "/",
+            &[(
+                "accept",
+                "multipart/mixed; deferSpec=20220824, application/json",
+            )],
+            r#"{"query": "{ add(a: 1, b: 2) value @defer }"}"#,
+        ),
+        |builder| builder,
+    )
+    .await;
+    assert_eq!(resp.status(), ::http::StatusCode::OK);
+    assert_eq!(
+        resp.headers()["content-type"],
+        "multipart/mixed; boundary=\"-\"; deferSpec=20220824"
+    );
+    let body = body_string(resp.into_body()).await;
+    assert!(body.contains(r#"{"data":{"add":3,"value":null},"hasNext":true}"#));
+    assert!(body.contains(
+        r#"{"incremental":[{"data":{"value":10},"path":[],"label":"value"}],"hasNext":true}"#
+    ));
+    assert!(body.contains(r#"{"hasNext":false}"#));
+}
+
+#[async_std::test]
+pub async fn test_handle_request_with_multiple_accept_headers() {
+    let schema = Schema::new(QueryRoot, MutationRoot, EmptySubscription);
+
+    let resp = handle_request(
+        &schema,
+        request(
+            "POST",
+            "/",
+            &[
+                ("accept", "application/json;q=0.5"),
+                ("accept", "application/graphql-response+json"),
+            ],
+            r#"{"query": "{ add(a: 1, b: 2) }"}"#,
+        ),
+        |builder| builder,
+    )
+    .await;
+    assert_eq!(resp.status(), ::http::StatusCode::OK);
+    assert_eq!(
+        resp.headers()["content-type"],
+        "application/graphql-response+json"
+    );
+
+    let resp = handle_request(
+        &schema,
+        request(
+            "POST",
+            "/",
+            &[
+                ("accept", "application/json"),
+                ("accept", "multipart/mixed"),
+            ],
+            r#"{"query": "{ add(a: 1, b: 2) value @defer }"}"#,
+        ),
+        |builder| builder,
+    )
+    .await;
+    assert_eq!(resp.status(), ::http::StatusCode::OK);
+    assert_eq!(
+        resp.headers()["content-type"],
+        "multipart/mixed; boundary=\"-\""
+    );
+}
@@ -4,6 +4,19 @@ use async_graphql::validators::{
 };
 use async_graphql::*;
 
+/// The span of the argument name at `column` of a single line query.
+fn argument_span(column: usize, name: &str) -> Span {
+    Span {
+        start: Pos { line: 1, column },
+        end: Pos {
+            line: 1,
+            column: column + name.len(),
+        },
+        start_offset: column - 1,
+        end_offset: column - 1 + name.len(),
+    }
+}
+
 #[async_std::test]
 pub async fn test_input_validator_string_min_length() {
     struct QueryRoot;
@@ -69,6 +82,7 @@ pub async fn test_input_validator_string_min_length() {
                             line: 1,
                             column: 17
                         }),
+                        spans: vec!(argument_span(17, "id")),
                         message: field_error_msg
                     })
                 }
@@ -85,6 +99,7 @@ pub async fn test_input_validator_string_min_length() {
                             line: 1,
                             column: 14
                         }),
+                        spans: vec!(argument_span(14, "input")),
                         message: object_error_msg
                     })
                 }
@@ -175,6 +190,7 @@ pub async fn test_input_validator_string_max_length() {
                             line: 1,
                             column: 17
                         }),
+                        spans: vec!(argument_span(17, "id")),
                         message: field_error_msg
                     })
                 }
@@ -191,6 +207,7 @@ pub async fn test_input_validator_string_max_length() {
                             line: 1,
                             column: 14
                         }),
+                        spans: vec!(argument_span(14, "input")),
                         message: object_error_msg
                     })
                 }
@@ -308,6 +325,7 @@ pub async fn test_input_validator_string_email() {
                             line: 1,
                             column: 17
                         }),
+                        spans: vec!(argument_span(17, "email")),
                         message: field_error_msg
                     })
                 }
@@ -325,6 +343,7 @@ pub async fn test_input_validator_string_email() {
                             line: 1,
                             column: 14
                         }),
+                        spans: vec!(argument_span(14, "input")),
                         message: object_error_msg
                     })
                 }
@@ -452,6 +471,7 @@ pub async fn test_input_validator_string_mac() {
                         line: 1,
                         column: 17
                     }),
+                    spans: vec!(argument_span(17, "mac")),
                     message: field_error_msg.clone()
                 })
             }
@@ -469,6 +489,7 @@ pub async fn test_input_validator_string_mac() {
                         line: 1,
                         column: 14
                     }),
+                    spans: vec!(argument_span(14, "input")),
                     message: object_error_msg.clone()
                 })
             }
@@ -485,6 +506,7 @@ pub async fn test_input_validator_string_mac() {
                         line: 1,
                         column: 17
                     }),
+                    spans: vec!(argument_span(17, "mac")),
                     message: field_error_msg
                 })
             }
@@ -502,6 +524,7 @@ pub async fn test_input_validator_string_mac() {
                         line: 1,
                         column: 14
                     }),
+                    spans: vec!(argument_span(14, "input")),
                     message: object_error_msg
                 })
             }
@@ -556,6 +579,7 @@ pub async fn test_input_validator_string_mac() {
                             line: 1,
                             column: 17
                         }),
+                        spans: vec!(argument_span(17, "mac")),
                         message: field_error_msg
                     })
                 }
@@ -573,6 +597,7 @@ pub async fn test_input_validator_string_mac() {
                             line: 1,
                             column: 14
                         }),
+                        spans: vec!(argument_span(14, "input")),
                         message: object_error_msg
                     })
                 }
@@ -611,6 +636,7 @@ pub async fn test_input_validator_string_mac() {
                             line: 1,
                             column: 17
                         }),
+                        spans: vec!(argument_span(17, "mac")),
                         message: field_error_msg
                     })
                 }
@@ -628,6 +654,7 @@ pub async fn test_input_validator_string_mac() {
                             line: 1,
                             column: 14
                         }),
+                        spans: vec!(argument_span(14, "input")),
                         message: object_error_msg
                     })
                 }
@@ -687,6 +714,7 @@ pub async fn test_input_validator_int_range() {
                             line: 1,
                             column: 17
                         }),
+                        spans: vec!(argument_span(17, "id")),
                         message: field_error_msg
                     })
                 }
@@ -703,6 +731,7 @@ pub async fn test_input_validator_int_range() {
                             line: 1,
                             column: 14
                         }),
+                        spans: vec!(argument_span(14, "input")),
                         message: object_error_msg
                     })
                 }
@@ -788,6 +817,7 @@ pub async fn test_input_validator_int_less_than() {
                             line: 1,
                             column: 17
                         }),
+                        spans: vec!(argument_span(17, "id")),
                         message: field_error_msg
                     })
                 }
@@ -804,6 +834,7 @@ pub async fn test_input_validator_int_less_than() {
                             line: 1,
                             column: 14
                         }),
+                        spans: vec!(argument_span(14, "input")),
                         message: object_error_msg
                     })
                 }
@@ -891,6 +922,7 @@ pub async fn test_input_validator_int_greater_than() {
                             line: 1,
                             column: 17
                         }),
+                        spans: vec!(argument_span(17, "id")),
                         message: field_error_msg
                     })
                 }
@@ -907,6 +939,7 @@ pub async fn test_input_validator_int_greater_than() {
                             line: 1,
                             column: 14
                         }),
+                        spans: vec!(argument_span(14, "input")),
                         message: object_error_msg
                     })
                 }
@@ -987,6 +1020,7 @@ pub async fn test_input_validator_int_nonzero() {
                             line: 1,
                             column: 17
                         }),
+                        spans: vec!(argument_span(17, "id")),
                         message: field_error_msg
                     })
                 }
@@ -1003,6 +1037,7 @@ pub async fn test_input_validator_int_nonzero() {
                             line: 1,
                             column: 14
                         }),
+                        spans: vec!(argument_span(14, "input")),
                         message: object_error_msg
                     })
                 }
@@ -1084,6 +1119,7 @@ pub async fn test_input_validator_int_equal() {
                             line: 1,
                             column: 17
                         }),
+                        spans: vec!(argument_span(17, "id")),
                         message: field_error_msg
                     })
                 }
@@ -1100,6 +1136,7 @@ pub async fn test_input_validator_int_equal() {
                             line: 1,
                             column: 14
                         }),
+                        spans: vec!(argument_span(14, "input")),
                         message: object_error_msg
                     })
                 }
@@ -1196,6 +1233,7 @@ pub async fn test_input_validator_list_max_length() {
                             line: 1,
                             column: 17
                         }),
+                        spans: vec!(argument_span(17, "id")),
                         message: field_error_msg
                     })
                 }
@@ -1212,6 +1250,7 @@ pub async fn test_input_validator_list_max_length() {
                             line: 1,
                             column: 14
                         }),
+                        spans: vec!(argument_span(14, "input")),
                         message: object_error_msg
                     })
                 }
@@ -1308,6 +1347,7 @@ pub async fn test_input_validator_list_min_length() {
                             line: 1,
                             column: 17
                         }),
+                        spans: vec!(argument_span(17, "id")),
                         message: field_error_msg
                     })
                 }
@@ -1324,6 +1364,7 @@ pub async fn test_input_validator_list_min_length() {
                             line: 1,
                             column: 14
                         }),
+                        spans: vec!(argument_span(14, "input")),
                         message: object_error_msg
                     })
                 }
@@ -1427,6 +1468,7 @@ pub async fn test_input_validator_operator_or() {
                             line: 1,
                             column: 17
                         }),
+                        spans: vec!(argument_span(17, "id")),
                         message: field_error_msg
                     })
                 }
@@ -1443,6 +1485,7 @@ pub async fn test_input_validator_operator_or() {
                             line: 1,
                             column: 14
                         }),
+                        spans: vec!(argument_span(14, "input")),
                         message: object_error_msg
                     })
                 }
@@ -1540,6 +1583,7 @@ pub async fn test_input_validator_operator_and() {
                             line: 1,
                             column: 17
                         }),
+                        spans: vec!(argument_span(17, "email")),
                         message: field_error_msg
                     })
                 }
@@ -1556,6 +1600,7 @@ pub async fn test_input_validator_operator_and() {
                             line: 1,
                             column: 14
                         }),
+                        spans: vec!(argument_span(14, "input")),
                         message: object_error_msg
                     })
                 }
@@ -0,0 +1,168 @@
+use async_graphql::middleware::{FieldMiddleware, NextFieldResolve};
+use async_graphql::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+struct Uppercase;
+
+#[async_trait::async_trait]
+impl FieldMiddleware for Uppercase {
+    async fn resolve(
+        &self,
+        _ctx: &Context<'_>,
+        next: NextFieldResolve<'_>,
+    ) -> Result<serde_json::Value> {
+        match next.run().await? {
+            serde_json::Value::String(s) => Ok(s.to_uppercase().into()),
+            value => Ok(value),
+        }
+    }
+}
+
+struct Suffix(&'static str);
+
+#[async_trait::async_trait]
+impl FieldMiddleware for Suffix {
+    async fn resolve(
+        &self,
+        _ctx: &Context<'_>,
+        next: NextFieldResolve<'_>,
+    ) -> Result<serde_json::Value> {
+        match next.run().await? {
+            serde_json::Value::String(s) => Ok(format!("{}{}", s, self.0).into()),
+            value => Ok(value),
+        }
+    }
+}
+
+#[async_std::test]
+pub async fn test_field_middleware() {
+    #[SimpleObject]
+    struct MyObj {
+        #[field(middleware = "Uppercase")]
+        name: String,
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        #[field(middleware = "Uppercase", middleware = r#"Suffix("!")"#)]
+        async fn value(&self, s: String) -> String {
+            s
+        }
+
+        async fn plain(&self) -> &str {
+            "abc"
+        }
+
+        async fn obj(&self) -> MyObj {
+            MyObj {
+                name: "def".to_string(),
+            }
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    assert_eq!(
+        schema
+            .execute(r#"{ value(s: "abc") plain obj { name } }"#)
+            .await
+            .unwrap()
+            .data,
+        serde_json::json!({
+            "value": "ABC!",
+            "plain": "abc",
+            "obj": { "name": "DEF" },
+        })
+    );
+}
+
+#[async_std::test]
+pub async fn test_schema_middleware() {
+    struct Counter(Arc<AtomicUsize>);
+
+    #[async_trait::async_trait]
+    impl FieldMiddleware for Counter {
+        async fn resolve(
+            &self,
+            _ctx: &Context<'_>,
+            next: NextFieldResolve<'_>,
+        ) -> Result<serde_json::Value> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            next.run().await
+        }
+    }
+
+    struct ShortCircuit;
+
+    #[async_trait::async_trait]
+    impl FieldMiddleware for ShortCircuit {
+        async fn resolve(
+            &self,
+            ctx: &Context<'_>,
+            next: NextFieldResolve<'_>,
+        ) -> Result<serde_json::Value> {
+            if ctx.name.node == "secret" {
+                Ok("hidden".into())
+            } else {
+                next.run().await
+            }
+        }
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        #[field(middleware = r#"Suffix("?")"#)]
+        async fn value(&self) -> &str {
+            "abc"
+        }
+
+        async fn secret(&self) -> &str {
+            unreachable!()
+        }
+    }
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .field_middleware(Counter(counter.clone()))
+        .field_middleware(ShortCircuit)
+        .field_middleware(Uppercase)
+        .finish();
+    assert_eq!(
+        schema.execute("{ value secret }").await.unwrap().data,
+        serde_json::json!({ "value": "ABC?", "secret": "hidden" })
+    );
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+}
+
+#[async_std::test]
+pub async fn test_field_middleware_created_once() {
+    static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+    fn suffix() -> Suffix {
+        CREATED.fetch_add(1, Ordering::SeqCst);
+        Suffix("!")
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        #[field(middleware = "suffix()")]
+        async fn value(&self) -> &str {
+            "abc"
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    for _ in 0..2 {
+        assert_eq!(
+            schema.execute("{ a: value b: value }").await.unwrap().data,
+            serde_json::json!({ "a": "abc!", "b": "abc!" })
+        );
+    }
+    assert_eq!(CREATED.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,45 @@
+use async_graphql::parser::ParserOptions;
+use async_graphql::*;
+
+#[async_std::test]
+pub async fn test_parser_options() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self) -> i32 {
+            10
+        }
+    }
+
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .parser_options(ParserOptions {
+            max_depth: Some(2),
+            max_aliases: Some(2),
+            ..Default::default()
+        })
+        .finish();
+
+    assert_eq!(
+        schema.execute("{ a: value b: value }").await.unwrap().data,
+        serde_json::json!({ "a": 10, "b": 10 })
+    );
+
+    match schema.execute("{ a: value b: value c: value }").await {
+        Err(Error::Parse(err)) => {
+            assert_eq!(
+                err.message,
+                "The query has too many aliases, the maximum is 2"
+            )
+        }
+        _ => panic!("expected a parse error"),
+    }
+
+    match schema.execute("{ value(a: [[1]]) }").await {
+        Err(Error::Parse(err)) => assert_eq!(
+            err.message,
+            "The query is nested too deeply, the maximum depth is 2"
+        ),
+        _ => panic!("expected a parse error"),
+    }
+}
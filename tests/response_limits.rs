@@ -0,0 +1,158 @@
+use async_graphql::*;
+
+struct Item(i32);
+
+#[Object]
+impl Item {
+    async fn value(&self) -> i32 {
+        self.0
+    }
+
+    async fn name(&self) -> String {
+        format!("item{}", self.0)
+    }
+}
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn items(&self, n: i32) -> Vec<Item> {
+        (0..n).map(Item).collect()
+    }
+
+    async fn numbers(&self, n: i32) -> Vec<i32> {
+        (0..n).collect()
+    }
+
+    async fn text(&self, n: i32) -> String {
+        "a".repeat(n as usize)
+    }
+}
+
+struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn text(&self, n: i32) -> String {
+        "a".repeat(n as usize)
+    }
+
+    async fn numbers(&self, n: i32) -> Vec<i32> {
+        (0..n).collect()
+    }
+}
+
+#[async_std::test]
+pub async fn test_limit_list_items() {
+    let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_list_items(5)
+        .finish();
+
+    assert_eq!(
+        schema
+            .execute("{ items(n: 5) { value } numbers(n: 5) }")
+            .await
+            .unwrap()
+            .data,
+        serde_json::json!({
+            "items": [{ "value": 0 }, { "value": 1 }, { "value": 2 }, { "value": 3 }, { "value": 4 }],
+            "numbers": [0, 1, 2, 3, 4],
+        })
+    );
+
+    assert_eq!(
+        schema
+            .execute("{ items(n: 6) { value } }")
+            .await
+            .unwrap_err(),
+        Error::Query {
+            pos: Pos { line: 1, column: 3 },
+            path: Some(serde_json::json!(["items"])),
+            err: QueryError::TooManyListItems { len: 6, limit: 5 },
+        }
+    );
+    assert_eq!(
+        schema
+            .execute("{ numbers(n: 10) }")
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Query error: List has 10 items, exceeding the limit of 5"
+    );
+}
+
+#[async_std::test]
+pub async fn test_limit_response_size() {
+    let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_response_size(1000)
+        .finish();
+
+    assert_eq!(
+        schema.execute("{ text(n: 100) }").await.unwrap().data,
+        serde_json::json!({ "text": "a".repeat(100) })
+    );
+    assert!(schema
+        .execute("{ items(n: 10) { value name } }")
+        .await
+        .is_ok());
+
+    assert_eq!(
+        schema
+            .execute("{ text(n: 1000) }")
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Query error: Response size exceeds the limit of 1000 bytes"
+    );
+
+    match schema
+        .execute("{ items(n: 100) { value name } }")
+        .await
+        .unwrap_err()
+    {
+        Error::Query { path, err, .. } => {
+            assert_eq!(err, QueryError::ResponseTooLarge { limit: 1000 });
+            // Aborted while resolving the items.
+            assert_eq!(path.unwrap()[0], serde_json::json!("items"));
+        }
+        _ => panic!("unexpected error"),
+    }
+
+    // The limit applies to each query separately.
+    assert!(schema.execute("{ text(n: 900) }").await.is_ok());
+    assert!(schema.execute("{ text(n: 900) }").await.is_ok());
+}
+
+#[async_std::test]
+pub async fn test_limit_response_size_of_mutations() {
+    let schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .limit_response_size(1000)
+        .finish();
+
+    assert!(schema
+        .execute("mutation { text(n: 500) numbers(n: 100) }")
+        .await
+        .is_ok());
+
+    match schema
+        .execute("mutation { a: text(n: 500) b: text(n: 500) }")
+        .await
+        .unwrap_err()
+    {
+        Error::Query { path, err, .. } => {
+            assert_eq!(err, QueryError::ResponseTooLarge { limit: 1000 });
+            assert_eq!(path, Some(serde_json::json!(["b"])));
+        }
+        _ => panic!("unexpected error"),
+    }
+
+    assert_eq!(
+        schema
+            .execute("mutation { numbers(n: 300) }")
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Query error: Response size exceeds the limit of 1000 bytes"
+    );
+}
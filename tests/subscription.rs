@@ -180,7 +180,7 @@ pub async fn test_subscription_with_ctx_data() {
     {
         let mut stream = schema
             .create_subscription_stream(
-                "subscription { values objects { value } }",
+                "subscription { values }",
                 None,
                 Default::default(),
                 Some(Arc::new({
@@ -195,6 +195,23 @@ pub async fn test_subscription_with_ctx_data() {
             Some(Ok(serde_json::json!({ "values": 100 }))),
             stream.next().await
         );
+        assert!(stream.next().await.is_none());
+    }
+
+    {
+        let mut stream = schema
+            .create_subscription_stream(
+                "subscription { objects { value } }",
+                None,
+                Default::default(),
+                Some(Arc::new({
+                    let mut data = Data::default();
+                    data.insert(100i32);
+                    data
+                })),
+            )
+            .await
+            .unwrap();
         assert_eq!(
             Some(Ok(serde_json::json!({ "objects": { "value": 100 } }))),
             stream.next().await
@@ -0,0 +1,139 @@
+use async_graphql::*;
+use async_std::task;
+use futures::StreamExt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+struct Finished(Arc<AtomicBool>);
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn fast(&self) -> i32 {
+        1
+    }
+
+    async fn slow(&self, ctx: &Context<'_>) -> i32 {
+        task::sleep(Duration::from_millis(300)).await;
+        ctx.data::<Finished>().0.store(true, Ordering::SeqCst);
+        2
+    }
+
+    #[field(timeout = "50ms")]
+    async fn limited(&self) -> i32 {
+        task::sleep(Duration::from_millis(300)).await;
+        3
+    }
+
+    async fn deferred(&self) -> Deferred<SlowObj> {
+        SlowObj.into()
+    }
+}
+
+struct SlowObj;
+
+#[Object]
+impl SlowObj {
+    async fn value(&self) -> i32 {
+        task::sleep(Duration::from_millis(300)).await;
+        4
+    }
+}
+
+fn schema() -> Schema<QueryRoot, EmptyMutation, EmptySubscription> {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .query_timeout(Duration::from_millis(100))
+        .finish()
+}
+
+#[async_std::test]
+pub async fn test_field_timeout() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    let err = schema.execute("{ fast limited }").await.unwrap_err();
+    match err {
+        Error::Query { path, err, .. } => {
+            assert_eq!(path, Some(serde_json::json!(["limited"])));
+            assert_eq!(
+                err,
+                QueryError::FieldError {
+                    err: "Field timed out".to_string(),
+                    extended_error: None,
+                }
+            );
+        }
+        _ => panic!("unexpected error"),
+    }
+}
+
+#[async_std::test]
+pub async fn test_deferred_field_timeout() {
+    let mut stream = QueryBuilder::new("{ fast deferred @defer { value } }")
+        .execute_stream(&schema())
+        .await
+        .into_stream();
+    stream.next().await.unwrap().unwrap();
+    match stream.next().await.unwrap().unwrap_err() {
+        Error::Incremental { label, path, err } => {
+            assert_eq!(label.as_deref(), Some("deferred"));
+            assert_eq!(path, vec![serde_json::json!("deferred")]);
+            match *err {
+                Error::Query { pos, path, err } => {
+                    assert_eq!((pos.line, pos.column), (1, 8));
+                    assert_eq!(path, Some(serde_json::json!(["deferred"])));
+                    assert_eq!(
+                        err,
+                        QueryError::FieldError {
+                            err: "Field timed out".to_string(),
+                            extended_error: None,
+                        }
+                    );
+                }
+                _ => panic!("unexpected error"),
+            }
+        }
+        _ => panic!("unexpected error"),
+    }
+}
+
+#[async_std::test]
+pub async fn test_query_timeout() {
+    let finished = Arc::new(AtomicBool::new(false));
+    let err = QueryBuilder::new("{ fast slow }")
+        .data(Finished(finished.clone()))
+        .execute(&schema())
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Query error: Query timed out");
+
+    // The pending resolver has been dropped.
+    task::sleep(Duration::from_millis(400)).await;
+    assert!(!finished.load(Ordering::SeqCst));
+
+    let finished = Arc::new(AtomicBool::new(false));
+    let resp = QueryBuilder::new("{ fast slow }")
+        .data(Finished(finished.clone()))
+        .timeout(Duration::from_secs(5))
+        .execute(&schema())
+        .await
+        .unwrap();
+    assert_eq!(resp.data, serde_json::json!({ "fast": 1, "slow": 2 }));
+    assert!(finished.load(Ordering::SeqCst));
+}
+
+#[async_std::test]
+pub async fn test_deferred_timeout() {
+    let mut stream = QueryBuilder::new("{ fast deferred @defer { value } }")
+        .execute_stream(&schema())
+        .await
+        .into_stream();
+    assert_eq!(
+        stream.next().await.unwrap().unwrap().data,
+        serde_json::json!({ "fast": 1, "deferred": null })
+    );
+    assert_eq!(
+        stream.next().await.unwrap().unwrap_err().to_string(),
+        "Query error: Failed to resolve field: Field timed out"
+    );
+}
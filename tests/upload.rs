@@ -0,0 +1,219 @@
+use async_graphql::*;
+use futures::io::Cursor;
+use futures::AsyncReadExt;
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {}
+
+struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn upload(&self, file: Upload) -> String {
+        let in_memory = file.is_in_memory();
+        let mut content = String::new();
+        file.into_async_read()
+            .read_to_string(&mut content)
+            .await
+            .unwrap();
+        format!("{}:{}", in_memory, content)
+    }
+
+    async fn upload_avatar(&self, avatar: Upload, files: Vec<Upload>) -> i32 {
+        let _ = avatar;
+        files.len() as i32
+    }
+}
+
+/// Build a multipart request, the files are `(name, content_type, content)`.
+fn multipart_body(operations: &str, map: &str, files: &[(&str, &str, &str)]) -> String {
+    let mut parts = vec![
+        "--xxx".to_string(),
+        "Content-Disposition: form-data; name=\"operations\"".to_string(),
+        "".to_string(),
+        operations.to_string(),
+        "--xxx".to_string(),
+        "Content-Disposition: form-data; name=\"map\"".to_string(),
+        "".to_string(),
+        map.to_string(),
+    ];
+    for (name, content_type, content) in files {
+        parts.push("--xxx".to_string());
+        parts.push(format!(
+            "Content-Disposition: form-data; name=\"{}\"; filename=\"{}.txt\"",
+            name, name
+        ));
+        parts.push(format!("Content-Type: {}", content_type));
+        parts.push("".to_string());
+        parts.push(content.to_string());
+    }
+    parts.push("--xxx--".to_string());
+    parts.push("".to_string());
+    parts.join("\r\n")
+}
+
+async fn parse(
+    body: String,
+    opts: &IntoQueryBuilderOpts,
+) -> std::result::Result<QueryBuilder, ParseRequestError> {
+    (
+        Some("multipart/form-data; boundary=xxx"),
+        Cursor::new(body.into_bytes()),
+    )
+        .into_query_builder_opts(opts)
+        .await
+}
+
+async fn upload(content: &str, opts: IntoQueryBuilderOpts) -> serde_json::Value {
+    let schema = Schema::new(QueryRoot, MutationRoot, EmptySubscription);
+    let body = multipart_body(
+        r#"{ "query": "mutation ($file: Upload!) { upload(file: $file) }", "variables": { "file": null } }"#,
+        r#"{ "0": ["variables.file"] }"#,
+        &[("0", "text/plain", content)],
+    );
+    let builder = parse(body, &opts).await.unwrap();
+    builder.execute(&schema).await.unwrap().data
+}
+
+fn upload_avatar_body(files: &[(&str, &str, &str)]) -> String {
+    multipart_body(
+        r#"{ "query": "mutation ($avatar: Upload!, $files: [Upload!]!) { uploadAvatar(avatar: $avatar, files: $files) }", "variables": { "avatar": null, "files": [null, null] } }"#,
+        r#"{ "0": ["variables.avatar"], "1": ["variables.files.0"], "2": ["variables.files.1"] }"#,
+        files,
+    )
+}
+
+#[async_std::test]
+pub async fn test_upload_storage() {
+    assert_eq!(
+        upload("test", Default::default()).await,
+        serde_json::json!({"upload": "false:test"})
+    );
+
+    let opts = IntoQueryBuilderOpts {
+        max_in_memory_file_size: Some(4),
+        ..Default::default()
+    };
+    assert_eq!(
+        upload("test", opts.clone()).await,
+        serde_json::json!({"upload": "true:test"})
+    );
+    assert_eq!(
+        upload("test!", opts).await,
+        serde_json::json!({"upload": "false:test!"})
+    );
+}
+
+#[async_std::test]
+pub async fn test_upload_constraints() {
+    let files = [
+        ("0", "image/png", "avatar"),
+        ("1", "text/plain", "file1"),
+        ("2", "text/plain", "file2"),
+    ];
+
+    let schema = Schema::new(QueryRoot, MutationRoot, EmptySubscription);
+    let builder = parse(upload_avatar_body(&files), &Default::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        builder.execute(&schema).await.unwrap().data,
+        serde_json::json!({"uploadAvatar": 2})
+    );
+
+    let opts = IntoQueryBuilderOpts {
+        max_num_files: Some(2),
+        ..Default::default()
+    };
+    assert!(matches!(
+        parse(upload_avatar_body(&files), &opts).await,
+        Err(ParseRequestError::TooManyFiles)
+    ));
+
+    let opts = IntoQueryBuilderOpts {
+        max_file_size: Some(5),
+        ..Default::default()
+    };
+    assert!(matches!(
+        parse(upload_avatar_body(&files), &opts).await,
+        Err(ParseRequestError::PayloadTooLarge)
+    ));
+
+    let opts = IntoQueryBuilderOpts {
+        max_request_size: Some(100),
+        ..Default::default()
+    };
+    assert!(matches!(
+        parse(upload_avatar_body(&files), &opts).await,
+        Err(ParseRequestError::RequestTooLarge)
+    ));
+
+    let opts = IntoQueryBuilderOpts {
+        allowed_content_types: Some(vec!["image/*".to_string()]),
+        ..Default::default()
+    };
+    match parse(upload_avatar_body(&files), &opts).await {
+        Err(ParseRequestError::ContentTypeNotAllowed(content_type)) => {
+            assert_eq!(content_type, "text/plain")
+        }
+        _ => panic!("expected ContentTypeNotAllowed"),
+    }
+
+    let opts = IntoQueryBuilderOpts {
+        variable_constraints: vec![(
+            "variables.avatar".to_string(),
+            UploadConstraints {
+                allowed_content_types: Some(vec!["image/png".to_string()]),
+                ..Default::default()
+            },
+        )]
+        .into_iter()
+        .collect(),
+        ..Default::default()
+    };
+    assert!(parse(upload_avatar_body(&files), &opts).await.is_ok());
+
+    let opts = IntoQueryBuilderOpts {
+        variable_constraints: vec![(
+            "variables.files".to_string(),
+            UploadConstraints {
+                allowed_content_types: Some(vec!["image/*".to_string()]),
+                ..Default::default()
+            },
+        )]
+        .into_iter()
+        .collect(),
+        ..Default::default()
+    };
+    match parse(upload_avatar_body(&files), &opts).await {
+        Err(ParseRequestError::VariableContentTypeNotAllowed {
+            var_path,
+            content_type,
+        }) => {
+            assert_eq!(var_path, "variables.files.0");
+            assert_eq!(content_type, "text/plain");
+        }
+        _ => panic!("expected VariableContentTypeNotAllowed"),
+    }
+
+    let opts = IntoQueryBuilderOpts {
+        variable_constraints: vec![(
+            "variables.avatar".to_string(),
+            UploadConstraints {
+                max_file_size: Some(3),
+                ..Default::default()
+            },
+        )]
+        .into_iter()
+        .collect(),
+        ..Default::default()
+    };
+    match parse(upload_avatar_body(&files), &opts).await {
+        Err(ParseRequestError::VariableFileTooLarge { var_path }) => {
+            assert_eq!(var_path, "variables.avatar")
+        }
+        _ => panic!("expected VariableFileTooLarge"),
+    }
+}
@@ -0,0 +1,202 @@
+use async_graphql::parser::query::Field;
+use async_graphql::*;
+
+#[derive(Clone)]
+struct NoDeprecatedFields;
+
+impl<'a> Visitor<'a> for NoDeprecatedFields {
+    fn enter_field(&mut self, ctx: &mut VisitorContext<'a>, field: &'a Positioned<Field>) {
+        let deprecation = ctx
+            .parent_type_name()
+            .and_then(|ty| ctx.field_deprecation(ty, &field.name));
+        if let Some(reason) = deprecation {
+            ctx.report_error(
+                vec![field.name.span()],
+                format!("The field \"{}\" is deprecated: {}", field.name, reason),
+            );
+        }
+    }
+}
+
+/// Rejects the introspection queries.
+#[derive(Clone)]
+struct NoIntrospection;
+
+impl<'a> Visitor<'a> for NoIntrospection {
+    fn enter_field(&mut self, ctx: &mut VisitorContext<'a>, field: &'a Positioned<Field>) {
+        if ctx.current_type_name() == Some("__Schema") {
+            ctx.report_error(
+                vec![field.span()],
+                format!(
+                    "The field \"{}\" of type \"{}\" is not allowed",
+                    field.name,
+                    ctx.parent_type_name().unwrap_or_default()
+                ),
+            );
+        }
+    }
+}
+
+/// Counts the fields of the whole document, to check that each document gets a new rule.
+#[derive(Clone, Default)]
+struct MaxFields {
+    max: usize,
+    count: usize,
+}
+
+impl<'a> Visitor<'a> for MaxFields {
+    fn enter_field(&mut self, ctx: &mut VisitorContext<'a>, field: &'a Positioned<Field>) {
+        self.count += 1;
+        if self.count == self.max + 1 {
+            ctx.report_error(
+                vec![field.span()],
+                format!("The query selects more than {} fields", self.max),
+            );
+        }
+    }
+}
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn value(&self) -> i32 {
+        10
+    }
+
+    #[field(deprecation = "Use value")]
+    async fn old_value(&self) -> i32 {
+        10
+    }
+}
+
+#[async_std::test]
+pub async fn test_validation_rule() {
+    for mode in &[ValidationMode::Strict, ValidationMode::Fast] {
+        let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+            .validation_mode(*mode)
+            .validation_rule(NoDeprecatedFields)
+            .validation_rule(MaxFields {
+                max: 2,
+                ..Default::default()
+            })
+            .finish();
+
+        for _ in 0..2 {
+            assert_eq!(
+                schema.execute("{ a: value b: value }").await.unwrap().data,
+                serde_json::json!({ "a": 10, "b": 10 })
+            );
+        }
+
+        match schema.execute("{ value\n  oldValue }").await {
+            Err(Error::Rule { errors }) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(
+                    errors[0].message,
+                    "The field \"oldValue\" is deprecated: Use value"
+                );
+                assert_eq!(errors[0].locations, vec![Pos { line: 2, column: 3 }]);
+                assert_eq!(
+                    errors[0].spans[0].end,
+                    Pos {
+                        line: 2,
+                        column: 11
+                    }
+                );
+            }
+            _ => panic!("expected a rule error"),
+        }
+
+        match schema
+            .execute("{ a: value b: value c: value d: value }")
+            .await
+        {
+            Err(Error::Rule { errors }) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(errors[0].message, "The query selects more than 2 fields");
+                assert_eq!(
+                    errors[0].locations,
+                    vec![Pos {
+                        line: 1,
+                        column: 21
+                    }]
+                );
+            }
+            _ => panic!("expected a rule error"),
+        }
+    }
+}
+
+#[async_std::test]
+pub async fn test_validation_rule_with_builtin_errors() {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .validation_rule(NoDeprecatedFields)
+        .finish();
+
+    match schema.execute("{ oldValue unknown }").await {
+        Err(Error::Rule { errors }) => {
+            assert_eq!(
+                errors
+                    .iter()
+                    .map(|err| err.message.as_str())
+                    .collect::<Vec<_>>(),
+                vec![
+                    "Unknown field \"unknown\" on type \"Query\".",
+                    "The field \"oldValue\" is deprecated: Use value"
+                ]
+            );
+        }
+        _ => panic!("expected a rule error"),
+    }
+}
+
+#[async_std::test]
+pub async fn test_validation_rule_with_types() {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .validation_rule(NoIntrospection)
+        .finish();
+
+    assert!(schema.execute("{ value __typename }").await.is_ok());
+    match schema
+        .execute("{ value __schema { queryType { name } } }")
+        .await
+    {
+        Err(Error::Rule { errors }) => {
+            assert_eq!(errors.len(), 1);
+            assert_eq!(
+                errors[0].message,
+                "The field \"__schema\" of type \"Query\" is not allowed"
+            );
+            assert_eq!(errors[0].locations, vec![Pos { line: 1, column: 9 }]);
+        }
+        _ => panic!("expected a rule error"),
+    }
+}
+
+#[async_std::test]
+pub async fn test_type_system_definitions() {
+    for mode in &[ValidationMode::Strict, ValidationMode::Fast] {
+        let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+            .validation_mode(*mode)
+            .finish();
+
+        match schema.execute("{ value } type Foo { b: Int }").await {
+            Err(Error::Rule { errors }) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(
+                    errors[0].message,
+                    "The \"Foo\" definition is not executable."
+                );
+                assert_eq!(
+                    errors[0].locations,
+                    vec![Pos {
+                        line: 1,
+                        column: 11
+                    }]
+                );
+            }
+            _ => panic!("expected a rule error"),
+        }
+    }
+}